@@ -20,6 +20,7 @@ use crate::{
     editor::EditType,
     indent::{auto_detect_indent_style, IndentStyle},
     line_ending::{LineEnding, LineEndingDetermination},
+    mark::Marks,
     mode::Mode,
     selection::Selection,
     word::WordCursor,
@@ -63,6 +64,13 @@ struct Revision {
     cursor_after: Option<CursorMode>,
 }
 
+/// The size in bytes above which a text is a large file, for which the passes over the whole
+/// text, like normalizing its line endings, are skipped.
+pub const LARGE_FILE_THRESHOLD: usize = 16 * 1024 * 1024;
+
+/// The number of bytes at the start of a large file that its line ending is determined from.
+const LARGE_FILE_SAMPLE: usize = 64 * 1024;
+
 #[derive(Debug, Clone)]
 pub struct InvalLines {
     pub start_line: usize,
@@ -91,6 +99,9 @@ pub struct Buffer {
 
     indent_style: IndentStyle,
     line_ending: LineEnding,
+    large_file: bool,
+
+    marks: Marks,
 }
 
 impl Display for Buffer {
@@ -102,13 +113,14 @@ impl Display for Buffer {
 impl Buffer {
     pub fn new(text: impl Into<Rope>) -> Self {
         let text = text.into();
+        let large_file = text.len() > LARGE_FILE_THRESHOLD;
 
         // Determine the line ending of the text and adjust it if necessary
-        let line_ending = LineEndingDetermination::determine(&text);
+        let line_ending = determine_line_ending(&text, large_file);
         let line_ending = line_ending.unwrap_or(LineEnding::Lf);
 
         // Get rid of lone Cr's as Rope does not treat them as line endings
-        let text = line_ending.normalize_limited(&text);
+        let text = normalize_line_endings(line_ending, &text, large_file);
 
         let len = text.len();
         Self {
@@ -140,6 +152,9 @@ impl Buffer {
             last_edit_type: EditType::Other,
             indent_style: IndentStyle::DEFAULT_INDENT,
             line_ending,
+            large_file,
+
+            marks: Marks::default(),
         }
     }
 
@@ -195,10 +210,11 @@ impl Buffer {
 
     pub fn init_content(&mut self, content: Rope) {
         if !content.is_empty() {
-            let line_ending = LineEndingDetermination::determine(&content);
+            self.large_file = content.len() > LARGE_FILE_THRESHOLD;
+            let line_ending = determine_line_ending(&content, self.large_file);
             self.line_ending = line_ending.unwrap_or(self.line_ending);
 
-            let content = self.line_ending.normalize_limited(&content);
+            let content = normalize_line_endings(self.line_ending, &content, self.large_file);
 
             let delta = Delta::simple_edit(Interval::new(0, 0), content, 0);
             let (new_rev, new_text, new_tombstones, new_deletes_from_union) =
@@ -216,10 +232,11 @@ impl Buffer {
 
     pub fn reload(&mut self, content: Rope, set_pristine: bool) -> (Rope, RopeDelta, InvalLines) {
         // Determine the line ending of the new text
-        let line_ending = LineEndingDetermination::determine(&content);
+        self.large_file = content.len() > LARGE_FILE_THRESHOLD;
+        let line_ending = determine_line_ending(&content, self.large_file);
         self.line_ending = line_ending.unwrap_or(self.line_ending);
 
-        let content = self.line_ending.normalize_limited(&content);
+        let content = normalize_line_endings(self.line_ending, &content, self.large_file);
 
         let len = self.text.len();
         let delta = Delta::simple_edit(Interval::new(0, len), content, len);
@@ -231,8 +248,56 @@ impl Buffer {
         (text, delta, inval_lines)
     }
 
+    /// Appends content to the end of the buffer, like the next part of a file that is loaded
+    /// incrementally. Like [`Buffer::init_content`], this can't be undone, and it doesn't change
+    /// whether the buffer is pristine.
+    ///
+    /// The line ending is determined from the first content appended to an empty buffer, and the
+    /// content isn't normalized, as a line ending can be split between two parts.
+    pub fn append_content(&mut self, content: Rope) -> (Rope, RopeDelta, InvalLines) {
+        let text = self.text.clone();
+        if text.is_empty() {
+            let line_ending = determine_line_ending(&content, self.large_file);
+            self.line_ending = line_ending.unwrap_or(self.line_ending);
+        }
+
+        let pristine = self.is_pristine();
+        let len = text.len();
+        let delta = Delta::simple_edit(Interval::new(len, len), content, len);
+        let (new_rev, new_text, new_tombstones, new_deletes_from_union) =
+            self.mk_new_rev(0, delta.clone());
+        let inval_lines = self.apply_edit(
+            &delta,
+            new_rev,
+            new_text,
+            new_tombstones,
+            new_deletes_from_union,
+        );
+        if pristine {
+            self.set_pristine();
+        }
+
+        (text, delta, inval_lines)
+    }
+
+    /// Detects the indent style of the text, or uses the default for a large file.
     pub fn detect_indent(&mut self, default: impl FnOnce() -> IndentStyle) {
-        self.indent_style = auto_detect_indent_style(&self.text).unwrap_or_else(default);
+        self.indent_style = if self.large_file {
+            default()
+        } else {
+            auto_detect_indent_style(&self.text).unwrap_or_else(default)
+        };
+    }
+
+    /// Whether the buffer holds a large file, which is decided by the size of its content when
+    /// it's created, initialized or reloaded. See [`LARGE_FILE_THRESHOLD`].
+    pub fn is_large_file(&self) -> bool {
+        self.large_file
+    }
+
+    /// Sets whether the buffer holds a large file, like before loading a file whose size is known.
+    pub fn set_large_file(&mut self, large_file: bool) {
+        self.large_file = large_file;
     }
 
     pub fn indent_style(&self) -> IndentStyle {
@@ -252,6 +317,15 @@ impl Buffer {
         self.line_ending = line_ending;
     }
 
+    /// The marks of the buffer, which move with the text around them as the buffer is edited.
+    pub fn marks(&self) -> &Marks {
+        &self.marks
+    }
+
+    pub fn marks_mut(&mut self) -> &mut Marks {
+        &mut self.marks
+    }
+
     pub fn reset_edit_type(&mut self) {
         self.last_edit_type = EditType::Other;
     }
@@ -299,6 +373,68 @@ impl Buffer {
         self.add_delta(delta)
     }
 
+    /// Applies a delta that was made against the text of the revision `base_rev`, like an edit
+    /// from a background formatter or from another peer, rebasing it over the edits made since
+    /// then. Text that the delta inserts where a later edit inserted text goes after it.
+    ///
+    /// Returns the text before the edit and the rebased delta, which applies to that text, or
+    /// `None` when the revision isn't known or the delta doesn't apply to its text.
+    ///
+    /// Note: the delta's line-endings should be normalized.
+    pub fn apply_delta_at_rev(
+        &mut self,
+        base_rev: u64,
+        delta: RopeDelta,
+        edit_type: EditType,
+    ) -> Option<(Rope, RopeDelta, InvalLines)> {
+        let base_index = self.find_rev(base_rev)?;
+        if delta.base_len
+            != self
+                .deletes_from_union_for_index(base_index)
+                .len_after_delete()
+        {
+            return None;
+        }
+
+        let text = self.text.clone();
+
+        self.this_edit_type = edit_type;
+        let undo_group = self.calculate_undo_group();
+        self.last_edit_type = self.this_edit_type;
+
+        let (new_rev, new_text, new_tombstones, new_deletes_from_union) =
+            self.mk_new_rev_at(base_index, undo_group, delta);
+
+        // The delta from the current text to the new text, through the tombstones of the
+        // current text in the new union string.
+        let new_inserts = match &new_rev.edit {
+            Contents::Edit { inserts, .. } => inserts.clone(),
+            Contents::Undo { .. } => unreachable!(),
+        };
+        let old_deletes_from_union = self.deletes_from_union.transform_union(&new_inserts);
+        let old_tombstones = shuffle_tombstones(
+            &new_text,
+            &new_tombstones,
+            &new_deletes_from_union,
+            &old_deletes_from_union,
+        );
+        let delta = Delta::synthesize(
+            &old_tombstones,
+            &old_deletes_from_union,
+            &new_deletes_from_union,
+        );
+
+        let inval_lines = self.apply_edit(
+            &delta,
+            new_rev,
+            new_text,
+            new_tombstones,
+            new_deletes_from_union,
+        );
+
+        Some((text, delta, inval_lines))
+    }
+
     pub fn normalize_line_endings(&mut self) -> Option<(Rope, RopeDelta, InvalLines)> {
         let Some(delta) = self.line_ending.normalize_delta(&self.text) else {
             // There were no changes needed
@@ -348,6 +484,7 @@ impl Buffer {
         self.text = new_text;
         self.tombstones = new_tombstones;
         self.deletes_from_union = new_deletes_from_union;
+        self.marks.apply_delta(delta);
 
         let logical_start_line = self.text.line_of_offset(iv.start);
         let new_logical_end_line = self.text.line_of_offset(iv.start + newlen) + 1;
@@ -380,12 +517,34 @@ impl Buffer {
 
     /// Returns `(Revision, new text, new tombstones, new deletes from union)`
     fn mk_new_rev(&self, undo_group: usize, delta: RopeDelta) -> (Revision, Rope, Rope, Subset) {
+        self.mk_new_rev_at(self.revs.len() - 1, undo_group, delta)
+    }
+
+    /// [`Buffer::mk_new_rev`] for a delta against the text of the revision at `base_index`,
+    /// which is rebased over the revisions after it.
+    fn mk_new_rev_at(
+        &self,
+        base_index: usize,
+        undo_group: usize,
+        delta: RopeDelta,
+    ) -> (Revision, Rope, Rope, Subset) {
         let (ins_delta, deletes) = delta.factor();
 
-        let deletes_at_rev = &self.deletes_from_union;
+        // Rebase the delta to be on the union string of the base revision instead of its text
+        let deletes_at_rev = self.deletes_from_union_for_index(base_index);
 
-        let union_ins_delta = ins_delta.transform_expand(deletes_at_rev, true);
-        let mut new_deletes = deletes.transform_expand(deletes_at_rev);
+        let mut union_ins_delta = ins_delta.transform_expand(&deletes_at_rev, true);
+        let mut new_deletes = deletes.transform_expand(&deletes_at_rev);
+
+        // Rebase it onto the current union string, through the text inserted since
+        for rev in &self.revs[base_index + 1..] {
+            if let Contents::Edit { ref inserts, .. } = rev.edit {
+                if !inserts.is_empty() {
+                    union_ins_delta = union_ins_delta.transform_expand(inserts, true);
+                    new_deletes = new_deletes.transform_expand(inserts);
+                }
+            }
+        }
 
         let new_inserts = union_ins_delta.inserted_subset();
         if !new_inserts.is_empty() {
@@ -675,6 +834,25 @@ impl RopeText for Buffer {
     }
 }
 
+/// Determines the line ending of the text, only from its start when it's a large file.
+fn determine_line_ending(text: &Rope, large_file: bool) -> LineEndingDetermination {
+    if large_file {
+        LineEndingDetermination::determine_limited(text, LARGE_FILE_SAMPLE)
+    } else {
+        LineEndingDetermination::determine(text)
+    }
+}
+
+/// Replaces the lone carriage returns of the text, unless it's a large file, whose lone carriage
+/// returns are left as they are.
+fn normalize_line_endings(line_ending: LineEnding, text: &Rope, large_file: bool) -> Rope {
+    if large_file {
+        text.clone()
+    } else {
+        line_ending.normalize_limited(text)
+    }
+}
+
 fn shuffle_tombstones(
     text: &Rope,
     tombstones: &Rope,
@@ -1,7 +1,7 @@
 use super::{Buffer, RopeText};
 
 mod editing {
-    use lapce_xi_rope::Rope;
+    use lapce_xi_rope::{DeltaBuilder, Rope};
 
     use super::*;
     use crate::{editor::EditType, selection::Selection};
@@ -16,6 +16,43 @@ mod editing {
         buffer.do_undo();
         assert!(buffer.is_pristine());
     }
+
+    #[test]
+    fn apply_delta_at_rev() {
+        let mut buffer = Buffer::new("hello world");
+        let base_rev = buffer.rev();
+        buffer.edit(&[(Selection::caret(6), "big ")], EditType::InsertChars);
+
+        // Made against the text before the insertion above.
+        let mut builder = DeltaBuilder::new(11);
+        builder.replace(0..0, Rope::from("oh "));
+        builder.replace(6..11, Rope::from("there"));
+        let (text, delta, _) = buffer
+            .apply_delta_at_rev(base_rev, builder.build(), EditType::Other)
+            .unwrap();
+        assert_eq!(buffer.text().slice_to_cow(..), "oh hello big there");
+        assert_eq!(delta.apply(&text).slice_to_cow(..), "oh hello big there");
+
+        // Text inserted where a later edit inserted text goes after it.
+        let mut builder = DeltaBuilder::new(11);
+        builder.replace(0..0, Rope::from("so "));
+        buffer.apply_delta_at_rev(base_rev, builder.build(), EditType::Other);
+        assert_eq!(buffer.text().slice_to_cow(..), "oh so hello big there");
+
+        // Each applied delta is its own undo group.
+        buffer.do_undo();
+        buffer.do_undo();
+        assert_eq!(buffer.text().slice_to_cow(..), "hello big world");
+
+        let delta = DeltaBuilder::new(3).build();
+        assert!(buffer
+            .apply_delta_at_rev(base_rev, delta, EditType::Other)
+            .is_none());
+        let delta = DeltaBuilder::new(11).build();
+        assert!(buffer
+            .apply_delta_at_rev(1000, delta, EditType::Other)
+            .is_none());
+    }
 }
 
 mod motion {
@@ -166,3 +203,61 @@ mod motion {
         }
     }
 }
+
+mod large_file {
+    use lapce_xi_rope::Rope;
+
+    use super::*;
+    use crate::{
+        buffer::LARGE_FILE_THRESHOLD,
+        editor::EditType,
+        line_ending::{LineEnding, LineEndingDetermination},
+        selection::Selection,
+    };
+
+    #[test]
+    fn skips_normalizing_line_endings() {
+        let mut text = "a\r\n".repeat(LARGE_FILE_THRESHOLD / 3 + 1);
+        text.push_str("b\rc\n");
+        let buffer = Buffer::new(text.as_str());
+        assert!(buffer.is_large_file());
+        // The line ending comes from the start of the text, despite the mixed endings at its end.
+        assert_eq!(buffer.line_ending(), LineEnding::CrLf);
+        assert_eq!(buffer.len(), text.len());
+
+        let buffer = Buffer::new("a\r\nb\rc\n");
+        assert!(!buffer.is_large_file());
+        assert_eq!(buffer.text().slice_to_cow(..), "a\r\nb\r\nc\n");
+    }
+
+    #[test]
+    fn determine_limited() {
+        let text = Rope::from(format!("{}\r\n", "a".repeat(8 * 1024)));
+        assert!(matches!(
+            LineEndingDetermination::determine_limited(&text, 16),
+            LineEndingDetermination::Unknown
+        ));
+        assert!(matches!(
+            LineEndingDetermination::determine_limited(&text, text.len()),
+            LineEndingDetermination::CrLf
+        ));
+    }
+
+    #[test]
+    fn append_content() {
+        let mut buffer = Buffer::new("");
+        buffer.set_large_file(true);
+        buffer.append_content(Rope::from("a\r\nb\r"));
+        let (_, _, inval) = buffer.append_content(Rope::from("\nc"));
+        assert_eq!(buffer.text().slice_to_cow(..), "a\r\nb\r\nc");
+        assert_eq!(buffer.line_ending(), LineEnding::CrLf);
+        assert_eq!(inval.start_line, 1);
+        assert!(buffer.is_pristine());
+
+        // Loading can't be undone, unlike the edits made while loading.
+        buffer.edit(&[(Selection::caret(0), "d")], EditType::InsertChars);
+        buffer.append_content(Rope::from("e"));
+        buffer.do_undo();
+        assert_eq!(buffer.text().slice_to_cow(..), "a\r\nb\r\nce");
+    }
+}
@@ -1,3 +1,5 @@
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
 use strum_macros::{Display, EnumIter, EnumMessage, EnumString, IntoStaticStr};
 
 use crate::movement::{LinePosition, Movement};
@@ -5,6 +7,7 @@ use crate::movement::{LinePosition, Movement};
 #[derive(
     Display, EnumString, EnumIter, Clone, PartialEq, Eq, Debug, EnumMessage, IntoStaticStr,
 )]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub enum EditCommand {
     #[strum(message = "Move Line Up")]
     #[strum(serialize = "move_line_up")]
@@ -152,6 +155,7 @@ impl EditCommand {
 #[derive(
     Display, EnumString, EnumIter, Clone, PartialEq, Eq, Debug, EnumMessage, IntoStaticStr,
 )]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub enum MoveCommand {
     #[strum(message = "Down")]
     #[strum(serialize = "down")]
@@ -256,6 +260,7 @@ impl MoveCommand {
 #[derive(
     Display, EnumString, EnumIter, Clone, PartialEq, Eq, Debug, EnumMessage, IntoStaticStr,
 )]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub enum ScrollCommand {
     #[strum(message = "Page Up")]
     #[strum(serialize = "page_up")]
@@ -467,6 +472,7 @@ pub enum FocusCommand {
 #[derive(
     Display, EnumString, EnumIter, Clone, PartialEq, Eq, Debug, EnumMessage, IntoStaticStr,
 )]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub enum MotionModeCommand {
     #[strum(message = "Motion Mode Delete")]
     #[strum(serialize = "motion_mode_delete")]
@@ -485,6 +491,7 @@ pub enum MotionModeCommand {
 #[derive(
     Display, EnumString, EnumIter, Clone, PartialEq, Eq, Debug, EnumMessage, IntoStaticStr,
 )]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub enum MultiSelectionCommand {
     #[strum(message = "Select Undo")]
     #[strum(serialize = "select_undo")]
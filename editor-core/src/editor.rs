@@ -8,7 +8,7 @@ use crate::{
     command::EditCommand,
     cursor::{get_first_selection_after, Cursor, CursorMode},
     mode::{Mode, MotionMode, VisualMode},
-    register::{Clipboard, Register, RegisterData, RegisterKind},
+    register::{Clipboard, NoClipboard, Register, RegisterData, RegisterKind},
     selection::{InsertDrift, SelRegion, Selection},
     util::{
         has_unmatched_pair, matching_char, matching_pair_direction, str_is_pair_left,
@@ -411,12 +411,34 @@ impl Action {
         range: Range<usize>,
         is_vertical: bool,
         register: &mut Register,
+    ) -> Vec<(Rope, RopeDelta, InvalLines)> {
+        Self::execute_motion_mode_with_clipboard(
+            cursor,
+            buffer,
+            motion_mode,
+            range,
+            is_vertical,
+            register,
+            &mut NoClipboard,
+        )
+    }
+
+    /// Like [`Action::execute_motion_mode`], writing deletes and yanks to the clipboard when the
+    /// clipboard register is selected.
+    pub fn execute_motion_mode_with_clipboard<T: Clipboard>(
+        cursor: &mut Cursor,
+        buffer: &mut Buffer,
+        motion_mode: MotionMode,
+        range: Range<usize>,
+        is_vertical: bool,
+        register: &mut Register,
+        clipboard: &mut T,
     ) -> Vec<(Rope, RopeDelta, InvalLines)> {
         let mut deltas = Vec::new();
         match motion_mode {
             MotionMode::Delete { .. } => {
                 let range = format_start_end(buffer, range, is_vertical, false, 1);
-                register.add(
+                register.add_with_clipboard(
                     RegisterKind::Delete,
                     RegisterData {
                         content: buffer.slice_to_cow(range.clone()).to_string(),
@@ -426,6 +448,7 @@ impl Action {
                             VisualMode::Normal
                         },
                     },
+                    clipboard,
                 );
                 let selection = Selection::region(range.start, range.end);
                 let (text, delta, inval_lines) =
@@ -435,7 +458,7 @@ impl Action {
             }
             MotionMode::Yank { .. } => {
                 let range = format_start_end(buffer, range, is_vertical, false, 1);
-                register.add(
+                register.add_with_clipboard(
                     RegisterKind::Yank,
                     RegisterData {
                         content: buffer.slice_to_cow(range).to_string(),
@@ -445,6 +468,7 @@ impl Action {
                             VisualMode::Normal
                         },
                     },
+                    clipboard,
                 );
             }
             MotionMode::Indent => {
@@ -1036,7 +1060,7 @@ impl Action {
                 match &cursor.mode {
                     CursorMode::Visual { start, end, .. } => {
                         let data = cursor.yank(buffer);
-                        register.add_yank(data);
+                        register.add_yank_with_clipboard(data, clipboard);
 
                         let offset = *start.min(end);
                         let offset = buffer.offset_line_end(offset, false).min(offset);
@@ -1048,12 +1072,12 @@ impl Action {
                 vec![]
             }
             Paste => {
-                let data = register.unnamed.clone();
+                let data = register.take_selected(clipboard);
                 Self::do_paste(cursor, buffer, &data)
             }
             PasteBefore => {
                 let offset = cursor.offset();
-                let data = register.unnamed.clone();
+                let data = register.take_selected(clipboard);
                 let mut local_cursor =
                     Cursor::new(CursorMode::Insert(Selection::new()), None, None);
                 local_cursor.set_offset(offset, false, false);
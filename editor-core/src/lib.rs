@@ -6,12 +6,14 @@ pub mod cursor;
 pub mod editor;
 pub mod indent;
 pub mod line_ending;
+pub mod mark;
 pub mod mode;
 pub mod movement;
 pub mod paragraph;
 pub mod register;
 pub mod selection;
 pub mod soft_tab;
+pub mod text_object;
 pub mod util;
 pub mod word;
 
@@ -105,10 +105,26 @@ impl LineEndingDetermination {
     // TODO: should we just do a simpler routine of checking the first few lines?
     // Based off of xi-rope's line-ending determination logic
     pub fn determine(text: &Rope) -> Self {
+        Self::determine_chunks(text.iter_chunks(..))
+    }
+
+    /// Determine the line ending from about the first `limit` bytes of the text, for text that
+    /// is too large to look through.
+    pub fn determine_limited(text: &Rope, limit: usize) -> Self {
+        let mut seen = 0;
+        let chunks = text.iter_chunks(..).take_while(|chunk| {
+            let take = seen < limit;
+            seen += chunk.len();
+            take
+        });
+        Self::determine_chunks(chunks)
+    }
+
+    fn determine_chunks<'a>(chunks: impl Iterator<Item = &'a str>) -> Self {
         let mut crlf = false;
         let mut lf = false;
 
-        for chunk in text.iter_chunks(..) {
+        for chunk in chunks {
             match LineEndingDetermination::determine_str(chunk) {
                 LineEndingDetermination::CrLf => crlf = true,
                 LineEndingDetermination::Lf => lf = true,
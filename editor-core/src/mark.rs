@@ -0,0 +1,76 @@
+use std::collections::BTreeMap;
+
+use lapce_xi_rope::{RopeDelta, Transformer};
+
+/// Named offsets in a buffer, like the marks `a` to `z` of vim.
+///
+/// The marks are moved through every edit of the buffer so that they stay at the same place in
+/// the text. A mark in text that's deleted moves to where the text was.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Marks {
+    marks: BTreeMap<char, usize>,
+}
+
+impl Marks {
+    /// Sets the mark `name` at the offset, replacing it if it's already set.
+    pub fn set(&mut self, name: char, offset: usize) {
+        self.marks.insert(name, offset);
+    }
+
+    /// The offset of the mark `name`, if it's set.
+    pub fn get(&self, name: char) -> Option<usize> {
+        self.marks.get(&name).copied()
+    }
+
+    pub fn remove(&mut self, name: char) -> Option<usize> {
+        self.marks.remove(&name)
+    }
+
+    /// The names and offsets of the marks, ordered by name.
+    pub fn iter(&self) -> impl Iterator<Item = (char, usize)> + '_ {
+        self.marks.iter().map(|(name, offset)| (*name, *offset))
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.marks.is_empty()
+    }
+
+    /// Moves the marks through the edit of the delta.
+    pub fn apply_delta(&mut self, delta: &RopeDelta) {
+        for offset in self.marks.values_mut() {
+            *offset = Transformer::new(delta).transform(*offset, false);
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::Marks;
+    use crate::{buffer::Buffer, editor::EditType, selection::Selection};
+
+    #[test]
+    fn marks_follow_edits() {
+        let mut buffer = Buffer::new("one two three");
+        buffer.marks_mut().set('a', 4);
+        buffer.marks_mut().set('b', 8);
+
+        buffer.edit([(Selection::caret(0), "zero ")], EditType::InsertChars);
+        assert_eq!(buffer.marks().get('a'), Some(9));
+        buffer.edit([(Selection::region(9, 13), "")], EditType::Delete);
+        assert_eq!(buffer.marks().get('a'), Some(9));
+        assert_eq!(buffer.marks().get('b'), Some(9));
+
+        // Undoing the edits moves the marks back, though not out of the text they were deleted
+        // with.
+        buffer.do_undo();
+        buffer.do_undo();
+        assert_eq!(buffer.marks().get('a'), Some(4));
+        assert_eq!(buffer.marks().get('c'), None);
+
+        let mut marks = Marks::default();
+        marks.set('x', 1);
+        assert_eq!(marks.iter().collect::<Vec<_>>(), [('x', 1)]);
+        assert_eq!(marks.remove('x'), Some(1));
+        assert!(marks.is_empty());
+    }
+}
@@ -1,3 +1,8 @@
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::buffer::rope_text::RopeText;
+
 #[derive(Clone, Debug)]
 pub enum LinePosition {
     First,
@@ -26,6 +31,12 @@ pub enum Movement {
     MatchPairs,
     ParagraphForward,
     ParagraphBackward,
+    /// Move to a character on the line. `repeat` is set when the find is repeated, like with `;`
+    /// and `,` in vim.
+    InlineFind {
+        find: InlineFind,
+        repeat: bool,
+    },
 }
 
 impl PartialEq for Movement {
@@ -49,7 +60,11 @@ impl Movement {
     }
 
     pub fn is_inclusive(&self) -> bool {
-        matches!(self, Movement::WordEndForward)
+        match self {
+            Movement::WordEndForward => true,
+            Movement::InlineFind { find, .. } => find.direction == InlineFindDirection::Right,
+            _ => false,
+        }
     }
 
     pub fn is_jump(&self) -> bool {
@@ -92,9 +107,98 @@ impl Movement {
     }
 }
 
+/// The direction of an [`InlineFind`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum InlineFindDirection {
+    Left,
+    Right,
+}
+
+/// A search for a character on the line of the cursor, like `f`, `t`, `F` and `T` in vim.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct InlineFind {
+    pub direction: InlineFindDirection,
+    pub c: char,
+    /// Whether to stop next to the character, like `t` and `T`, instead of on it.
+    pub till: bool,
+}
+
+impl InlineFind {
+    /// The same find in the other direction, like `,` repeats the last find in vim.
+    pub fn reversed(self) -> InlineFind {
+        let direction = match self.direction {
+            InlineFindDirection::Left => InlineFindDirection::Right,
+            InlineFindDirection::Right => InlineFindDirection::Left,
+        };
+        InlineFind { direction, ..self }
+    }
+
+    /// The offset of the `count`th occurrence of the character on the line of `offset`, or `None`
+    /// if the line doesn't have that many in the direction of the find.
+    ///
+    /// When `repeat` is set, an occurrence right next to the offset is skipped by a till find, so
+    /// that repeating it moves on to the next occurrence instead of staying in place.
+    pub fn offset(
+        &self,
+        text: &impl RopeText,
+        offset: usize,
+        count: usize,
+        repeat: bool,
+    ) -> Option<usize> {
+        let line = text.line_of_offset(offset);
+        let line_start = text.offset_of_line(line);
+        let content = text.line_content(line);
+        let content = content.trim_end_matches(['\n', '\r']);
+        let col = offset.saturating_sub(line_start).min(content.len());
+        let skip = self.till && repeat;
+
+        let col = match self.direction {
+            InlineFindDirection::Right => {
+                let after = col + content[col..].chars().next().map_or(0, char::len_utf8);
+                let (found, _) = content[after..]
+                    .char_indices()
+                    .filter(|(_, c)| *c == self.c)
+                    .map(|(i, c)| (after + i, c))
+                    .filter(|(i, _)| !skip || *i != after)
+                    .nth(count.max(1) - 1)?;
+                if self.till {
+                    found
+                        - content[..found]
+                            .chars()
+                            .next_back()
+                            .map_or(0, char::len_utf8)
+                } else {
+                    found
+                }
+            }
+            InlineFindDirection::Left => {
+                let before = content[..col].chars().next_back().map_or(0, char::len_utf8);
+                let (found, c) = content[..col]
+                    .char_indices()
+                    .rev()
+                    .filter(|(_, c)| *c == self.c)
+                    .filter(|(i, _)| !skip || *i != col - before)
+                    .nth(count.max(1) - 1)?;
+                if self.till {
+                    found + c.len_utf8()
+                } else {
+                    found
+                }
+            }
+        };
+
+        Some(line_start + col)
+    }
+}
+
 #[cfg(test)]
 mod test {
-    use crate::movement::Movement;
+    use crate::{
+        buffer::Buffer,
+        movement::{InlineFind, InlineFindDirection, Movement},
+    };
 
     #[test]
     fn test_wrapping() {
@@ -137,4 +241,33 @@ mod test {
         assert_eq!(0, Movement::Up.update_index(0, 5, 2, false));
         assert_eq!(2, Movement::Down.update_index(0, 5, 2, false));
     }
+
+    #[test]
+    fn test_inline_find() {
+        let buffer = Buffer::new("a,b,c;d\ne,f");
+        let find = |direction, c, till| InlineFind { direction, c, till };
+        let right = find(InlineFindDirection::Right, ',', false);
+        assert_eq!(right.offset(&buffer, 0, 1, false), Some(1));
+        assert_eq!(right.offset(&buffer, 1, 1, false), Some(3));
+        assert_eq!(right.offset(&buffer, 0, 2, false), Some(3));
+        // The find doesn't leave the line.
+        assert_eq!(right.offset(&buffer, 3, 1, false), None);
+
+        let till = find(InlineFindDirection::Right, ',', true);
+        assert_eq!(till.offset(&buffer, 0, 1, false), Some(0));
+        // Repeating a till find moves past the character next to the cursor.
+        assert_eq!(till.offset(&buffer, 0, 1, true), Some(2));
+
+        let left = right.reversed();
+        assert_eq!(left.direction, InlineFindDirection::Left);
+        assert_eq!(left.offset(&buffer, 6, 1, false), Some(3));
+        assert_eq!(left.offset(&buffer, 6, 2, false), Some(1));
+        assert_eq!(left.offset(&buffer, 1, 1, false), None);
+
+        let till_left = find(InlineFindDirection::Left, ',', true);
+        assert_eq!(till_left.offset(&buffer, 6, 1, false), Some(4));
+        assert_eq!(till_left.offset(&buffer, 4, 1, false), Some(4));
+        assert_eq!(till_left.offset(&buffer, 4, 1, true), Some(2));
+        assert_eq!(till_left.offset(&buffer, 11, 1, false), Some(10));
+    }
 }
@@ -1,20 +1,66 @@
+use std::collections::{HashMap, VecDeque};
+
 use crate::mode::VisualMode;
 
+/// How many deletes are kept in the numbered registers `"1` to `"9`.
+const NUMBERED_REGISTERS: usize = 9;
+
 pub trait Clipboard {
     fn get_string(&mut self) -> Option<String>;
     fn put_string(&mut self, s: impl AsRef<str>);
 }
 
-#[derive(Clone, Default)]
+/// A clipboard that is always empty, for the functions that aren't given one.
+pub(crate) struct NoClipboard;
+
+impl Clipboard for NoClipboard {
+    fn get_string(&mut self) -> Option<String> {
+        None
+    }
+
+    fn put_string(&mut self, _s: impl AsRef<str>) {}
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
 pub struct RegisterData {
     pub content: String,
     pub mode: VisualMode,
 }
 
+impl RegisterData {
+    /// Appends the data, like yanking into an uppercase register in vim. The result is linewise
+    /// when either of them is.
+    pub fn append(&mut self, data: RegisterData) {
+        let linewise = |mode| mode == VisualMode::Linewise;
+        if (linewise(self.mode) || linewise(data.mode))
+            && !self.content.is_empty()
+            && !self.content.ends_with('\n')
+        {
+            self.content.push('\n');
+        }
+        self.content.push_str(&data.content);
+        if linewise(data.mode) {
+            self.mode = VisualMode::Linewise;
+        }
+    }
+}
+
+/// The registers of the modal mode.
+///
+/// Like in vim, yanks and deletes go to the unnamed register `""`. The last yank is also kept in
+/// `"0`, deletes that span lines in the numbered registers `"1` to `"9`, the most recent first,
+/// and smaller deletes in `"-`. A register can be [selected](Register::select) for the next
+/// yank, delete or paste instead: the named registers `"a` to `"z` (with `"A` to `"Z` appending
+/// to them), the system clipboard `"+` (or `"*`), or the black hole register `"_`, which
+/// discards what's written to it.
 #[derive(Clone, Default)]
 pub struct Register {
     pub unnamed: RegisterData,
     last_yank: RegisterData,
+    small_delete: RegisterData,
+    deletes: VecDeque<RegisterData>,
+    named: HashMap<char, RegisterData>,
+    selected: Option<char>,
 }
 
 pub enum RegisterKind {
@@ -23,19 +69,268 @@ pub enum RegisterKind {
 }
 
 impl Register {
+    /// Whether `name` is the name of a register that can be selected.
+    pub fn is_valid_name(name: char) -> bool {
+        name.is_ascii_alphanumeric() || matches!(name, '"' | '-' | '+' | '*' | '_')
+    }
+
+    /// Selects the register that the next yank, delete or paste uses, like `"a` in vim.
+    /// An invalid name is ignored.
+    pub fn select(&mut self, name: char) {
+        if Self::is_valid_name(name) {
+            self.selected = Some(name);
+        }
+    }
+
+    /// The register selected for the next yank, delete or paste, if any.
+    pub fn selected(&self) -> Option<char> {
+        self.selected
+    }
+
+    /// Adds the data to the registers. When the clipboard register `"+` is selected, the data
+    /// only goes to the unnamed register, see [`Register::add_with_clipboard`].
     pub fn add(&mut self, kind: RegisterKind, data: RegisterData) {
+        self.add_with_clipboard(kind, data, &mut NoClipboard);
+    }
+
+    /// Adds the data to the registers, writing it to the clipboard when the clipboard register
+    /// `"+` is selected.
+    pub fn add_with_clipboard<T: Clipboard>(
+        &mut self,
+        kind: RegisterKind,
+        data: RegisterData,
+        clipboard: &mut T,
+    ) {
         match kind {
-            RegisterKind::Delete => self.add_delete(data),
-            RegisterKind::Yank => self.add_yank(data),
+            RegisterKind::Delete => self.add_delete_with_clipboard(data, clipboard),
+            RegisterKind::Yank => self.add_yank_with_clipboard(data, clipboard),
         }
     }
 
     pub fn add_delete(&mut self, data: RegisterData) {
+        self.add_delete_with_clipboard(data, &mut NoClipboard);
+    }
+
+    pub fn add_delete_with_clipboard<T: Clipboard>(
+        &mut self,
+        data: RegisterData,
+        clipboard: &mut T,
+    ) {
+        if self.add_selected(&data, clipboard) {
+            return;
+        }
+        if data.mode == VisualMode::Linewise || data.content.contains('\n') {
+            self.deletes.push_front(data.clone());
+            self.deletes.truncate(NUMBERED_REGISTERS);
+        } else {
+            self.small_delete = data.clone();
+        }
         self.unnamed = data;
     }
 
     pub fn add_yank(&mut self, data: RegisterData) {
+        self.add_yank_with_clipboard(data, &mut NoClipboard);
+    }
+
+    pub fn add_yank_with_clipboard<T: Clipboard>(&mut self, data: RegisterData, clipboard: &mut T) {
+        if self.add_selected(&data, clipboard) {
+            return;
+        }
         self.unnamed = data.clone();
         self.last_yank = data;
     }
+
+    /// Writes the data to the selected register, and unselects it. Returns `false` when no
+    /// register is selected, or the unnamed or a numbered register is, since those are written
+    /// to like when none is.
+    fn add_selected<T: Clipboard>(&mut self, data: &RegisterData, clipboard: &mut T) -> bool {
+        match self.selected.take() {
+            None | Some('"' | '0'..='9' | '-') => return false,
+            Some('_') => {}
+            Some('+' | '*') => {
+                clipboard.put_string(&data.content);
+                self.unnamed = data.clone();
+            }
+            Some(name) if name.is_ascii_uppercase() => {
+                let register = self.named.entry(name.to_ascii_lowercase()).or_default();
+                register.append(data.clone());
+                self.unnamed = register.clone();
+            }
+            Some(name) => {
+                self.named.insert(name, data.clone());
+                self.unnamed = data.clone();
+            }
+        }
+        true
+    }
+
+    /// Writes the register `name` directly, like recording a macro into it, appending to the
+    /// lowercase register for `A` to `Z`. The clipboard and black hole registers can't be
+    /// written like this, and the unnamed register is left as it is for the others.
+    pub fn set(&mut self, name: char, data: RegisterData) {
+        match name {
+            '"' => self.unnamed = data,
+            '0' => self.last_yank = data,
+            '1'..='9' => {
+                let index = name as usize - '1' as usize;
+                if self.deletes.len() <= index {
+                    self.deletes.resize(index + 1, RegisterData::default());
+                }
+                self.deletes[index] = data;
+            }
+            '-' => self.small_delete = data,
+            'a'..='z' => {
+                self.named.insert(name, data);
+            }
+            'A'..='Z' => self
+                .named
+                .entry(name.to_ascii_lowercase())
+                .or_default()
+                .append(data),
+            _ => {}
+        }
+    }
+
+    /// The content of the register `name`. The clipboard register isn't read by this, see
+    /// [`Register::take_selected`].
+    pub fn get(&self, name: char) -> Option<&RegisterData> {
+        match name {
+            '"' => Some(&self.unnamed),
+            '0' => Some(&self.last_yank),
+            '1'..='9' => self.deletes.get(name as usize - '1' as usize),
+            '-' => Some(&self.small_delete),
+            'a'..='z' | 'A'..='Z' => self.named.get(&name.to_ascii_lowercase()),
+            _ => None,
+        }
+    }
+
+    /// The content to paste, which is that of the selected register, or of the unnamed register
+    /// when none is selected. The register is unselected.
+    pub fn take_selected<T: Clipboard>(&mut self, clipboard: &mut T) -> RegisterData {
+        match self.selected.take() {
+            None => self.unnamed.clone(),
+            Some('+' | '*') => clipboard
+                .get_string()
+                .map(|content| {
+                    let mode = if content.ends_with('\n') {
+                        VisualMode::Linewise
+                    } else {
+                        VisualMode::Normal
+                    };
+                    RegisterData { content, mode }
+                })
+                .unwrap_or_default(),
+            Some(name) => self.get(name).cloned().unwrap_or_default(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{Clipboard, Register, RegisterData, RegisterKind};
+    use crate::mode::VisualMode;
+
+    #[derive(Default)]
+    struct TestClipboard(Option<String>);
+
+    impl Clipboard for TestClipboard {
+        fn get_string(&mut self) -> Option<String> {
+            self.0.clone()
+        }
+
+        fn put_string(&mut self, s: impl AsRef<str>) {
+            self.0 = Some(s.as_ref().to_string());
+        }
+    }
+
+    fn data(content: &str) -> RegisterData {
+        RegisterData {
+            content: content.to_string(),
+            mode: if content.ends_with('\n') {
+                VisualMode::Linewise
+            } else {
+                VisualMode::Normal
+            },
+        }
+    }
+
+    #[test]
+    fn named_registers() {
+        let mut clipboard = TestClipboard::default();
+        let mut register = Register::default();
+
+        register.select('a');
+        register.add(RegisterKind::Yank, data("one"));
+        assert_eq!(register.selected(), None);
+        register.add(RegisterKind::Yank, data("two"));
+        assert_eq!(register.get('a'), Some(&data("one")));
+        assert_eq!(register.get('0'), Some(&data("two")));
+
+        // An uppercase register appends to the lowercase one.
+        register.select('A');
+        register.add(RegisterKind::Delete, data("three\n"));
+        assert_eq!(register.get('a'), Some(&data("one\nthree\n")));
+        assert_eq!(register.unnamed, data("one\nthree\n"));
+        assert_eq!(register.get('1'), None);
+
+        register.select('a');
+        assert_eq!(register.take_selected(&mut clipboard), data("one\nthree\n"));
+        assert_eq!(register.take_selected(&mut clipboard), data("one\nthree\n"));
+
+        // The black hole register keeps the other registers as they are.
+        register.select('_');
+        register.add(RegisterKind::Delete, data("four"));
+        assert_eq!(register.unnamed, data("one\nthree\n"));
+
+        register.select('%');
+        assert_eq!(register.selected(), None);
+    }
+
+    #[test]
+    fn numbered_registers() {
+        let mut register = Register::default();
+        for i in 0..12 {
+            register.add_delete(data(&format!("{i}\n")));
+        }
+        register.add_delete(data("word"));
+
+        assert_eq!(register.get('1'), Some(&data("11\n")));
+        assert_eq!(register.get('9'), Some(&data("3\n")));
+        assert_eq!(register.get('-'), Some(&data("word")));
+        assert_eq!(register.unnamed, data("word"));
+    }
+
+    #[test]
+    fn clipboard_register() {
+        let mut clipboard = TestClipboard::default();
+        let mut register = Register::default();
+
+        register.select('+');
+        register.add_yank_with_clipboard(data("copied"), &mut clipboard);
+        assert_eq!(clipboard.0.as_deref(), Some("copied"));
+        assert_eq!(register.get('0'), Some(&RegisterData::default()));
+
+        // Without a clipboard, the data only goes to the unnamed register.
+        register.select('+');
+        register.add_yank(data("kept"));
+        assert_eq!(clipboard.0.as_deref(), Some("copied"));
+        assert_eq!(register.unnamed, data("kept"));
+
+        clipboard.0 = Some("line\n".to_string());
+        register.select('*');
+        assert_eq!(register.take_selected(&mut clipboard), data("line\n"));
+    }
+
+    #[test]
+    fn set_registers() {
+        let mut register = Register::default();
+        register.set('q', data("one"));
+        register.set('Q', data("two"));
+        register.set('3', data("three\n"));
+        register.set('+', data("four"));
+        assert_eq!(register.get('q'), Some(&data("onetwo")));
+        assert_eq!(register.get('3'), Some(&data("three\n")));
+        assert_eq!(register.get('1'), Some(&RegisterData::default()));
+        assert_eq!(register.unnamed, RegisterData::default());
+    }
 }
@@ -0,0 +1,514 @@
+use std::ops::Range;
+
+use lapce_xi_rope::{Cursor, Rope};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    buffer::rope_text::RopeText,
+    word::{get_char_property, CharClassification},
+};
+
+/// A text object, which is a range of text around an offset that an operator can be applied to
+/// or that can be selected in visual mode, like `iw` or `a(` in vim.
+///
+/// The inner variant (`around: false`) selects only the object itself, like the word or the text
+/// between the brackets. The outer variant (`around: true`) also selects the surrounding
+/// whitespace, brackets, quotes or tags.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum TextObject {
+    /// A word, a sequence of other non-blank characters, or a sequence of whitespace (`iw`, `aw`)
+    Word { around: bool },
+    /// A block between a pair of brackets (`i(`, `a[`, `i{`, `a<`)
+    Pair {
+        open: char,
+        close: char,
+        around: bool,
+    },
+    /// A string between a pair of quotes on the same line (`i"`, `a'`, ``i` ``)
+    Quote { quote: char, around: bool },
+    /// The content between a pair of matching XML or HTML tags (`it`, `at`)
+    Tag { around: bool },
+    /// A paragraph, which is a sequence of non-blank lines or of blank lines (`ip`, `ap`)
+    Paragraph { around: bool },
+}
+
+impl TextObject {
+    /// The text object for the character typed after `i` or `a` in vim.
+    pub fn from_char(c: char, around: bool) -> Option<TextObject> {
+        let object = match c {
+            'w' => TextObject::Word { around },
+            '(' | ')' | 'b' => TextObject::Pair {
+                open: '(',
+                close: ')',
+                around,
+            },
+            '[' | ']' => TextObject::Pair {
+                open: '[',
+                close: ']',
+                around,
+            },
+            '{' | '}' | 'B' => TextObject::Pair {
+                open: '{',
+                close: '}',
+                around,
+            },
+            '<' | '>' => TextObject::Pair {
+                open: '<',
+                close: '>',
+                around,
+            },
+            '"' | '\'' | '`' => TextObject::Quote { quote: c, around },
+            't' => TextObject::Tag { around },
+            'p' => TextObject::Paragraph { around },
+            _ => return None,
+        };
+        Some(object)
+    }
+
+    /// The character of the text object and whether it's the outer variant, the inverse of
+    /// [`TextObject::from_char`].
+    pub fn to_char(&self) -> (char, bool) {
+        match *self {
+            TextObject::Word { around } => ('w', around),
+            TextObject::Pair { open, around, .. } => (open, around),
+            TextObject::Quote { quote, around } => (quote, around),
+            TextObject::Tag { around } => ('t', around),
+            TextObject::Paragraph { around } => ('p', around),
+        }
+    }
+
+    /// Whether the text object covers whole lines, so that operators act linewise on it.
+    pub fn is_linewise(&self) -> bool {
+        matches!(self, TextObject::Paragraph { .. })
+    }
+
+    /// The range of the text object at the offset, or `None` if there is no such object there,
+    /// like for `i(` outside of any parentheses.
+    pub fn range(&self, text: &impl RopeText, offset: usize) -> Option<Range<usize>> {
+        let offset = offset.min(text.len());
+        match *self {
+            TextObject::Word { around } => word_range(text, offset, around),
+            TextObject::Pair {
+                open,
+                close,
+                around,
+            } => pair_range(text, offset, open, close, around),
+            TextObject::Quote { quote, around } => quote_range(text, offset, quote, around),
+            TextObject::Tag { around } => tag_range(text, offset, around),
+            TextObject::Paragraph { around } => paragraph_range(text, offset, around),
+        }
+    }
+}
+
+/// The content of the line of the offset without its line ending, with the offset of its start.
+fn line_around(text: &impl RopeText, offset: usize) -> (usize, String) {
+    let line = text.line_of_offset(offset);
+    let content = text.line_content(line);
+    let content = content.trim_end_matches(['\n', '\r']).to_string();
+    (text.offset_of_line(line), content)
+}
+
+/// The class of a character for word text objects. Whitespace, punctuation and the other
+/// characters each form their own words.
+fn word_class(c: char) -> CharClassification {
+    match get_char_property(c) {
+        CharClassification::Cr | CharClassification::Lf => CharClassification::Space,
+        class => class,
+    }
+}
+
+fn word_range(text: &impl RopeText, offset: usize, around: bool) -> Option<Range<usize>> {
+    let (line_start, content) = line_around(text, offset);
+    let chars: Vec<(usize, char)> = content.char_indices().collect();
+    if chars.is_empty() {
+        return None;
+    }
+
+    let col = offset - line_start;
+    let index = chars
+        .iter()
+        .rposition(|(i, _)| *i <= col)
+        .unwrap_or(0)
+        .min(chars.len() - 1);
+    let class = |i: usize| word_class(chars[i].1);
+    // The indices of the first and after the last character of the run of characters of the
+    // same class as the character at `i`.
+    let run = |i: usize| {
+        let mut start = i;
+        while start > 0 && class(start - 1) == class(i) {
+            start -= 1;
+        }
+        let mut end = i + 1;
+        while end < chars.len() && class(end) == class(i) {
+            end += 1;
+        }
+        (start, end)
+    };
+
+    let (mut start, mut end) = run(index);
+    if around {
+        if class(index) == CharClassification::Space {
+            // The whitespace and the word after it, or the word before it at the end of the line.
+            if end < chars.len() {
+                end = run(end).1;
+            } else if start > 0 {
+                start = run(start - 1).0;
+            }
+        } else if end < chars.len() && class(end) == CharClassification::Space {
+            end = run(end).1;
+        } else if start > 0 && class(start - 1) == CharClassification::Space {
+            // The whitespace before the word, unless it's the indentation of the line.
+            let before = run(start - 1).0;
+            if before > 0 {
+                start = before;
+            }
+        }
+    }
+
+    let byte = |i: usize| chars.get(i).map(|(b, _)| *b).unwrap_or(content.len());
+    Some(line_start + byte(start)..line_start + byte(end))
+}
+
+fn pair_range(
+    text: &impl RopeText,
+    offset: usize,
+    open: char,
+    close: char,
+    around: bool,
+) -> Option<Range<usize>> {
+    let rope = text.text();
+
+    let start = if Cursor::new(rope, offset).peek_next_codepoint() == Some(open) {
+        offset
+    } else {
+        let mut cursor = Cursor::new(rope, offset);
+        let mut depth = 0;
+        loop {
+            match cursor.prev_codepoint()? {
+                c if c == close => depth += 1,
+                c if c == open && depth == 0 => break cursor.pos(),
+                c if c == open => depth -= 1,
+                _ => {}
+            }
+        }
+    };
+
+    let mut cursor = Cursor::new(rope, start + open.len_utf8());
+    let mut depth = 0;
+    let end = loop {
+        match cursor.next_codepoint()? {
+            c if c == open => depth += 1,
+            c if c == close && depth == 0 => break cursor.pos() - close.len_utf8(),
+            c if c == close => depth -= 1,
+            _ => {}
+        }
+    };
+
+    if around {
+        return Some(start..end + close.len_utf8());
+    }
+
+    // When the brackets are on lines of their own, like the braces of a block, the inner object
+    // is the lines between them.
+    let inner = start + open.len_utf8()..end;
+    let open_line = text.line_of_offset(start);
+    let close_line = text.line_of_offset(end);
+    let open_line_end = text.line_end_offset(open_line, true);
+    let close_line_start = text.offset_of_line(close_line);
+    if open_line < close_line
+        && open_line_end == inner.start
+        && text.slice_to_cow(close_line_start..end).trim().is_empty()
+    {
+        return Some(text.offset_of_line(open_line + 1)..close_line_start);
+    }
+
+    Some(inner)
+}
+
+fn quote_range(
+    text: &impl RopeText,
+    offset: usize,
+    quote: char,
+    around: bool,
+) -> Option<Range<usize>> {
+    let (line_start, content) = line_around(text, offset);
+    let col = offset - line_start;
+
+    // The quotes on the line that aren't escaped with a backslash, paired up from the start.
+    let mut quotes = Vec::new();
+    let mut escaped = false;
+    for (i, c) in content.char_indices() {
+        if c == quote && !escaped {
+            quotes.push(i);
+        }
+        escaped = c == '\\' && !escaped;
+    }
+    let (start, end) = quotes
+        .chunks_exact(2)
+        .map(|pair| (pair[0], pair[1]))
+        .find(|(start, end)| (*start..=*end).contains(&col))
+        .or_else(|| {
+            // Like vim, use the first string after the offset when it's not in one.
+            quotes
+                .chunks_exact(2)
+                .map(|pair| (pair[0], pair[1]))
+                .find(|(start, _)| *start > col)
+        })?;
+
+    let (start, end) = if around {
+        let is_space = |c: char| c == ' ' || c == '\t';
+        let end = end + quote.len_utf8();
+        let trailing = content[end..].len() - content[end..].trim_start_matches(is_space).len();
+        if trailing > 0 {
+            (start, end + trailing)
+        } else {
+            let leading =
+                content[..start].len() - content[..start].trim_end_matches(is_space).len();
+            if leading < start {
+                (start - leading, end)
+            } else {
+                (start, end)
+            }
+        }
+    } else {
+        (start + quote.len_utf8(), end)
+    };
+
+    Some(line_start + start..line_start + end)
+}
+
+/// How far before and after the offset the tags of a tag text object are searched for, so that
+/// it doesn't go through the whole of a large document.
+const TAG_SEARCH_LIMIT: usize = 64 * 1024;
+
+/// A tag of a tag text object, with the name of the element.
+enum Tag {
+    Open(String),
+    Close(String),
+}
+
+/// The tag starting at the `<` at `start` and the offset after its `>`, or `None` if it isn't an
+/// opening or closing tag, like a self-closing tag or a comparison.
+fn read_tag(rope: &Rope, start: usize, limit: usize) -> Option<(Tag, usize)> {
+    let mut cursor = Cursor::new(rope, start + 1);
+    let mut tag = String::new();
+    loop {
+        if cursor.pos() >= limit {
+            return None;
+        }
+        match cursor.next_codepoint()? {
+            '>' => break,
+            '<' => return None,
+            c => tag.push(c),
+        }
+    }
+
+    let tag = if let Some(name) = tag.strip_prefix('/') {
+        Tag::Close(name.trim().to_string())
+    } else {
+        let name = tag.split(char::is_whitespace).next().unwrap_or_default();
+        if tag.ends_with('/') || !name.starts_with(char::is_alphabetic) {
+            return None;
+        }
+        Tag::Open(name.to_string())
+    };
+    Some((tag, cursor.pos()))
+}
+
+fn tag_range(text: &impl RopeText, offset: usize, around: bool) -> Option<Range<usize>> {
+    let rope = text.text();
+    let window_start = offset.saturating_sub(TAG_SEARCH_LIMIT);
+    let window_end = (offset + TAG_SEARCH_LIMIT).min(text.len());
+
+    // Go back through the tags from the offset, including one that starts at it, skipping the
+    // elements that are closed before the offset, until an opening tag whose closing tag is after
+    // the offset. That's the innermost element around the offset.
+    let mut cursor = Cursor::new(rope, offset);
+    cursor.next_codepoint();
+    let mut closed: Vec<String> = Vec::new();
+    while cursor.pos() > window_start {
+        if cursor.prev_codepoint()? != '<' {
+            continue;
+        }
+        let start = cursor.pos();
+        match read_tag(rope, start, window_end) {
+            Some((Tag::Close(name), end)) if end <= offset => closed.push(name),
+            Some((Tag::Open(name), open_end)) => {
+                if let Some(i) = closed.iter().rposition(|closed| *closed == name) {
+                    closed.truncate(i);
+                    continue;
+                }
+                let Some((close_start, close_end)) = closing_tag(rope, &name, open_end, window_end)
+                else {
+                    continue;
+                };
+                if close_end > offset {
+                    return Some(if around {
+                        start..close_end
+                    } else {
+                        open_end..close_start
+                    });
+                }
+            }
+            _ => {}
+        }
+    }
+
+    None
+}
+
+/// The range of the closing tag of the element `name` whose opening tag ends at `offset`.
+fn closing_tag(rope: &Rope, name: &str, offset: usize, limit: usize) -> Option<(usize, usize)> {
+    let mut cursor = Cursor::new(rope, offset);
+    let mut depth = 0;
+    while cursor.pos() < limit {
+        if cursor.next_codepoint()? != '<' {
+            continue;
+        }
+        let start = cursor.pos() - 1;
+        match read_tag(rope, start, limit) {
+            Some((Tag::Open(open), _)) if open == name => depth += 1,
+            Some((Tag::Close(close), end)) if close == name => {
+                if depth == 0 {
+                    return Some((start, end));
+                }
+                depth -= 1;
+            }
+            _ => {}
+        }
+    }
+    None
+}
+
+fn paragraph_range(text: &impl RopeText, offset: usize, around: bool) -> Option<Range<usize>> {
+    // The empty line after a line ending at the end of the text isn't a line of its own.
+    let last_line = match text.last_line() {
+        line if line > 0 && text.offset_of_line(line) == text.len() => line - 1,
+        line => line,
+    };
+    let blank = |line: usize| text.is_line_whitespace(line);
+    // The first and last lines of the run of lines that are blank or not like `line`.
+    let run = |line: usize| {
+        let mut start = line;
+        while start > 0 && blank(start - 1) == blank(line) {
+            start -= 1;
+        }
+        let mut end = line;
+        while end < last_line && blank(end + 1) == blank(line) {
+            end += 1;
+        }
+        (start, end)
+    };
+
+    let line = text.line_of_offset(offset).min(last_line);
+    let (mut start, mut end) = run(line);
+    if around {
+        if end < last_line {
+            end = run(end + 1).1;
+        } else if start > 0 && !blank(line) {
+            // The last paragraph takes the blank lines before it instead.
+            start = run(start - 1).0;
+        }
+    }
+
+    Some(text.offset_of_line(start)..text.offset_of_line(end + 1))
+}
+
+#[cfg(test)]
+mod test {
+    use super::TextObject;
+    use crate::buffer::Buffer;
+
+    fn select(text: &str, offset: usize, object: &str) -> Option<String> {
+        let buffer = Buffer::new(text);
+        let mut chars = object.chars();
+        let around = chars.next() == Some('a');
+        let c = chars.next().unwrap();
+        let object = TextObject::from_char(c, around).unwrap();
+        if !matches!(c, ')' | ']' | '}' | '>' | 'b' | 'B') {
+            assert_eq!(object.to_char(), (c, around));
+        }
+        object
+            .range(&buffer, offset)
+            .map(|range| text[range].to_string())
+    }
+
+    #[test]
+    fn word() {
+        let text = "let foo_bar = (baz);";
+        assert_eq!(select(text, 5, "iw").as_deref(), Some("foo_bar"));
+        assert_eq!(select(text, 5, "aw").as_deref(), Some("foo_bar "));
+        assert_eq!(select(text, 11, "iw").as_deref(), Some(" "));
+        assert_eq!(select(text, 11, "aw").as_deref(), Some(" ="));
+        assert_eq!(select(text, 14, "iw").as_deref(), Some("("));
+        // Without whitespace after the word, the whitespace before it is selected.
+        assert_eq!(select(text, 17, "aw").as_deref(), Some("baz"));
+        assert_eq!(select("a bc", 3, "aw").as_deref(), Some(" bc"));
+        // The indentation isn't part of the first word.
+        assert_eq!(select("    foo", 5, "aw").as_deref(), Some("foo"));
+        assert_eq!(select("one\n\ntwo", 4, "iw"), None);
+    }
+
+    #[test]
+    fn pair() {
+        let text = "f(a, g(b), c)";
+        assert_eq!(select(text, 3, "i(").as_deref(), Some("a, g(b), c"));
+        assert_eq!(select(text, 7, "i(").as_deref(), Some("b"));
+        assert_eq!(select(text, 7, "ab").as_deref(), Some("(b)"));
+        // On the brackets themselves.
+        assert_eq!(select(text, 1, "a)").as_deref(), Some(&text[1..]));
+        assert_eq!(select(text, 12, "i(").as_deref(), Some("a, g(b), c"));
+        assert_eq!(select(text, 0, "i("), None);
+        assert_eq!(select(text, 3, "i["), None);
+
+        // The inner block of braces on lines of their own is linewise.
+        let text = "fn a() {\n    b();\n}\n";
+        assert_eq!(select(text, 13, "i{").as_deref(), Some("    b();\n"));
+        assert_eq!(select(text, 13, "a{").as_deref(), Some("{\n    b();\n}"));
+    }
+
+    #[test]
+    fn quote() {
+        let text = r#"say("hi \"you\"", 'x') "#;
+        assert_eq!(select(text, 6, "i\"").as_deref(), Some(r#"hi \"you\""#));
+        assert_eq!(select(text, 4, "a\"").as_deref(), Some(r#""hi \"you\"""#));
+        // The string after the offset, when it's not in one.
+        assert_eq!(select(text, 0, "i'").as_deref(), Some("x"));
+        assert_eq!(select("a 'b' c", 3, "a'").as_deref(), Some("'b' "));
+        assert_eq!(select("a 'b'", 3, "a'").as_deref(), Some(" 'b'"));
+        assert_eq!(select(text, 0, "i`"), None);
+    }
+
+    #[test]
+    fn tag() {
+        let text = "<div class=\"a\"><p>one</p><br/><p>two</p></div>";
+        assert_eq!(select(text, 20, "it").as_deref(), Some("one"));
+        assert_eq!(select(text, 20, "at").as_deref(), Some("<p>one</p>"));
+        assert_eq!(
+            select(text, 26, "it").as_deref(),
+            Some("<p>one</p><br/><p>two</p>")
+        );
+        assert_eq!(select("a < b <i>c</i>", 11, "it").as_deref(), Some("c"));
+        // On the closing tag, and in an element of the same name.
+        assert_eq!(select(text, 22, "at").as_deref(), Some("<p>one</p>"));
+        assert_eq!(
+            select("<b><b>x</b>y</b>", 6, "at").as_deref(),
+            Some("<b>x</b>")
+        );
+        assert_eq!(select("no tags", 2, "it"), None);
+    }
+
+    #[test]
+    fn paragraph() {
+        let text = "a\nb\n\n\nc\nd\n";
+        assert_eq!(select(text, 2, "ip").as_deref(), Some("a\nb\n"));
+        assert_eq!(select(text, 2, "ap").as_deref(), Some("a\nb\n\n\n"));
+        assert_eq!(select(text, 4, "ip").as_deref(), Some("\n\n"));
+        assert_eq!(select(text, 4, "ap").as_deref(), Some("\n\nc\nd\n"));
+        // The last paragraph takes the blank lines before it.
+        assert_eq!(select(text, 6, "ap").as_deref(), Some("\n\nc\nd\n"));
+        assert!(TextObject::Paragraph { around: false }.is_linewise());
+    }
+}
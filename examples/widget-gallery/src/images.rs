@@ -1,6 +1,6 @@
 use floem::{
     unit::UnitExt,
-    views::{img, svg, Decorators},
+    views::{img, svg, Decorators, HorizPosition, ObjectFit, VertPosition},
     IntoView,
 };
 
@@ -34,13 +34,15 @@ pub fn img_view() -> impl IntoView {
             form_item("JPG(resized):".to_string(), 120.0, move || {
                 img(move || sunflower.to_vec()).style(|s| s.width(320.px()).height(490.px()))
             }),
+            form_item("PNG(contain, top left):".to_string(), 120.0, move || {
+                img(move || ferris_png.to_vec())
+                    .object_fit(ObjectFit::Contain)
+                    .object_position(VertPosition::Top, HorizPosition::Left)
+                    .style(|s| s.width(230.px()).height(230.px()))
+            }),
             //TODO: support percentages for width/height
             //     img(move || ferris_png.to_vec()).style(|s| s.width(90.pct()).height(90.pct()))
             //
-            //TODO: object fit and object position
-            //     img(move || ferris_png.to_vec())
-            //     .object_fit(ObjectFit::Contain).object_position(VertPosition::Top, HorizPosition::Left))
-            //
         )
     })
 }
@@ -108,3 +108,134 @@ pub fn derive_state(input: TokenStream) -> TokenStream {
 
     TokenStream::from(expanded)
 }
+
+struct ValidatorArgs {
+    validators: Punctuated<syn::Expr, Token![,]>,
+}
+
+impl Parse for ValidatorArgs {
+    fn parse(input: syn::parse::ParseStream) -> syn::Result<Self> {
+        Ok(ValidatorArgs {
+            validators: input.parse_terminated(syn::Expr::parse, Token![,])?,
+        })
+    }
+}
+
+#[proc_macro_derive(FormFields, attributes(form_skip, form_validate, form_async_validate))]
+pub fn derive_form_fields(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    let struct_name = &input.ident;
+    let form_struct_name = format_ident!("{}Form", struct_name);
+
+    let fields = match &input.data {
+        Data::Struct(data) => match &data.fields {
+            Fields::Named(fields) => &fields.named,
+            _ => panic!("Only named fields are supported"),
+        },
+        _ => panic!("FormFields can only be derived for structs"),
+    };
+
+    let is_skipped = |f: &syn::Field| f.attrs.iter().any(|attr| attr.path().is_ident("form_skip"));
+
+    let mut errors = Vec::new();
+    let mut attr_exprs = |f: &syn::Field, name: &str| {
+        let mut exprs = Vec::new();
+        for attr in f.attrs.iter().filter(|attr| attr.path().is_ident(name)) {
+            match attr.parse_args::<ValidatorArgs>() {
+                Ok(args) => exprs.extend(args.validators),
+                Err(err) => errors.push(err),
+            }
+        }
+        exprs
+    };
+    // The synchronous and asynchronous validators of each field.
+    let validators = fields
+        .iter()
+        .map(|f| {
+            (
+                attr_exprs(f, "form_validate"),
+                attr_exprs(f, "form_async_validate"),
+            )
+        })
+        .collect::<Vec<_>>();
+    for f in fields {
+        if f.ident.as_ref().is_some_and(|name| name == "form") {
+            errors.push(syn::Error::new_spanned(
+                f,
+                format!(
+                    "`form` is the name of the form in `{form_struct_name}`, rename this field"
+                ),
+            ));
+        }
+    }
+    if let Some(error) = errors.into_iter().reduce(|mut all, error| {
+        all.combine(error);
+        all
+    }) {
+        return error.to_compile_error().into();
+    }
+
+    let form_fields = fields.iter().map(|f| {
+        let name = &f.ident;
+        let ty = &f.ty;
+        let vis = &f.vis;
+
+        if is_skipped(f) {
+            quote! { #vis #name: #ty }
+        } else {
+            quote! { #vis #name: ::floem::views::form::Field<#ty> }
+        }
+    });
+
+    let to_form_fields = fields.iter().zip(&validators).map(|(f, validators)| {
+        let name = &f.ident;
+
+        if is_skipped(f) {
+            quote! { #name: self.#name.clone() }
+        } else {
+            let (validators, async_validators) = validators;
+            quote! {
+                #name: form.field(self.#name.clone())
+                    #(.validator(#validators))*
+                    #(.async_validator(#async_validators))*
+            }
+        }
+    });
+
+    let to_value_fields = fields.iter().map(|f| {
+        let name = &f.ident;
+
+        if is_skipped(f) {
+            quote! { #name: self.#name.clone() }
+        } else {
+            quote! { #name: ::floem::reactive::SignalGet::get_untracked(&self.#name.value()) }
+        }
+    });
+
+    let expanded = quote! {
+        pub struct #form_struct_name {
+            pub form: ::floem::views::form::Form,
+            #(#form_fields,)*
+        }
+
+        impl #struct_name {
+            pub fn to_form(&self) -> #form_struct_name {
+                let form = ::floem::views::form::Form::new();
+                #form_struct_name {
+                    #(#to_form_fields,)*
+                    form,
+                }
+            }
+        }
+
+        impl #form_struct_name {
+            pub fn to_value(&self) -> #struct_name {
+                #struct_name {
+                    #(#to_value_fields,)*
+                }
+            }
+        }
+    };
+
+    TokenStream::from(expanded)
+}
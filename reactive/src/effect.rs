@@ -111,6 +111,75 @@ where
     run_initial_updater_effect(effect)
 }
 
+struct TrackerEffect<F>
+where
+    F: Fn(),
+{
+    id: Id,
+    on_change: F,
+    observers: RefCell<HashSet<Id>>,
+}
+
+impl<F> Drop for TrackerEffect<F>
+where
+    F: Fn(),
+{
+    fn drop(&mut self) {
+        self.id.dispose();
+    }
+}
+
+/// Tracks the Signals read by a function that can't be an Effect itself, for example
+/// because it borrows something that only lives for the duration of the call.
+///
+/// Each call to [`Tracker::track`] subscribes to the Signals read in that call, replacing the
+/// ones from the previous call. `on_change` runs when any of them changes, and the Tracker
+/// stays unsubscribed until `track` is called again.
+pub struct Tracker {
+    effect: Rc<dyn EffectTrait>,
+}
+
+impl Tracker {
+    pub fn new(on_change: impl Fn() + 'static) -> Self {
+        let id = Id::next();
+        id.set_scope();
+        Self {
+            effect: Rc::new(TrackerEffect {
+                id,
+                on_change,
+                observers: RefCell::new(HashSet::default()),
+            }),
+        }
+    }
+
+    /// Run `f`, subscribing to the Signals it reads.
+    pub fn track<T: 'static>(&self, f: impl FnOnce() -> T) -> T {
+        let effect_id = self.effect.id();
+        effect_id.dispose();
+
+        observer_clean_up(&self.effect);
+
+        let prev_effect = RUNTIME.with(|runtime| {
+            runtime
+                .current_effect
+                .borrow_mut()
+                .replace(self.effect.clone())
+        });
+
+        let effect_scope = Scope(effect_id);
+        let result = with_scope(effect_scope, || {
+            effect_scope.track();
+            f()
+        });
+
+        RUNTIME.with(|runtime| {
+            *runtime.current_effect.borrow_mut() = prev_effect;
+        });
+
+        result
+    }
+}
+
 /// Signals that's wrapped this untrack will not subscribe to any effect
 pub fn untrack<T>(f: impl FnOnce() -> T) -> T {
     let prev_effect = RUNTIME.with(|runtime| runtime.current_effect.borrow_mut().take());
@@ -248,6 +317,28 @@ where
     }
 }
 
+impl<F> EffectTrait for TrackerEffect<F>
+where
+    F: Fn(),
+{
+    fn id(&self) -> Id {
+        self.id
+    }
+
+    fn run(&self) -> bool {
+        (self.on_change)();
+        true
+    }
+
+    fn add_observer(&self, id: Id) {
+        self.observers.borrow_mut().insert(id);
+    }
+
+    fn clear_observers(&self) -> HashSet<Id> {
+        mem::take(&mut *self.observers.borrow_mut())
+    }
+}
+
 impl<T, I, C, U> EffectTrait for UpdaterEffect<T, I, C, U>
 where
     T: 'static,
@@ -20,7 +20,7 @@ mod write;
 
 pub use base::{create_base_signal, BaseSignal};
 pub use context::{provide_context, use_context};
-pub use effect::{batch, create_effect, create_stateful_updater, create_updater, untrack};
+pub use effect::{batch, create_effect, create_stateful_updater, create_updater, untrack, Tracker};
 pub use get_update_fn::{create_get_update, GetUpdateFn};
 pub use memo::{create_memo, Memo};
 pub use read::{ReadSignalValue, SignalGet, SignalRead, SignalTrack, SignalWith};
@@ -1,6 +1,8 @@
 use std::{cell::Cell, rc::Rc};
 
-use floem_reactive::{batch, create_effect, create_rw_signal, SignalTrack, SignalUpdate};
+use floem_reactive::{
+    batch, create_effect, create_rw_signal, SignalGet, SignalTrack, SignalUpdate, Tracker,
+};
 
 #[test]
 fn batch_simple() {
@@ -68,3 +70,37 @@ fn batch_batch() {
 
     assert_eq!(count.get(), 2);
 }
+
+#[test]
+fn tracker_retracks_on_each_call() {
+    let a = create_rw_signal(1);
+    let b = create_rw_signal(2);
+
+    let count = Rc::new(Cell::new(0));
+    let tracker = Tracker::new({
+        let count = count.clone();
+        move || count.set(count.get() + 1)
+    });
+
+    // A borrowed value, like the context passed to a paint function.
+    let mut sum = 0;
+    tracker.track(|| sum += a.get());
+    assert_eq!(sum, 1);
+    assert_eq!(count.get(), 0);
+
+    b.set(3);
+    assert_eq!(count.get(), 0);
+
+    a.set(2);
+    assert_eq!(count.get(), 1);
+
+    // Not tracking anything until the next call.
+    a.set(3);
+    assert_eq!(count.get(), 1);
+
+    tracker.track(|| b.track());
+    a.set(4);
+    assert_eq!(count.get(), 1);
+    b.set(4);
+    assert_eq!(count.get(), 2);
+}
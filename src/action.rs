@@ -19,6 +19,7 @@ use web_time::{Duration, Instant};
 
 use crate::{
     app::{add_app_update_event, AppUpdateEvent},
+    clock,
     id::ViewId,
     menu::Menu,
     update::{UpdateMessage, UPDATE_MESSAGES},
@@ -82,6 +83,8 @@ pub(crate) struct Timer {
     pub(crate) token: TimerToken,
     pub(crate) action: Box<dyn FnOnce(TimerToken)>,
     pub(crate) deadline: Instant,
+    /// Whether `deadline` is in system time, instead of the time of the animation clock.
+    pub(crate) system_time: bool,
 }
 
 /// A token associated with a timer
@@ -116,6 +119,9 @@ impl TimerToken {
 }
 
 /// Execute a callback after a specified duration
+///
+/// The duration is measured by the animation [clock](crate::clock), so timers slow down, speed up
+/// and pause along with animations.
 pub fn exec_after(duration: Duration, action: impl FnOnce(TimerToken) + 'static) -> TimerToken {
     let view = get_current_view();
     let action = move |token| {
@@ -126,17 +132,31 @@ pub fn exec_after(duration: Duration, action: impl FnOnce(TimerToken) + 'static)
     };
 
     let token = TimerToken::next();
-    let deadline = Instant::now() + duration;
+    let deadline = clock::now() + duration;
     add_app_update_event(AppUpdateEvent::RequestTimer {
         timer: Timer {
             token,
             action: Box::new(action),
             deadline,
+            system_time: false,
         },
     });
     token
 }
 
+/// Wakes up the event loop at `deadline` in system time, even while the animation
+/// [clock](crate::clock) is paused. Used by gestures, which are timed on the system time.
+pub(crate) fn wake_at(deadline: Instant) {
+    add_app_update_event(AppUpdateEvent::RequestTimer {
+        timer: Timer {
+            token: TimerToken::next(),
+            action: Box::new(|_| {}),
+            deadline,
+            system_time: true,
+        },
+    });
+}
+
 /// Debounce an action
 ///
 /// This tracks a signal and checks if the inner value has changed by checking it's hash and will run the action only once an **uninterrupted** duration has passed
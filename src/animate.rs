@@ -3,17 +3,23 @@
 //! Animations
 
 use crate::{
+    app::{add_app_update_event, AppUpdateEvent},
+    clock,
     easing::*,
-    style::{Style, StylePropRef},
+    style::{Style, StylePropRef, StylePropValue, Transition},
     unit::UnitExt,
     view_state::StackOffset,
     ViewId,
 };
 
 use std::any::Any;
+use std::cell::{Cell, RefCell};
 use std::rc::Rc;
 
-use floem_reactive::{create_updater, RwSignal, SignalGet, Trigger};
+use floem_reactive::{
+    create_effect, create_rw_signal, create_updater, untrack, ReadSignal, RwSignal, SignalGet,
+    SignalUpdate, Trigger,
+};
 use smallvec::{smallvec, SmallVec};
 #[cfg(not(target_arch = "wasm32"))]
 use std::time::{Duration, Instant};
@@ -777,7 +783,7 @@ impl Animation {
                 started_on,
                 elapsed,
             } => {
-                let duration = Instant::now() - *started_on;
+                let duration = clock::now() - *started_on;
                 Some(*elapsed + duration)
             }
             AnimState::PassFinished { elapsed, .. } => Some(*elapsed),
@@ -797,7 +803,7 @@ impl Animation {
                 started_on,
                 mut elapsed,
             } => {
-                let now = Instant::now();
+                let now = clock::now();
                 let duration = now - *started_on;
                 let og_elapsed = elapsed;
                 elapsed = duration;
@@ -828,7 +834,7 @@ impl Animation {
                 started_on,
                 mut elapsed,
             } => {
-                let now = Instant::now();
+                let now = clock::now();
                 let duration = now - *started_on;
                 elapsed = duration;
 
@@ -845,7 +851,7 @@ impl Animation {
             } => match self.repeat_mode {
                 RepeatMode::LoopForever => {
                     self.state = AnimState::PassInProgress {
-                        started_on: Instant::now(),
+                        started_on: clock::now(),
                         elapsed: Duration::ZERO,
                     }
                 }
@@ -862,7 +868,7 @@ impl Animation {
                         }
                     } else {
                         self.state = AnimState::PassInProgress {
-                            started_on: Instant::now(),
+                            started_on: clock::now(),
                             elapsed: Duration::ZERO,
                         }
                     }
@@ -888,7 +894,7 @@ impl Animation {
             AnimStateCommand::Resume => {
                 if let AnimState::Paused { elapsed } = &self.state {
                     self.state = AnimState::PassInProgress {
-                        started_on: Instant::now(),
+                        started_on: clock::now(),
                         elapsed: elapsed.unwrap_or(Duration::ZERO),
                     }
                 }
@@ -897,7 +903,7 @@ impl Animation {
                 self.folded_style.map.clear();
                 self.repeat_count = 0;
                 self.state = AnimState::PassInProgress {
-                    started_on: Instant::now(),
+                    started_on: clock::now(),
                     elapsed: Duration::ZERO,
                 }
             }
@@ -1186,3 +1192,255 @@ impl Animation {
             }
     }
 }
+
+/// Creates a signal that follows `source`, easing towards each new value with `transition`
+/// instead of jumping to it.
+///
+/// This is meant for values that aren't style props, such as the needle of a gauge or the bars
+/// of a chart in a custom painted view. Values that can't be interpolated change immediately.
+/// If `source` changes while a [`Spring`] is still moving, the new animation starts out with the
+/// spring's current velocity.
+///
+/// Frames are only requested while the value is moving, and time is read from the animation
+/// [clock](crate::clock).
+///
+/// ```rust
+/// # use std::time::Duration;
+/// # use floem::animate::create_animated_signal;
+/// # use floem::reactive::{create_rw_signal, SignalGet};
+/// # use floem::style::Transition;
+/// let target = create_rw_signal(0.0);
+/// let needle = create_animated_signal(
+///     move || target.get(),
+///     Transition::spring(Duration::from_millis(400)),
+/// );
+/// ```
+pub fn create_animated_signal<T: StylePropValue + 'static>(
+    source: impl Fn() -> T + 'static,
+    transition: Transition,
+) -> ReadSignal<T> {
+    let initial = untrack(&source);
+    let value = create_rw_signal(initial.clone());
+    let tween = Rc::new(RefCell::new(Tween::new(initial, transition)));
+    let ticking = Rc::new(Cell::new(false));
+
+    create_effect(move |_| {
+        let target = source();
+        tween.borrow_mut().retarget(target, clock::now());
+        if tween.borrow().is_moving() && !ticking.get() {
+            ticking.set(true);
+            schedule_tick(tween.clone(), ticking.clone(), value);
+        }
+    });
+
+    value.read_only()
+}
+
+thread_local! {
+    /// The callbacks waiting for the next frame, see [`request_animation_frame`].
+    static FRAME_CALLBACKS: RefCell<Vec<Box<dyn FnOnce()>>> = RefCell::new(Vec::new());
+}
+
+/// Runs `f` before the next frame is painted, requesting that frame. A callback that requests
+/// another frame runs once per frame, at the rate of the display.
+pub(crate) fn request_animation_frame(f: impl FnOnce() + 'static) {
+    FRAME_CALLBACKS.with_borrow_mut(|callbacks| callbacks.push(Box::new(f)));
+    add_app_update_event(AppUpdateEvent::RequestAnimationFrame);
+}
+
+/// Runs the callbacks waiting for this frame. The frames they request run on the next frame.
+pub(crate) fn run_animation_frame_callbacks() {
+    let callbacks = FRAME_CALLBACKS.with_borrow_mut(std::mem::take);
+    for callback in callbacks {
+        callback();
+    }
+}
+
+fn schedule_tick<T: StylePropValue + 'static>(
+    tween: Rc<RefCell<Tween<T>>>,
+    ticking: Rc<Cell<bool>>,
+    value: RwSignal<T>,
+) {
+    request_animation_frame(move || {
+        let (current, moving) = tween.borrow_mut().step(clock::now());
+        // The signal is gone once its scope is disposed, so there is nothing left to animate.
+        let alive = value.try_update(|value| *value = current).is_some();
+        if moving && alive {
+            schedule_tick(tween, ticking, value);
+        } else {
+            ticking.set(false);
+        }
+    });
+}
+
+#[derive(Debug)]
+struct ActiveTween<T> {
+    start: Instant,
+    from: T,
+    easing: Rc<dyn Easing>,
+}
+
+/// The state behind [`create_animated_signal`].
+#[derive(Debug)]
+struct Tween<T> {
+    transition: Transition,
+    target: T,
+    active: Option<ActiveTween<T>>,
+}
+
+impl<T: StylePropValue> Tween<T> {
+    fn new(target: T, transition: Transition) -> Self {
+        Self {
+            transition,
+            target,
+            active: None,
+        }
+    }
+
+    fn is_moving(&self) -> bool {
+        self.active.is_some()
+    }
+
+    /// The time since the start of `active`, as a fraction of the transition duration,
+    /// or `None` once it has finished.
+    fn time_percent(&self, active: &ActiveTween<T>, now: Instant) -> Option<f64> {
+        let time = now.saturating_duration_since(active.start);
+        let time_percent = time.as_secs_f64() / self.transition.duration.as_secs_f64();
+        (time < self.transition.duration || !active.easing.finished(time_percent))
+            .then_some(time_percent)
+    }
+
+    fn value_at(&self, now: Instant) -> Option<T> {
+        let active = self.active.as_ref()?;
+        let time_percent = self.time_percent(active, now)?;
+        active
+            .from
+            .interpolate(&self.target, active.easing.eval(time_percent))
+    }
+
+    /// Returns the value at `now`, and whether it is still moving.
+    fn step(&mut self, now: Instant) -> (T, bool) {
+        match self.value_at(now) {
+            Some(value) => (value, true),
+            None => {
+                self.active = None;
+                (self.target.clone(), false)
+            }
+        }
+    }
+
+    fn retarget(&mut self, target: T, now: Instant) {
+        if target == self.target {
+            return;
+        }
+        let current = self.value_at(now).unwrap_or_else(|| self.target.clone());
+        let easing = self
+            .carried_velocity(&current, &target, now)
+            .and_then(|velocity| self.transition.easing.with_initial_velocity(velocity))
+            .unwrap_or_else(|| self.transition.easing.clone());
+        self.active = Some(ActiveTween {
+            start: now,
+            from: current,
+            easing,
+        });
+        self.target = target;
+    }
+
+    /// The velocity of the running animation, in progress per transition duration, projected
+    /// onto the path from `current` to the new `target`.
+    fn carried_velocity(&self, current: &T, target: &T, now: Instant) -> Option<f64> {
+        let active = self.active.as_ref()?;
+        let time_percent = self.time_percent(active, now)?;
+        let velocity = active.easing.velocity(time_percent)?;
+        let progress = active.easing.eval(time_percent);
+
+        // The running animation moves along `a`, from its start to the old target, and the new
+        // one moves along `b`, from the current value to the new target. Only distances are
+        // known, so the dot product of `a` and `b` is found with the law of cosines.
+        let a = active.from.distance(&self.target)?;
+        let b = current.distance(target)?;
+        let start_to_new = active.from.distance(target)?;
+        let old_to_new = self.target.distance(target)?;
+        if b == 0.0 {
+            return None;
+        }
+        let a_dot_b = (a * a + start_to_new * start_to_new - old_to_new * old_to_new) / 2.0
+            - progress * a * a;
+        Some(velocity * a_dot_b / (b * b))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ms(ms: u64) -> Duration {
+        Duration::from_millis(ms)
+    }
+
+    #[test]
+    fn tween_reaches_target() {
+        let start = Instant::now();
+        let mut tween = Tween::new(0.0, Transition::linear(ms(100)));
+        tween.retarget(10.0, start);
+
+        assert_eq!(tween.step(start + ms(50)), (5.0, true));
+        assert_eq!(tween.step(start + ms(100)), (10.0, false));
+        assert!(!tween.is_moving());
+    }
+
+    #[test]
+    fn tween_retargets_from_current_value() {
+        let start = Instant::now();
+        let mut tween = Tween::new(0.0, Transition::linear(ms(100)));
+        tween.retarget(10.0, start);
+        tween.retarget(0.0, start + ms(50));
+
+        assert_eq!(tween.step(start + ms(50)), (5.0, true));
+        assert_eq!(tween.step(start + ms(100)), (2.5, true));
+    }
+
+    #[test]
+    fn tween_ignores_values_that_cant_be_interpolated() {
+        let start = Instant::now();
+        let mut tween = Tween::new(false, Transition::linear(ms(100)));
+        tween.retarget(true, start);
+
+        assert_eq!(tween.step(start + ms(10)), (true, false));
+    }
+
+    #[test]
+    fn spring_keeps_velocity_when_retargeted() {
+        let start = Instant::now();
+        let transition = Transition::spring(ms(1000));
+        let mut tween = Tween::new(0.0, transition);
+        tween.retarget(100.0, start);
+
+        let at = start + ms(100);
+        let before = tween.step(at - ms(1)).0;
+        let current = tween.step(at).0;
+        tween.retarget(200.0, at);
+        let after = tween.step(at + ms(1)).0;
+
+        // Continuing in the same direction with the same speed, not starting again from rest.
+        let speed_before = current - before;
+        let speed_after = after - current;
+        assert!(speed_before > 0.0);
+        assert!((speed_after - speed_before).abs() < speed_before * 0.1);
+    }
+
+    #[test]
+    fn spring_reverses_velocity_when_target_is_behind() {
+        let start = Instant::now();
+        let mut tween = Tween::new(0.0, Transition::spring(ms(1000)));
+        tween.retarget(100.0, start);
+
+        let at = start + ms(100);
+        let current = tween.step(at).0;
+        tween.retarget(0.0, at);
+        let after = tween.step(at + ms(1)).0;
+
+        // Still moving away from the new target, as it overshoots due to momentum.
+        assert!(after > current);
+    }
+}
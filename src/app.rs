@@ -79,6 +79,11 @@ pub(crate) enum AppUpdateEvent {
     CancelTimer {
         timer: TimerToken,
     },
+    /// The animation clock was paused, resumed, advanced or scaled.
+    ClockChanged,
+    /// A callback is waiting for the next frame, see
+    /// [`request_animation_frame`](crate::animate::request_animation_frame).
+    RequestAnimationFrame,
     #[cfg(any(target_os = "linux", target_os = "freebsd"))]
     MenuAction {
         window_id: WindowId,
@@ -175,6 +180,8 @@ impl Application {
                 floem_winit::event::Event::Resumed => {}
                 floem_winit::event::Event::AboutToWait => {}
                 floem_winit::event::Event::LoopExiting => {
+                    #[cfg(feature = "serde")]
+                    crate::persist::flush_persisted();
                     if let Some(action) = self.event_listener.as_ref() {
                         action(AppEvent::WillTerminate);
                     }
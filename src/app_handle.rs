@@ -22,7 +22,9 @@ use peniko::kurbo::{Point, Size};
 use crate::{
     action::{Timer, TimerToken},
     app::{AppUpdateEvent, UserEvent, APP_UPDATE_EVENTS},
+    clock,
     ext_event::EXT_EVENT_HANDLER,
+    gesture::GestureInput,
     inspector::Capture,
     profiler::{Profile, ProfileEvent},
     view::View,
@@ -95,6 +97,22 @@ impl ApplicationHandle {
                 AppUpdateEvent::CancelTimer { timer } => {
                     self.remove_timer(&timer);
                 }
+                AppUpdateEvent::ClockChanged => {
+                    for handle in self.window_handles.values() {
+                        handle.id.request_style_recursive();
+                        handle.schedule_repaint();
+                    }
+                    self.handle_updates_for_all_windows();
+                    // Stepping or advancing the clock can make timers due.
+                    self.handle_timer(event_loop);
+                }
+                // The callbacks of a paused clock wait for it to change, which requests a frame.
+                AppUpdateEvent::RequestAnimationFrame if !clock::is_paused() => {
+                    for handle in self.window_handles.values() {
+                        handle.schedule_repaint();
+                    }
+                }
+                AppUpdateEvent::RequestAnimationFrame => {}
                 AppUpdateEvent::CaptureWindow { window_id, capture } => {
                     capture.set(self.capture_window(window_id).map(Rc::new));
                 }
@@ -234,12 +252,31 @@ impl ApplicationHandle {
             WindowEvent::MouseInput { state, button, .. } => {
                 window_handle.mouse_input(button, state);
             }
-            WindowEvent::TouchpadMagnify { .. } => {}
-            WindowEvent::SmartMagnify { .. } => {}
-            WindowEvent::TouchpadRotate { .. } => {}
+            WindowEvent::TouchpadMagnify { delta, phase, .. } => {
+                window_handle.gesture_input(GestureInput::Magnify {
+                    pos: window_handle.cursor_position,
+                    delta,
+                    phase: phase.into(),
+                });
+            }
+            WindowEvent::SmartMagnify { .. } => {
+                window_handle.gesture_input(GestureInput::SmartMagnify {
+                    pos: window_handle.cursor_position,
+                });
+            }
+            WindowEvent::TouchpadRotate { delta, phase, .. } => {
+                // winit reports counterclockwise degrees
+                window_handle.gesture_input(GestureInput::Rotate {
+                    pos: window_handle.cursor_position,
+                    delta: -(delta as f64).to_radians(),
+                    phase: phase.into(),
+                });
+            }
             WindowEvent::TouchpadPressure { .. } => {}
             WindowEvent::AxisMotion { .. } => {}
-            WindowEvent::Touch(_) => {}
+            WindowEvent::Touch(touch) => {
+                window_handle.touch(touch);
+            }
             WindowEvent::ScaleFactorChanged { scale_factor, .. } => {
                 window_handle.scale(scale_factor);
             }
@@ -296,6 +333,8 @@ impl ApplicationHandle {
             mac_os_config,
             web_config,
             font_embolden,
+            #[cfg(feature = "serde")]
+            persist_key,
         }: WindowConfig,
     ) {
         let logical_size = size.map(|size| LogicalSize::new(size.width, size.height));
@@ -420,7 +459,8 @@ impl ApplicationHandle {
             return;
         };
         let window_id = window.id();
-        let window_handle = WindowHandle::new(
+        #[allow(unused_mut)]
+        let mut window_handle = WindowHandle::new(
             window,
             event_proxy,
             view_fn,
@@ -429,6 +469,10 @@ impl ApplicationHandle {
             logical_size,
             font_embolden,
         );
+        #[cfg(feature = "serde")]
+        {
+            window_handle.persist_key = persist_key;
+        }
         self.window_handles.insert(window_id, window_handle);
     }
 
@@ -486,18 +530,32 @@ impl ApplicationHandle {
             return;
         }
 
-        let deadline = self.timers.values().map(|timer| timer.deadline).min();
-        if let Some(deadline) = deadline {
-            event_loop.set_control_flow(ControlFlow::WaitUntil(deadline));
+        // Most timer deadlines are in animation clock time, which doesn't move while it's paused.
+        let deadline = self
+            .timers
+            .values()
+            .filter_map(|timer| {
+                if timer.system_time {
+                    Some(timer.deadline)
+                } else {
+                    clock::to_real_instant(timer.deadline)
+                }
+            })
+            .min();
+        match deadline {
+            Some(deadline) => event_loop.set_control_flow(ControlFlow::WaitUntil(deadline)),
+            None => event_loop.set_control_flow(ControlFlow::Wait),
         }
     }
 
     pub(crate) fn handle_timer(&mut self, event_loop: &EventLoopWindowTarget<UserEvent>) {
-        let now = Instant::now();
+        let now = clock::now();
+        let system_now = Instant::now();
         let tokens: Vec<TimerToken> = self
             .timers
             .iter()
             .filter_map(|(token, timer)| {
+                let now = if timer.system_time { system_now } else { now };
                 if timer.deadline <= now {
                     Some(*token)
                 } else {
@@ -511,6 +569,9 @@ impl ApplicationHandle {
                     (timer.action)(token);
                 }
             }
+            for handle in self.window_handles.values_mut() {
+                handle.poll_gestures();
+            }
             self.handle_updates_for_all_windows();
         }
         self.fire_timer(event_loop);
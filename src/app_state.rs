@@ -9,6 +9,7 @@ use crate::{
     event::{Event, EventListener},
     id::ViewId,
     inspector::CaptureState,
+    keyboard::HotKey,
     menu::Menu,
     responsive::{GridBreakpoints, ScreenSizeBp},
     style::{CursorStyle, Style, StyleClassRef, StyleSelector},
@@ -31,6 +32,7 @@ pub struct AppState {
     pub(crate) request_compute_layout: bool,
     pub(crate) request_paint: bool,
     pub(crate) disabled: HashSet<ViewId>,
+    pub(crate) invalid: HashSet<ViewId>,
     pub(crate) keyboard_navigable: HashSet<ViewId>,
     pub(crate) draggable: HashSet<ViewId>,
     pub(crate) dragging: Option<DragState>,
@@ -46,6 +48,8 @@ pub struct AppState {
     pub(crate) last_cursor: CursorIcon,
     pub(crate) keyboard_navigation: bool,
     pub(crate) window_menu: HashMap<usize, Box<dyn Fn()>>,
+    /// The shortcuts of the window menu items, with the ids of their actions.
+    pub(crate) window_menu_keys: Vec<(HotKey, usize)>,
     pub(crate) context_menu: HashMap<usize, Box<dyn Fn()>>,
 
     /// This is set if we're currently capturing the window for the inspector.
@@ -67,6 +71,7 @@ impl AppState {
             request_paint: false,
             request_compute_layout: false,
             disabled: HashSet::new(),
+            invalid: HashSet::new(),
             keyboard_navigable: HashSet::new(),
             draggable: HashSet::new(),
             dragging: None,
@@ -79,6 +84,7 @@ impl AppState {
             keyboard_navigation: false,
             grid_bps: GridBreakpoints::default(),
             window_menu: HashMap::new(),
+            window_menu_keys: Vec::new(),
             context_menu: HashMap::new(),
             capture: None,
         }
@@ -115,6 +121,7 @@ impl AppState {
         let _ = taffy.remove(node);
         id.remove();
         self.disabled.remove(&id);
+        self.invalid.remove(&id);
         self.keyboard_navigable.remove(&id);
         self.draggable.remove(&id);
         self.dragging_over.remove(&id);
@@ -145,6 +152,10 @@ impl AppState {
         self.disabled.contains(id)
     }
 
+    pub fn is_invalid(&self, id: &ViewId) -> bool {
+        self.invalid.contains(id)
+    }
+
     pub fn is_focused(&self, id: &ViewId) -> bool {
         self.focus.map(|f| &f == id).unwrap_or(false)
     }
@@ -288,6 +299,30 @@ impl AppState {
             || (selector_kind == StyleSelector::Dragging && view_state.dragging_style.is_some())
     }
 
+    pub(crate) fn update_window_menu(&mut self, menu: &mut Menu) {
+        if let Some(action) = menu.item.action.take() {
+            self.window_menu.insert(menu.item.id as usize, action);
+        }
+        for child in menu.children.iter_mut() {
+            match child {
+                crate::menu::MenuEntry::Separator => {}
+                crate::menu::MenuEntry::Item(item) => {
+                    if let Some(action) = item.action.take() {
+                        self.window_menu.insert(item.id as usize, action);
+                        if let (Some(key), true) = (&item.key, item.enabled) {
+                            self.window_menu_keys.push((key.clone(), item.id as usize));
+                        }
+                    }
+                }
+                crate::menu::MenuEntry::SubMenu(m) => {
+                    if m.item.enabled {
+                        self.update_window_menu(m);
+                    }
+                }
+            }
+        }
+    }
+
     pub(crate) fn update_context_menu(&mut self, menu: &mut Menu) {
         if let Some(action) = menu.item.action.take() {
             self.context_menu.insert(menu.item.id as usize, action);
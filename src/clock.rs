@@ -0,0 +1,206 @@
+#![deny(missing_docs)]
+
+//! The clock that drives animations.
+//!
+//! [Animations](crate::animate::Animation), style [transitions](crate::style::Transition),
+//! layout animations and [`exec_after`](crate::action::exec_after) timers all read the current
+//! time from this clock instead of from `Instant::now()`. By default the clock follows the system
+//! time, but it can be slowed down, sped up, paused and stepped frame by frame, which is useful
+//! when debugging an animation. The inspector exposes these controls. Gestures like a long press
+//! are timed on the system time.
+//!
+//! In tests the clock can be driven manually by pausing it and advancing it by exact durations:
+//! ```rust
+//! use std::time::Duration;
+//! use floem::clock;
+//!
+//! clock::pause();
+//! let start = clock::now();
+//! clock::advance(Duration::from_millis(100));
+//! assert_eq!(clock::now() - start, Duration::from_millis(100));
+//! clock::reset();
+//! ```
+//!
+//! The clock is per thread, like the rest of Floem's UI state.
+
+use std::cell::RefCell;
+
+#[cfg(not(target_arch = "wasm32"))]
+use std::time::{Duration, Instant};
+#[cfg(target_arch = "wasm32")]
+use web_time::{Duration, Instant};
+
+use crate::app::{add_app_update_event, AppUpdateEvent};
+
+/// The amount of time that [`step_frame`] advances the clock by.
+pub const FRAME_DURATION: Duration = Duration::from_nanos(16_666_667);
+
+struct Clock {
+    /// The system time at which `clock_anchor` was taken.
+    real_anchor: Instant,
+    /// The clock time at `real_anchor`.
+    clock_anchor: Instant,
+    time_scale: f64,
+    paused: bool,
+}
+
+impl Clock {
+    fn new() -> Self {
+        let now = Instant::now();
+        Self {
+            real_anchor: now,
+            clock_anchor: now,
+            time_scale: 1.0,
+            paused: false,
+        }
+    }
+
+    fn now(&self) -> Instant {
+        if self.paused {
+            self.clock_anchor
+        } else {
+            let real_elapsed = Instant::now().saturating_duration_since(self.real_anchor);
+            self.clock_anchor + real_elapsed.mul_f64(self.time_scale)
+        }
+    }
+
+    /// Move the anchors to the current time so the time scale or paused state can change
+    /// without the clock jumping.
+    fn rebase(&mut self) {
+        self.clock_anchor = self.now();
+        self.real_anchor = Instant::now();
+    }
+
+    fn to_real(&self, instant: Instant) -> Option<Instant> {
+        if self.paused {
+            return None;
+        }
+        let clock_remaining = instant.saturating_duration_since(self.clock_anchor);
+        Some(self.real_anchor + clock_remaining.div_f64(self.time_scale))
+    }
+}
+
+thread_local! {
+    static CLOCK: RefCell<Clock> = RefCell::new(Clock::new());
+}
+
+fn update_clock(f: impl FnOnce(&mut Clock)) {
+    CLOCK.with_borrow_mut(|clock| {
+        clock.rebase();
+        f(clock);
+    });
+    // Timers need to be rescheduled, and running animations need a new frame.
+    add_app_update_event(AppUpdateEvent::ClockChanged);
+}
+
+/// The current time of the animation clock.
+pub fn now() -> Instant {
+    CLOCK.with_borrow(|clock| clock.now())
+}
+
+/// Convert a time of the animation clock to the system time at which the clock will reach it.
+///
+/// Returns `None` while the clock is paused.
+pub(crate) fn to_real_instant(instant: Instant) -> Option<Instant> {
+    CLOCK.with_borrow(|clock| clock.to_real(instant))
+}
+
+/// The speed of the clock relative to the system time.
+pub fn time_scale() -> f64 {
+    CLOCK.with_borrow(|clock| clock.time_scale)
+}
+
+/// Set the speed of the clock relative to the system time.
+///
+/// A scale of `0.1` makes every animation run ten times slower.
+///
+/// # Panics
+/// Panics if `scale` is not a finite, positive number.
+pub fn set_time_scale(scale: f64) {
+    assert!(
+        scale.is_finite() && scale > 0.0,
+        "the time scale must be positive, got {scale}"
+    );
+    update_clock(|clock| clock.time_scale = scale);
+}
+
+/// Stop the clock. Animations and timers freeze until [`resume`] is called or the clock is
+/// moved with [`advance`] or [`step_frame`], and no frames are requested for them meanwhile.
+pub fn pause() {
+    update_clock(|clock| clock.paused = true);
+}
+
+/// Start a paused clock again from the time it was paused at.
+pub fn resume() {
+    update_clock(|clock| clock.paused = false);
+}
+
+/// Returns `true` if the clock is paused.
+pub fn is_paused() -> bool {
+    CLOCK.with_borrow(|clock| clock.paused)
+}
+
+/// Move the clock forward by `duration`, regardless of the time scale.
+///
+/// This is mostly useful while the clock is paused, to drive animations manually.
+pub fn advance(duration: Duration) {
+    update_clock(|clock| clock.clock_anchor += duration);
+}
+
+/// Move the clock forward by a single frame. See [`FRAME_DURATION`].
+pub fn step_frame() {
+    advance(FRAME_DURATION);
+}
+
+/// Make the clock follow the system time again at normal speed.
+pub fn reset() {
+    update_clock(|clock| *clock = Clock::new());
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn paused_clock_only_moves_when_advanced() {
+        pause();
+        let start = now();
+        std::thread::sleep(Duration::from_millis(5));
+        assert_eq!(now(), start);
+
+        advance(Duration::from_millis(250));
+        assert_eq!(now() - start, Duration::from_millis(250));
+
+        step_frame();
+        assert_eq!(now() - start, Duration::from_millis(250) + FRAME_DURATION);
+        assert_eq!(to_real_instant(now()), None);
+        reset();
+    }
+
+    #[test]
+    fn resume_continues_from_paused_time() {
+        pause();
+        let paused_at = now();
+        std::thread::sleep(Duration::from_millis(20));
+        resume();
+        assert!(now() - paused_at < Duration::from_millis(20));
+        assert!(!is_paused());
+        reset();
+    }
+
+    #[test]
+    fn time_scale_slows_the_clock() {
+        set_time_scale(0.1);
+        let start = now();
+        let real_start = Instant::now();
+        std::thread::sleep(Duration::from_millis(50));
+        let elapsed = now() - start;
+        let real_elapsed = Instant::now() - real_start;
+        assert!(elapsed <= real_elapsed.mul_f64(0.1) + Duration::from_millis(1));
+
+        let deadline = now() + Duration::from_millis(10);
+        let real_deadline = to_real_instant(deadline).unwrap();
+        assert!(real_deadline - Instant::now() > Duration::from_millis(90));
+        reset();
+    }
+}
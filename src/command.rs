@@ -0,0 +1,328 @@
+//! Application-wide commands.
+//!
+//! A [`Command`] is a named action with a title, an optional default [`KeyBinding`] and an
+//! optional predicate that tells whether it can currently run. Once registered, a command can be
+//! run from anywhere by its id: its key binding runs it when the focused view doesn't handle the
+//! key, [`MenuItem::command`](crate::menu::MenuItem::command) and
+//! [`Button::command`](crate::views::Button::command) run it when clicked, and the
+//! [`command_palette`](crate::views::command_palette) lists the commands so they can be searched
+//! and run.
+//!
+//! ```rust
+//! # use floem::command::{run_command, Command};
+//! # use floem::reactive::{create_rw_signal, SignalGet};
+//! let dirty = create_rw_signal(false);
+//! Command::new("file.save", "Save", move || println!("saving"))
+//!     .category("File")
+//!     .key("Primary+S")
+//!     .enabled(move || dirty.get())
+//!     .register();
+//!
+//! // Nothing happens, because the command isn't enabled.
+//! assert!(!run_command("file.save"));
+//! ```
+//!
+//! Key bindings use the syntax of [`KeyBinding::parse`], so a binding can also be a sequence of
+//! shortcuts, like `Ctrl+K Ctrl+S`.
+
+use std::{cell::RefCell, rc::Rc};
+
+use crate::keyboard::{HotKey, Key, KeyBinding, KeyEvent, NamedKey};
+
+/// A named action that can be run from key bindings, menus, buttons and the command palette.
+/// See the [module documentation](self).
+#[derive(Clone)]
+pub struct Command {
+    id: String,
+    title: String,
+    category: Option<String>,
+    key: Option<KeyBinding>,
+    enabled: Option<Rc<dyn Fn() -> bool>>,
+    handler: Rc<dyn Fn()>,
+}
+
+impl Command {
+    /// Creates a command that runs `handler`. The `id` identifies the command when it's run or
+    /// rebound, and the `title` is what menus and the command palette show.
+    pub fn new(
+        id: impl Into<String>,
+        title: impl Into<String>,
+        handler: impl Fn() + 'static,
+    ) -> Self {
+        Self {
+            id: id.into(),
+            title: title.into(),
+            category: None,
+            key: None,
+            enabled: None,
+            handler: Rc::new(handler),
+        }
+    }
+
+    /// Sets the category that the command palette shows before the title, like `File`.
+    pub fn category(mut self, category: impl Into<String>) -> Self {
+        self.category = Some(category.into());
+        self
+    }
+
+    /// Sets the default key binding, such as `Ctrl+Shift+P` or `Ctrl+K Ctrl+S`.
+    ///
+    /// A binding that can't be parsed is ignored.
+    pub fn key(mut self, key: &str) -> Self {
+        self.key = KeyBinding::parse(key);
+        self
+    }
+
+    /// Sets a predicate that tells whether the command can currently run. The predicate is
+    /// tracked by reactive views, like a [`Button`](crate::views::Button) that runs the command.
+    pub fn enabled(mut self, enabled: impl Fn() -> bool + 'static) -> Self {
+        self.enabled = Some(Rc::new(enabled));
+        self
+    }
+
+    /// Adds the command to the registry, replacing any command with the same id.
+    pub fn register(self) {
+        COMMANDS.with_borrow_mut(|commands| {
+            match commands.iter_mut().find(|command| command.id == self.id) {
+                Some(command) => *command = self,
+                None => commands.push(self),
+            }
+        });
+    }
+
+    pub fn id(&self) -> &str {
+        &self.id
+    }
+
+    pub fn title(&self) -> &str {
+        &self.title
+    }
+
+    pub fn category_name(&self) -> Option<&str> {
+        self.category.as_deref()
+    }
+
+    pub fn key_binding(&self) -> Option<&KeyBinding> {
+        self.key.as_ref()
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.enabled.as_ref().map_or(true, |enabled| enabled())
+    }
+
+    /// Runs the command if it's enabled. Returns `true` if it was run.
+    pub fn run(&self) -> bool {
+        if !self.is_enabled() {
+            return false;
+        }
+        (self.handler)();
+        true
+    }
+}
+
+thread_local! {
+    /// The registered commands, in the order they were registered.
+    static COMMANDS: RefCell<Vec<Command>> = const { RefCell::new(Vec::new()) };
+    /// The shortcuts that have been pressed so far of a key binding with several shortcuts.
+    static PENDING_KEYS: RefCell<Vec<HotKey>> = const { RefCell::new(Vec::new()) };
+}
+
+/// The registered command with the id.
+pub fn command(id: &str) -> Option<Command> {
+    COMMANDS.with_borrow(|commands| commands.iter().find(|command| command.id == id).cloned())
+}
+
+/// All the registered commands, in the order they were registered.
+pub fn commands() -> Vec<Command> {
+    COMMANDS.with_borrow(|commands| commands.clone())
+}
+
+/// Runs the command with the id if it's registered and enabled. Returns `true` if it was run.
+pub fn run_command(id: &str) -> bool {
+    command(id).is_some_and(|command| command.run())
+}
+
+/// Returns `true` if the command with the id is registered and enabled.
+pub fn is_command_enabled(id: &str) -> bool {
+    command(id).is_some_and(|command| command.is_enabled())
+}
+
+/// Removes the command with the id from the registry.
+pub fn unregister_command(id: &str) {
+    COMMANDS.with_borrow_mut(|commands| commands.retain(|command| command.id != id));
+}
+
+/// Replaces the key binding of the command with the id, or removes it when `key` is `None`.
+pub fn rebind_command(id: &str, key: Option<KeyBinding>) {
+    COMMANDS.with_borrow_mut(|commands| {
+        if let Some(command) = commands.iter_mut().find(|command| command.id == id) {
+            command.key = key;
+        }
+    });
+}
+
+/// Returns `true` if the first shortcuts of a key binding have been pressed, and the next key
+/// press should go to the command registry before the focused view.
+pub(crate) fn has_pending_keys() -> bool {
+    PENDING_KEYS.with_borrow(|pending| !pending.is_empty())
+}
+
+/// Runs the command bound to the key press, if there is one. Returns `true` if the key press was
+/// used, either to run a command or as part of a key binding with several shortcuts.
+pub(crate) fn dispatch_command_key(event: &KeyEvent) -> bool {
+    if matches!(
+        event.key.logical_key,
+        Key::Named(
+            NamedKey::Shift
+                | NamedKey::Control
+                | NamedKey::Alt
+                | NamedKey::AltGraph
+                | NamedKey::Meta
+                | NamedKey::Super
+        )
+    ) {
+        // Pressing a modifier doesn't break a key binding that's being typed.
+        return has_pending_keys();
+    }
+    dispatch_hotkey(HotKey::from(event))
+}
+
+fn dispatch_hotkey(pressed: HotKey) -> bool {
+    let mut pending = PENDING_KEYS.take();
+    pending.push(pressed.clone());
+
+    // The handlers and predicates may use the registry, so it's not borrowed while they run.
+    let commands = commands();
+    let mut is_prefix = false;
+    for command in &commands {
+        let Some(KeyBinding(keys)) = &command.key else {
+            continue;
+        };
+        if !keys.starts_with(&pending) || !command.is_enabled() {
+            continue;
+        }
+        if keys.len() == pending.len() {
+            (command.handler)();
+            return true;
+        }
+        is_prefix = true;
+    }
+
+    if is_prefix {
+        PENDING_KEYS.set(pending);
+        true
+    } else if pending.len() > 1 {
+        // The key binding that was being typed doesn't continue with this shortcut, but a new
+        // one might start with it.
+        dispatch_hotkey(pressed)
+    } else {
+        false
+    }
+}
+
+/// Scores how well `text` matches the `query` typed in the command palette, or returns `None`
+/// if the characters of the query don't appear in the text in order. Case is ignored.
+///
+/// Consecutive matches and matches at the start of words score higher.
+pub(crate) fn fuzzy_score(query: &str, text: &str) -> Option<i64> {
+    let mut query = query
+        .chars()
+        .filter(|c| !c.is_whitespace())
+        .flat_map(char::to_lowercase)
+        .peekable();
+    let mut score = 0;
+    let mut prev: Option<char> = None;
+    let mut prev_matched = false;
+    for c in text.chars() {
+        let Some(q) = query.peek() else {
+            break;
+        };
+        let is_match = c.to_lowercase().eq(std::iter::once(*q));
+        if is_match {
+            score += 1;
+            if prev_matched {
+                score += 4;
+            }
+            if prev.map_or(true, |prev| !prev.is_alphanumeric()) {
+                score += 8;
+            }
+            query.next();
+        } else if score > 0 {
+            // Gaps after the first match cost a little.
+            score -= 1;
+        }
+        prev_matched = is_match;
+        prev = Some(c);
+    }
+    query.peek().is_none().then_some(score)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cell::Cell;
+
+    use super::*;
+    use crate::keyboard::Modifiers;
+
+    #[test]
+    fn key_bindings_run_commands() {
+        let runs = Rc::new(Cell::new(0));
+        let enabled = Rc::new(Cell::new(true));
+        {
+            let runs = runs.clone();
+            let enabled = enabled.clone();
+            Command::new("test.save", "Save", move || runs.set(runs.get() + 1))
+                .key("Ctrl+K Ctrl+S")
+                .enabled(move || enabled.get())
+                .register();
+        }
+        let ctrl = |c: &str| HotKey::new(Modifiers::CONTROL, Key::Character(c.into()));
+
+        assert!(dispatch_hotkey(ctrl("k")));
+        assert!(has_pending_keys());
+        assert!(dispatch_hotkey(ctrl("s")));
+        assert!(!has_pending_keys());
+        assert_eq!(runs.get(), 1);
+
+        // A broken sequence isn't run, and the key press that broke it isn't used.
+        assert!(dispatch_hotkey(ctrl("k")));
+        assert!(!dispatch_hotkey(ctrl("x")));
+        assert!(!has_pending_keys());
+        assert_eq!(runs.get(), 1);
+
+        // The key press that broke a sequence can start a new one.
+        assert!(dispatch_hotkey(ctrl("k")));
+        assert!(dispatch_hotkey(ctrl("k")));
+        assert!(dispatch_hotkey(ctrl("s")));
+        assert_eq!(runs.get(), 2);
+
+        enabled.set(false);
+        assert!(!dispatch_hotkey(ctrl("k")));
+        assert!(!run_command("test.save"));
+        enabled.set(true);
+        assert!(run_command("test.save"));
+        assert_eq!(runs.get(), 3);
+
+        rebind_command("test.save", KeyBinding::parse("F2"));
+        assert!(!dispatch_hotkey(ctrl("k")));
+        assert!(dispatch_hotkey(HotKey::parse("F2").unwrap()));
+        assert_eq!(runs.get(), 4);
+
+        unregister_command("test.save");
+        assert!(!run_command("test.save"));
+        assert!(commands().is_empty());
+    }
+
+    #[test]
+    fn fuzzy_scores() {
+        assert_eq!(fuzzy_score("xyz", "Save File"), None);
+        assert!(fuzzy_score("", "Save File").is_some());
+        assert!(fuzzy_score("sf", "Save File").is_some());
+        assert!(fuzzy_score("fs", "Save File").is_none());
+
+        // Word starts and consecutive matches rank higher than scattered matches.
+        assert!(fuzzy_score("sf", "Save File") > fuzzy_score("sf", "Transform"));
+        assert!(fuzzy_score("save", "Save File") > fuzzy_score("save", "Sample Leave"));
+        assert!(fuzzy_score("file", "File: Save") > fuzzy_score("file", "Profile"));
+    }
+}
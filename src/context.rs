@@ -22,6 +22,7 @@ use crate::view_state::IsHiddenState;
 use crate::{
     action::{exec_after, show_context_menu},
     app_state::AppState,
+    clock,
     event::{Event, EventListener, EventPropagation},
     id::ViewId,
     inspector::CaptureState,
@@ -350,7 +351,7 @@ impl<'a> EventCx<'a> {
                         self.app_state.dragging.as_mut().filter(|d| d.id == view_id)
                     {
                         let dragging_id = dragging.id;
-                        dragging.released_at = Some(Instant::now());
+                        dragging.released_at = Some(clock::now());
                         self.app_state.request_paint(view_id);
                         dragging_id.apply_event(&EventListener::DragEnd, &event);
                     }
@@ -504,6 +505,7 @@ pub struct InteractionState {
     pub(crate) is_hovered: bool,
     pub(crate) is_selected: bool,
     pub(crate) is_disabled: bool,
+    pub(crate) is_invalid: bool,
     pub(crate) is_focused: bool,
     pub(crate) is_clicking: bool,
     pub(crate) using_keyboard_navigation: bool,
@@ -518,8 +520,10 @@ pub struct StyleCx<'a> {
     pub(crate) now: Instant,
     saved_disabled: Vec<bool>,
     saved_selected: Vec<bool>,
+    saved_invalid: Vec<bool>,
     disabled: bool,
     selected: bool,
+    invalid: bool,
 }
 
 impl<'a> StyleCx<'a> {
@@ -530,11 +534,13 @@ impl<'a> StyleCx<'a> {
             current: Default::default(),
             direct: Default::default(),
             saved: Default::default(),
-            now: Instant::now(),
+            now: clock::now(),
             saved_disabled: Default::default(),
             saved_selected: Default::default(),
+            saved_invalid: Default::default(),
             disabled: false,
             selected: false,
+            invalid: false,
         }
     }
 
@@ -548,6 +554,7 @@ impl<'a> StyleCx<'a> {
             is_selected: self.selected,
             is_hovered: self.app_state.is_hovered(id),
             is_disabled: self.app_state.is_disabled(id),
+            is_invalid: self.app_state.is_invalid(id),
             is_focused: self.app_state.is_focused(id),
             is_clicking: self.app_state.is_clicking(id),
             using_keyboard_navigation: self.app_state.keyboard_navigation,
@@ -588,6 +595,8 @@ impl<'a> StyleCx<'a> {
         let mut view_interact_state = self.get_interact_state(&view_id);
         view_interact_state.is_disabled |= self.disabled;
         self.disabled = view_interact_state.is_disabled;
+        view_interact_state.is_invalid |= self.invalid;
+        self.invalid = view_interact_state.is_invalid;
         let mut new_frame = self.app_state.compute_style(
             view_id,
             view_style,
@@ -676,12 +685,14 @@ impl<'a> StyleCx<'a> {
         self.saved.push(self.current.clone());
         self.saved_disabled.push(self.disabled);
         self.saved_selected.push(self.selected);
+        self.saved_invalid.push(self.invalid);
     }
 
     pub fn restore(&mut self) {
         self.current = self.saved.pop().unwrap_or_default();
         self.disabled = self.saved_disabled.pop().unwrap_or_default();
         self.selected = self.saved_selected.pop().unwrap_or_default();
+        self.invalid = self.saved_invalid.pop().unwrap_or_default();
     }
 
     pub fn get_prop<P: StyleProp>(&self, _prop: P) -> Option<P::Type> {
@@ -795,6 +806,17 @@ impl<'a> ComputeLayoutCx<'a> {
             self.viewport = self.viewport.intersect(this_viewport);
         }
 
+        let started_layout_animation = view_state
+            .borrow_mut()
+            .layout_animation
+            .as_mut()
+            .is_some_and(|animation| {
+                animation.update(size.to_rect().with_origin(origin), clock::now())
+            });
+        if started_layout_animation {
+            self.app_state.request_paint(id);
+        }
+
         let window_origin = origin + self.window_origin.to_vec2() - this_viewport_origin;
         self.window_origin = window_origin;
         {
@@ -1015,7 +1037,9 @@ impl<'a> PaintCx<'a> {
                 let mut offset_scale = None;
                 if let Some(released_at) = dragging.released_at {
                     const LIMIT: f64 = 300.0;
-                    let elapsed = released_at.elapsed().as_millis() as f64;
+                    let elapsed = clock::now()
+                        .saturating_duration_since(released_at)
+                        .as_millis() as f64;
                     if elapsed < LIMIT {
                         offset_scale = Some(1.0 - elapsed / LIMIT);
                         exec_after(Duration::from_millis(8), move |_| {
@@ -1048,7 +1072,7 @@ impl<'a> PaintCx<'a> {
                         view_style_props.read_explicit(
                             &style,
                             &style,
-                            &Instant::now(),
+                            &clock::now(),
                             &mut _new_frame,
                         );
                     }
@@ -1119,15 +1143,52 @@ impl<'a> PaintCx<'a> {
         }
     }
 
+    /// Apply `transform` on top of the current transform, to scale, rotate or move everything
+    /// painted afterwards. Call [`save`](Self::save) before and [`restore`](Self::restore)
+    /// after to undo it.
+    pub fn apply_transform(&mut self, transform: Affine) {
+        self.transform = self.transform * transform;
+        self.paint_state.renderer_mut().transform(self.transform);
+        if let Some(rect) = self.clip.as_mut() {
+            let radius = rect.radii();
+            *rect = transform
+                .inverse()
+                .transform_rect_bbox(rect.rect())
+                .to_rounded_rect(radius);
+        }
+    }
+
     pub fn transform(&mut self, id: ViewId) -> Size {
         if let Some(layout) = id.get_layout() {
+            let view_state = id.state();
+
+            // A view with `animate_layout` is painted at its animated box until it catches up
+            // with the box computed by layout.
+            let animated_rect = view_state
+                .borrow_mut()
+                .layout_animation
+                .as_mut()
+                .and_then(|animation| animation.step(clock::now()));
+            let layout_size = Size::new(layout.size.width as f64, layout.size.height as f64);
+            let (animation_offset, size) = if let Some(animated_rect) = animated_rect {
+                self.app_state.schedule_paint(id);
+                (
+                    Vec2::new(
+                        animated_rect.x0 - layout.location.x as f64,
+                        animated_rect.y0 - layout.location.y as f64,
+                    ),
+                    animated_rect.size(),
+                )
+            } else {
+                (Vec2::ZERO, layout_size)
+            };
+
             let offset = layout.location;
             self.transform *= Affine::translate(Vec2 {
-                x: offset.x as f64,
-                y: offset.y as f64,
+                x: offset.x as f64 + animation_offset.x,
+                y: offset.y as f64 + animation_offset.y,
             });
 
-            let view_state = id.state();
             let transform_x = match view_state.borrow().layout_props.translate_x() {
                 crate::unit::PxPct::Px(px) => px,
                 crate::unit::PxPct::Pct(pct) => pct / 100.,
@@ -1169,11 +1230,21 @@ impl<'a> PaintCx<'a> {
                 let raidus = rect.radii();
                 *rect = rect
                     .rect()
-                    .with_origin(rect.origin() - Vec2::new(offset.x as f64, offset.y as f64))
+                    .with_origin(
+                        rect.origin()
+                            - Vec2::new(offset.x as f64, offset.y as f64)
+                            - animation_offset,
+                    )
                     .to_rounded_rect(raidus);
             }
 
-            Size::new(layout.size.width as f64, layout.size.height as f64)
+            // While the size is animating the children are already laid out at the final size,
+            // so they are clipped to the animated box.
+            if size != layout_size {
+                self.clip(&size.to_rect());
+            }
+
+            size
         } else {
             Size::ZERO
         }
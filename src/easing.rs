@@ -1,5 +1,7 @@
 //! The Easing trait and the built-in easing functions.
 
+use std::rc::Rc;
+
 use peniko::kurbo::{ParamCurve, Point};
 
 pub trait Easing: std::fmt::Debug {
@@ -11,6 +13,12 @@ pub trait Easing: std::fmt::Debug {
     fn finished(&self, time: f64) -> bool {
         time >= 1. || time <= 0.
     }
+    /// Returns a copy of this easing that starts out moving at `velocity`, for easings that
+    /// model physical motion. Used to keep momentum when an animation is retargeted.
+    fn with_initial_velocity(&self, velocity: f64) -> Option<Rc<dyn Easing>> {
+        let _ = velocity;
+        None
+    }
 }
 
 #[derive(Debug, Clone, Copy)]
@@ -142,6 +150,11 @@ pub struct Spring {
 }
 
 impl Spring {
+    /// A spring of the mass, stiffness and damping.
+    ///
+    /// `initial_velocity` is the velocity at the start of the distance that's left to the end, in
+    /// progress per unit of time, so a positive velocity starts out moving away from the end.
+    /// That's the opposite of [`Spring::velocity`], which is the velocity of the progress.
     pub fn new(mass: f64, stiffness: f64, damping: f64, initial_velocity: f64) -> Self {
         Spring {
             mass,
@@ -175,7 +188,8 @@ impl Spring {
         let m = self.mass;
         let k = self.stiffness;
         let c = self.damping;
-        let v0 = self.initial_velocity;
+        // The velocity of the progress at the start.
+        let v0 = -self.initial_velocity;
 
         let omega = (k / m).sqrt();
         let zeta = c / (2.0 * (k * m).sqrt());
@@ -188,7 +202,7 @@ impl Spring {
             let sin_term = (omega_d * time).sin();
 
             let a = 1.0;
-            let b = (v0 + zeta * omega * a) / omega_d;
+            let b = (zeta * omega * a - v0) / omega_d;
 
             1.0 - e * (a * cos_term + b * sin_term)
         } else if zeta > 1.0 {
@@ -196,7 +210,7 @@ impl Spring {
             let r1 = -omega * (zeta - (zeta * zeta - 1.0).sqrt());
             let r2 = -omega * (zeta + (zeta * zeta - 1.0).sqrt());
 
-            let a = (v0 - r2) / (r1 - r2);
+            let a = (-v0 - r2) / (r1 - r2);
             let b = 1.0 - a;
 
             1.0 - a * (r1 * time).exp() - b * (r2 * time).exp()
@@ -204,7 +218,7 @@ impl Spring {
             // Critically damped
             let e = (-omega * time).exp();
             let a = 1.0;
-            let b = v0 + omega * a;
+            let b = omega * a - v0;
 
             1.0 - e * (a + b * time)
         }
@@ -220,13 +234,14 @@ impl Spring {
 
     pub fn velocity(&self, time: f64) -> f64 {
         if time <= 0.0 {
-            return self.initial_velocity;
+            return -self.initial_velocity;
         }
 
         let m = self.mass;
         let k = self.stiffness;
         let c = self.damping;
-        let v0 = self.initial_velocity;
+        // The velocity of the progress at the start.
+        let v0 = -self.initial_velocity;
 
         let omega = (k / m).sqrt();
         let zeta = c / (2.0 * (k * m).sqrt());
@@ -239,16 +254,16 @@ impl Spring {
             let sin_term = (omega_d * time).sin();
 
             let a = 1.0;
-            let b = (v0 + zeta * omega * a) / omega_d;
+            let b = (zeta * omega * a - v0) / omega_d;
 
             e * ((zeta * omega * (a * cos_term + b * sin_term))
-                + (a * -omega_d * sin_term + b * omega_d * cos_term))
+                + (a * omega_d * sin_term - b * omega_d * cos_term))
         } else if zeta > 1.0 {
             // Overdamped
             let r1 = -omega * (zeta - (zeta * zeta - 1.0).sqrt());
             let r2 = -omega * (zeta + (zeta * zeta - 1.0).sqrt());
 
-            let a = (v0 - r2) / (r1 - r2);
+            let a = (-v0 - r2) / (r1 - r2);
             let b = 1.0 - a;
 
             -a * r1 * (r1 * time).exp() - b * r2 * (r2 * time).exp()
@@ -256,9 +271,9 @@ impl Spring {
             // Critically damped
             let e = (-omega * time).exp();
             let a = 1.0;
-            let b = v0 + omega * a;
+            let b = omega * a - v0;
 
-            e * (b - omega * (a + b * time))
+            e * (omega * (a + b * time) - b)
         }
     }
 }
@@ -284,4 +299,50 @@ impl Easing for Spring {
     fn finished(&self, time: f64) -> bool {
         self.finished(time)
     }
+
+    fn with_initial_velocity(&self, velocity: f64) -> Option<Rc<dyn Easing>> {
+        // The velocity is that of the progress, like `Spring::velocity`.
+        Some(Rc::new(Spring {
+            initial_velocity: -velocity,
+            ..*self
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Easing, Spring};
+
+    const STEP: f64 = 1e-6;
+
+    fn assert_close(a: f64, b: f64) {
+        assert!((a - b).abs() < 1e-3, "{a} != {b}");
+    }
+
+    #[test]
+    fn spring_velocity() {
+        // Underdamped, critically damped and overdamped springs.
+        for damping in [5.0, 20.0, 40.0] {
+            for initial_velocity in [-3.0, 0.0, 3.0] {
+                let spring = Spring::new(1.0, 100.0, damping, initial_velocity);
+                // The progress starts out moving against the initial velocity, which is that of
+                // the distance to the end.
+                assert_close(spring.eval(STEP) / STEP, -initial_velocity);
+                assert_close(spring.velocity(0.0), -initial_velocity);
+
+                for time in [0.05, 0.2, 0.5] {
+                    let slope =
+                        (spring.eval(time + STEP) - spring.eval(time - STEP)) / (2.0 * STEP);
+                    assert_close(spring.velocity(time), slope);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn retargeted_spring_keeps_velocity() {
+        let spring = Spring::snappy().with_initial_velocity(2.0).unwrap();
+        assert_close(spring.velocity(0.0).unwrap(), 2.0);
+        assert_close(spring.eval(STEP) / STEP, 2.0);
+    }
 }
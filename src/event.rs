@@ -6,6 +6,7 @@ use peniko::kurbo::{Point, Size};
 
 use crate::{
     dropped_file::DroppedFileEvent,
+    gesture::{LongPressEvent, PanEvent, PinchEvent, RotateEvent, TapEvent},
     keyboard::KeyEvent,
     pointer::{PointerInputEvent, PointerMoveEvent, PointerWheelEvent},
 };
@@ -97,6 +98,16 @@ pub enum EventListener {
     WindowMaximizeChanged,
     /// Receives [`Event::DroppedFile`]
     DroppedFile,
+    /// Receives [`Event::Tap`]
+    Tap,
+    /// Receives [`Event::LongPress`]
+    LongPress,
+    /// Receives [`Event::Pan`]
+    Pan,
+    /// Receives [`Event::Pinch`]
+    Pinch,
+    /// Receives [`Event::Rotate`]
+    Rotate,
 }
 
 #[derive(Debug, Clone)]
@@ -106,6 +117,11 @@ pub enum Event {
     PointerMove(PointerMoveEvent),
     PointerWheel(PointerWheelEvent),
     PointerLeave,
+    Tap(TapEvent),
+    LongPress(LongPressEvent),
+    Pan(PanEvent),
+    Pinch(PinchEvent),
+    Rotate(RotateEvent),
     DroppedFile(DroppedFileEvent),
     KeyDown(KeyEvent),
     KeyUp(KeyEvent),
@@ -135,6 +151,11 @@ impl Event {
             | Event::PointerMove(_)
             | Event::PointerWheel(_)
             | Event::PointerLeave
+            | Event::Tap(_)
+            | Event::LongPress(_)
+            | Event::Pan(_)
+            | Event::Pinch(_)
+            | Event::Rotate(_)
             | Event::FocusGained
             | Event::FocusLost
             | Event::ImeEnabled
@@ -160,7 +181,13 @@ impl Event {
             | Event::PointerMove(_)
             | Event::PointerWheel(_)
             | Event::PointerLeave => true,
-            Event::KeyDown(_)
+            // Gestures are delivered where they started, not to the active view.
+            Event::Tap(_)
+            | Event::LongPress(_)
+            | Event::Pan(_)
+            | Event::Pinch(_)
+            | Event::Rotate(_)
+            | Event::KeyDown(_)
             | Event::KeyUp(_)
             | Event::FocusGained
             | Event::FocusLost
@@ -199,6 +226,11 @@ impl Event {
             Event::PointerDown(_)
             | Event::PointerUp(_)
             | Event::PointerWheel(_)
+            | Event::Tap(_)
+            | Event::LongPress(_)
+            | Event::Pan(_)
+            | Event::Pinch(_)
+            | Event::Rotate(_)
             | Event::FocusGained
             | Event::FocusLost
             | Event::ImeEnabled
@@ -227,6 +259,12 @@ impl Event {
             }
             Event::PointerMove(pointer_event) => Some(pointer_event.pos),
             Event::PointerWheel(pointer_event) => Some(pointer_event.pos),
+            Event::Tap(event) => Some(event.pos),
+            Event::LongPress(event) => Some(event.pos),
+            // Continuous gestures stay with the view they started on.
+            Event::Pan(event) => Some(event.origin),
+            Event::Pinch(event) => Some(event.origin),
+            Event::Rotate(event) => Some(event.origin),
             Event::DroppedFile(event) => Some(event.pos),
             Event::PointerLeave
             | Event::KeyDown(_)
@@ -261,6 +299,35 @@ impl Event {
                 pointer_event.pos.x /= scale;
                 pointer_event.pos.y /= scale;
             }
+            Event::Tap(event) => {
+                event.pos.x /= scale;
+                event.pos.y /= scale;
+            }
+            Event::LongPress(event) => {
+                event.pos.x /= scale;
+                event.pos.y /= scale;
+            }
+            Event::Pan(event) => {
+                event.origin.x /= scale;
+                event.origin.y /= scale;
+                event.pos.x /= scale;
+                event.pos.y /= scale;
+                event.translation /= scale;
+                event.delta /= scale;
+                event.velocity /= scale;
+            }
+            Event::Pinch(event) => {
+                event.origin.x /= scale;
+                event.origin.y /= scale;
+                event.center.x /= scale;
+                event.center.y /= scale;
+            }
+            Event::Rotate(event) => {
+                event.origin.x /= scale;
+                event.origin.y /= scale;
+                event.center.x /= scale;
+                event.center.y /= scale;
+            }
             Event::DroppedFile(event) => {
                 event.pos.x /= scale;
                 event.pos.y /= scale;
@@ -296,6 +363,24 @@ impl Event {
             Event::PointerWheel(pointer_event) => {
                 pointer_event.pos -= offset;
             }
+            Event::Tap(event) => {
+                event.pos -= offset;
+            }
+            Event::LongPress(event) => {
+                event.pos -= offset;
+            }
+            Event::Pan(event) => {
+                event.origin -= offset;
+                event.pos -= offset;
+            }
+            Event::Pinch(event) => {
+                event.origin -= offset;
+                event.center -= offset;
+            }
+            Event::Rotate(event) => {
+                event.origin -= offset;
+                event.center -= offset;
+            }
             Event::DroppedFile(event) => {
                 event.pos -= offset;
             }
@@ -326,6 +411,11 @@ impl Event {
             Event::PointerMove(_) => Some(EventListener::PointerMove),
             Event::PointerWheel(_) => Some(EventListener::PointerWheel),
             Event::PointerLeave => Some(EventListener::PointerLeave),
+            Event::Tap(_) => Some(EventListener::Tap),
+            Event::LongPress(_) => Some(EventListener::LongPress),
+            Event::Pan(_) => Some(EventListener::Pan),
+            Event::Pinch(_) => Some(EventListener::Pinch),
+            Event::Rotate(_) => Some(EventListener::Rotate),
             Event::KeyDown(_) => Some(EventListener::KeyDown),
             Event::KeyUp(_) => Some(EventListener::KeyUp),
             Event::ImeEnabled => Some(EventListener::ImeEnabled),
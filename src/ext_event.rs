@@ -82,6 +82,15 @@ pub fn create_ext_action<T: Send + 'static>(
     }
 }
 
+/// Runs `f` on a new thread. On wasm, which has no threads, it runs right away, and the actions
+/// it sends with [`create_ext_action`] still run with the next events.
+pub(crate) fn spawn_background(f: impl FnOnce() + Send + 'static) {
+    #[cfg(not(target_arch = "wasm32"))]
+    std::thread::spawn(f);
+    #[cfg(target_arch = "wasm32")]
+    f();
+}
+
 pub fn update_signal_from_channel<T: Send + 'static>(
     writer: WriteSignal<Option<T>>,
     rx: crossbeam_channel::Receiver<T>,
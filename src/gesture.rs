@@ -0,0 +1,769 @@
+//! Touch gestures.
+//!
+//! Touch input is delivered as pointer events with a [`PointerId::Touch`](crate::pointer::PointerId)
+//! id, and is also fed through a [`GestureRecognizer`] that turns it into higher level
+//! [`Event::Tap`], [`Event::LongPress`], [`Event::Pan`], [`Event::Pinch`] and [`Event::Rotate`]
+//! events. Touchpad magnify and rotate gestures produce pinch and rotate events as well.
+//!
+//! Gestures are delivered to the view under the point where they started, even when the
+//! fingers later move outside of it.
+//!
+//! The recognizer is independent from the window, so a recorded sequence of [`GestureInput`]s can
+//! be replayed through it to check which gestures it produces:
+//! ```rust
+//! use std::time::{Duration, Instant};
+//! use floem::event::Event;
+//! use floem::gesture::{GestureInput, GestureRecognizer};
+//! use floem::kurbo::Point;
+//!
+//! let start = Instant::now();
+//! let events = GestureRecognizer::new().replay(
+//!     start,
+//!     [
+//!         (Duration::ZERO, GestureInput::Down { id: 0, pos: Point::new(10., 10.) }),
+//!         (Duration::from_millis(80), GestureInput::Up { id: 0, pos: Point::new(11., 10.) }),
+//!     ],
+//! );
+//! assert!(matches!(events[..], [Event::Tap(_)]));
+//! ```
+
+use std::f64::consts::PI;
+
+use floem_winit::event::TouchPhase;
+use peniko::kurbo::{Point, Vec2};
+
+#[cfg(not(target_arch = "wasm32"))]
+use std::time::{Duration, Instant};
+#[cfg(target_arch = "wasm32")]
+use web_time::{Duration, Instant};
+
+use crate::event::Event;
+
+/// How far a touch can move, in logical pixels, and still count as a tap or long press.
+const TAP_SLOP: f64 = 10.0;
+/// How far apart two taps can be, in logical pixels, to count as a double tap.
+const MULTI_TAP_SLOP: f64 = 24.0;
+const MULTI_TAP_INTERVAL: Duration = Duration::from_millis(300);
+const LONG_PRESS_DURATION: Duration = Duration::from_millis(500);
+/// The relative change in distance between two touches that starts a pinch.
+const PINCH_THRESHOLD: f64 = 0.05;
+/// The change in angle between two touches, in radians, that starts a rotation.
+const ROTATE_THRESHOLD: f64 = 0.1;
+/// If a touch is lifted this long after it last moved, a pan ends without velocity.
+const FLING_TIMEOUT: Duration = Duration::from_millis(100);
+
+/// The stage of a continuous gesture.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GesturePhase {
+    Started,
+    Changed,
+    Ended,
+    /// The gesture was interrupted, and its effects should be undone if possible.
+    Cancelled,
+}
+
+impl GesturePhase {
+    pub fn is_end(self) -> bool {
+        matches!(self, GesturePhase::Ended | GesturePhase::Cancelled)
+    }
+}
+
+impl From<TouchPhase> for GesturePhase {
+    fn from(value: TouchPhase) -> Self {
+        match value {
+            TouchPhase::Started => Self::Started,
+            TouchPhase::Moved => Self::Changed,
+            TouchPhase::Ended => Self::Ended,
+            TouchPhase::Cancelled => Self::Cancelled,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct TapEvent {
+    pub pos: Point,
+    /// The number of taps in quick succession, `2` for a double tap.
+    pub count: u8,
+}
+
+#[derive(Debug, Clone)]
+pub struct LongPressEvent {
+    pub pos: Point,
+}
+
+#[derive(Debug, Clone)]
+pub struct PanEvent {
+    pub phase: GesturePhase,
+    /// Where the pan started.
+    pub origin: Point,
+    /// The current position of the touch.
+    pub pos: Point,
+    /// The total movement since the pan started.
+    pub translation: Vec2,
+    /// The movement since the previous pan event.
+    pub delta: Vec2,
+    /// The speed of the touch in logical pixels per second.
+    /// When the pan ends, this is the velocity it was released with.
+    pub velocity: Vec2,
+}
+
+#[derive(Debug, Clone)]
+pub struct PinchEvent {
+    pub phase: GesturePhase,
+    /// The center of the touches when the pinch started.
+    pub origin: Point,
+    /// The current center of the touches.
+    pub center: Point,
+    /// The total scale factor since the pinch started.
+    pub scale: f64,
+    /// The scale factor since the previous pinch event.
+    pub scale_delta: f64,
+}
+
+#[derive(Debug, Clone)]
+pub struct RotateEvent {
+    pub phase: GesturePhase,
+    /// The center of the touches when the rotation started.
+    pub origin: Point,
+    /// The current center of the touches.
+    pub center: Point,
+    /// The total clockwise rotation in radians since the rotation started.
+    pub rotation: f64,
+    /// The rotation in radians since the previous rotate event.
+    pub delta: f64,
+}
+
+/// The raw input that gestures are recognized from.
+#[derive(Debug, Clone, PartialEq)]
+pub enum GestureInput {
+    /// A touch with the given id started.
+    Down { id: u64, pos: Point },
+    /// A touch moved.
+    Move { id: u64, pos: Point },
+    /// A touch ended.
+    Up { id: u64, pos: Point },
+    /// A touch was cancelled by the system.
+    Cancel { id: u64 },
+    /// A touchpad pinch, where `delta` is the change in scale.
+    Magnify {
+        pos: Point,
+        delta: f64,
+        phase: GesturePhase,
+    },
+    /// A touchpad rotation, where `delta` is the clockwise change in radians.
+    Rotate {
+        pos: Point,
+        delta: f64,
+        phase: GesturePhase,
+    },
+    /// A touchpad double tap with two fingers.
+    SmartMagnify { pos: Point },
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Touch {
+    id: u64,
+    start: Point,
+    pos: Point,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Pan {
+    origin: Point,
+    last_pos: Point,
+    last_time: Instant,
+    velocity: Vec2,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct TwoFingers {
+    origin: Point,
+    start_distance: f64,
+    start_angle: f64,
+    scale: Option<f64>,
+    rotation: Option<f64>,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct TouchpadGesture {
+    origin: Point,
+    value: f64,
+}
+
+/// Turns touch and touchpad input into gesture events. See the [module docs](self).
+#[derive(Debug, Default)]
+pub struct GestureRecognizer {
+    touches: Vec<Touch>,
+    /// Whether the current touch can still become a tap or a long press.
+    tap_possible: bool,
+    long_press_deadline: Option<Instant>,
+    last_tap: Option<(Instant, Point, u8)>,
+    pan: Option<Pan>,
+    two_fingers: Option<TwoFingers>,
+    touchpad_pinch: Option<TouchpadGesture>,
+    touchpad_rotate: Option<TouchpadGesture>,
+}
+
+impl GestureRecognizer {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The time at which [`poll`](Self::poll) needs to be called to recognize a long press.
+    pub fn next_deadline(&self) -> Option<Instant> {
+        self.long_press_deadline
+    }
+
+    /// Recognize the gestures that don't need any input to complete, such as a long press.
+    pub fn poll(&mut self, now: Instant) -> Vec<Event> {
+        let mut events = Vec::new();
+        if let Some(deadline) = self.long_press_deadline {
+            if deadline <= now {
+                self.long_press_deadline = None;
+                self.tap_possible = false;
+                if let [touch] = self.touches[..] {
+                    events.push(Event::LongPress(LongPressEvent { pos: touch.pos }));
+                }
+            }
+        }
+        events
+    }
+
+    /// Feed one input into the recognizer, returning the gesture events it completes.
+    pub fn input(&mut self, input: &GestureInput, now: Instant) -> Vec<Event> {
+        let mut events = self.poll(now);
+        match *input {
+            GestureInput::Down { id, pos } => self.touch_down(id, pos, now, &mut events),
+            GestureInput::Move { id, pos } => self.touch_move(id, pos, now, &mut events),
+            GestureInput::Up { id, pos } => self.touch_up(id, pos, now, &mut events),
+            GestureInput::Cancel { id } => self.touch_cancel(id, &mut events),
+            GestureInput::Magnify { pos, delta, phase } => {
+                let pinch = touchpad_gesture(&mut self.touchpad_pinch, pos, phase, 1.0, |scale| {
+                    scale * (1.0 + delta)
+                });
+                if let Some((gesture, previous)) = pinch {
+                    events.push(Event::Pinch(PinchEvent {
+                        phase,
+                        origin: gesture.origin,
+                        center: pos,
+                        scale: gesture.value,
+                        scale_delta: gesture.value / previous,
+                    }));
+                }
+            }
+            GestureInput::Rotate { pos, delta, phase } => {
+                let rotate =
+                    touchpad_gesture(&mut self.touchpad_rotate, pos, phase, 0.0, |rotation| {
+                        rotation + delta
+                    });
+                if let Some((gesture, previous)) = rotate {
+                    events.push(Event::Rotate(RotateEvent {
+                        phase,
+                        origin: gesture.origin,
+                        center: pos,
+                        rotation: gesture.value,
+                        delta: gesture.value - previous,
+                    }));
+                }
+            }
+            GestureInput::SmartMagnify { pos } => {
+                events.push(Event::Tap(TapEvent { pos, count: 2 }));
+            }
+        }
+        events
+    }
+
+    /// Feed a recorded sequence of inputs into the recognizer, where each input is paired with
+    /// its time relative to `start`, and return all of the gesture events produced.
+    ///
+    /// Pending long presses are recognized at the time of the following input.
+    pub fn replay(
+        &mut self,
+        start: Instant,
+        inputs: impl IntoIterator<Item = (Duration, GestureInput)>,
+    ) -> Vec<Event> {
+        inputs
+            .into_iter()
+            .flat_map(|(time, input)| self.input(&input, start + time))
+            .collect()
+    }
+
+    fn touch_down(&mut self, id: u64, pos: Point, now: Instant, events: &mut Vec<Event>) {
+        self.touches.retain(|touch| touch.id != id);
+        self.touches.push(Touch {
+            id,
+            start: pos,
+            pos,
+        });
+        match self.touches.len() {
+            1 => {
+                self.tap_possible = true;
+                self.long_press_deadline = Some(now + LONG_PRESS_DURATION);
+            }
+            2 => {
+                self.tap_possible = false;
+                self.long_press_deadline = None;
+                self.end_pan(GesturePhase::Ended, events);
+                let (center, distance, angle) = self.two_finger_geometry();
+                self.two_fingers = Some(TwoFingers {
+                    origin: center,
+                    start_distance: distance,
+                    start_angle: angle,
+                    scale: None,
+                    rotation: None,
+                });
+            }
+            _ => {}
+        }
+    }
+
+    fn touch_move(&mut self, id: u64, pos: Point, now: Instant, events: &mut Vec<Event>) {
+        let Some(touch) = self.touches.iter_mut().find(|touch| touch.id == id) else {
+            return;
+        };
+        touch.pos = pos;
+        let touch = *touch;
+
+        if self.touches.len() == 1 {
+            if self.tap_possible && touch.start.distance(pos) > TAP_SLOP {
+                self.tap_possible = false;
+                self.long_press_deadline = None;
+                self.pan = Some(Pan {
+                    origin: touch.start,
+                    last_pos: touch.start,
+                    last_time: now,
+                    velocity: Vec2::ZERO,
+                });
+                events.push(self.pan_event(GesturePhase::Started, pos, now));
+            } else if self.pan.is_some() {
+                events.push(self.pan_event(GesturePhase::Changed, pos, now));
+            }
+        } else if self.touches[..2].iter().any(|touch| touch.id == id) {
+            self.two_finger_move(events);
+        }
+    }
+
+    fn touch_up(&mut self, id: u64, pos: Point, now: Instant, events: &mut Vec<Event>) {
+        if let Some(touch) = self.touches.iter_mut().find(|touch| touch.id == id) {
+            touch.pos = pos;
+        } else {
+            return;
+        }
+
+        if self.touches.len() == 1 {
+            if self.tap_possible {
+                let count = match self.last_tap {
+                    Some((time, last_pos, count))
+                        if now.saturating_duration_since(time) < MULTI_TAP_INTERVAL
+                            && last_pos.distance(pos) < MULTI_TAP_SLOP =>
+                    {
+                        count.saturating_add(1)
+                    }
+                    _ => 1,
+                };
+                self.last_tap = Some((now, pos, count));
+                events.push(Event::Tap(TapEvent { pos, count }));
+            } else if self.pan.is_some() {
+                if let Some(pan) = self.pan.as_mut() {
+                    if now.saturating_duration_since(pan.last_time) > FLING_TIMEOUT {
+                        pan.velocity = Vec2::ZERO;
+                    }
+                }
+                self.end_pan(GesturePhase::Ended, events);
+            }
+        } else if self.touches[..2].iter().any(|touch| touch.id == id) {
+            self.end_two_fingers(GesturePhase::Ended, events);
+        }
+
+        self.touches.retain(|touch| touch.id != id);
+        if self.touches.is_empty() {
+            self.tap_possible = false;
+            self.long_press_deadline = None;
+        }
+    }
+
+    fn touch_cancel(&mut self, id: u64, events: &mut Vec<Event>) {
+        if !self.touches.iter().any(|touch| touch.id == id) {
+            return;
+        }
+        if let Some(pan) = self.pan.take() {
+            events.push(Event::Pan(PanEvent {
+                phase: GesturePhase::Cancelled,
+                origin: pan.origin,
+                pos: pan.last_pos,
+                translation: pan.last_pos - pan.origin,
+                delta: Vec2::ZERO,
+                velocity: Vec2::ZERO,
+            }));
+        }
+        self.end_two_fingers(GesturePhase::Cancelled, events);
+        self.touches.clear();
+        self.tap_possible = false;
+        self.long_press_deadline = None;
+    }
+
+    fn pan_event(&mut self, phase: GesturePhase, pos: Point, now: Instant) -> Event {
+        let pan = self.pan.as_mut().unwrap();
+        let delta = pos - pan.last_pos;
+        let elapsed = now.saturating_duration_since(pan.last_time).as_secs_f64();
+        if elapsed > 0.0 {
+            // Smooth out the velocity, as touch positions are noisy.
+            pan.velocity = pan.velocity * 0.2 + delta / elapsed * 0.8;
+            pan.last_time = now;
+        }
+        pan.last_pos = pos;
+        Event::Pan(PanEvent {
+            phase,
+            origin: pan.origin,
+            pos,
+            translation: pos - pan.origin,
+            delta,
+            velocity: pan.velocity,
+        })
+    }
+
+    fn end_pan(&mut self, phase: GesturePhase, events: &mut Vec<Event>) {
+        if let Some(pan) = self.pan.take() {
+            events.push(Event::Pan(PanEvent {
+                phase,
+                origin: pan.origin,
+                pos: pan.last_pos,
+                translation: pan.last_pos - pan.origin,
+                delta: Vec2::ZERO,
+                velocity: pan.velocity,
+            }));
+        }
+    }
+
+    /// The center, distance and angle between the first two touches.
+    fn two_finger_geometry(&self) -> (Point, f64, f64) {
+        let (a, b) = (self.touches[0].pos, self.touches[1].pos);
+        let between = b - a;
+        (a.midpoint(b), between.hypot(), between.atan2())
+    }
+
+    fn two_finger_move(&mut self, events: &mut Vec<Event>) {
+        let (center, distance, angle) = self.two_finger_geometry();
+        let Some(two_fingers) = self.two_fingers.as_mut() else {
+            return;
+        };
+
+        if two_fingers.start_distance > 0.0 {
+            let scale = distance / two_fingers.start_distance;
+            match two_fingers.scale {
+                None if (scale - 1.0).abs() > PINCH_THRESHOLD => {
+                    two_fingers.scale = Some(scale);
+                    events.push(Event::Pinch(PinchEvent {
+                        phase: GesturePhase::Started,
+                        origin: two_fingers.origin,
+                        center,
+                        scale,
+                        scale_delta: scale,
+                    }));
+                }
+                Some(previous) => {
+                    two_fingers.scale = Some(scale);
+                    events.push(Event::Pinch(PinchEvent {
+                        phase: GesturePhase::Changed,
+                        origin: two_fingers.origin,
+                        center,
+                        scale,
+                        scale_delta: scale / previous,
+                    }));
+                }
+                None => {}
+            }
+        }
+
+        let rotation = normalize_angle(angle - two_fingers.start_angle);
+        match two_fingers.rotation {
+            None if rotation.abs() > ROTATE_THRESHOLD => {
+                two_fingers.rotation = Some(rotation);
+                events.push(Event::Rotate(RotateEvent {
+                    phase: GesturePhase::Started,
+                    origin: two_fingers.origin,
+                    center,
+                    rotation,
+                    delta: rotation,
+                }));
+            }
+            Some(previous) => {
+                two_fingers.rotation = Some(rotation);
+                events.push(Event::Rotate(RotateEvent {
+                    phase: GesturePhase::Changed,
+                    origin: two_fingers.origin,
+                    center,
+                    rotation,
+                    delta: normalize_angle(rotation - previous),
+                }));
+            }
+            None => {}
+        }
+    }
+
+    fn end_two_fingers(&mut self, phase: GesturePhase, events: &mut Vec<Event>) {
+        let Some(two_fingers) = self.two_fingers.take() else {
+            return;
+        };
+        let (center, _, _) = self.two_finger_geometry();
+        if let Some(scale) = two_fingers.scale {
+            events.push(Event::Pinch(PinchEvent {
+                phase,
+                origin: two_fingers.origin,
+                center,
+                scale,
+                scale_delta: 1.0,
+            }));
+        }
+        if let Some(rotation) = two_fingers.rotation {
+            events.push(Event::Rotate(RotateEvent {
+                phase,
+                origin: two_fingers.origin,
+                center,
+                rotation,
+                delta: 0.0,
+            }));
+        }
+    }
+}
+
+/// Update a touchpad gesture, returning its new state and its previous value.
+fn touchpad_gesture(
+    state: &mut Option<TouchpadGesture>,
+    pos: Point,
+    phase: GesturePhase,
+    initial: f64,
+    update: impl FnOnce(f64) -> f64,
+) -> Option<(TouchpadGesture, f64)> {
+    if phase == GesturePhase::Started {
+        *state = Some(TouchpadGesture {
+            origin: pos,
+            value: initial,
+        });
+    }
+    let gesture = state.as_mut()?;
+    let previous = gesture.value;
+    gesture.value = update(previous);
+    let gesture = *gesture;
+    if phase.is_end() {
+        *state = None;
+    }
+    Some((gesture, previous))
+}
+
+/// Wrap an angle into `-PI..=PI`.
+fn normalize_angle(angle: f64) -> f64 {
+    let angle = angle % (2.0 * PI);
+    if angle > PI {
+        angle - 2.0 * PI
+    } else if angle < -PI {
+        angle + 2.0 * PI
+    } else {
+        angle
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ms(ms: u64) -> Duration {
+        Duration::from_millis(ms)
+    }
+
+    fn down(id: u64, x: f64, y: f64) -> GestureInput {
+        GestureInput::Down {
+            id,
+            pos: Point::new(x, y),
+        }
+    }
+
+    fn moved(id: u64, x: f64, y: f64) -> GestureInput {
+        GestureInput::Move {
+            id,
+            pos: Point::new(x, y),
+        }
+    }
+
+    fn up(id: u64, x: f64, y: f64) -> GestureInput {
+        GestureInput::Up {
+            id,
+            pos: Point::new(x, y),
+        }
+    }
+
+    #[test]
+    fn tap_and_double_tap() {
+        let events = GestureRecognizer::new().replay(
+            Instant::now(),
+            [
+                (ms(0), down(0, 10., 10.)),
+                (ms(50), moved(0, 12., 11.)),
+                (ms(80), up(0, 12., 11.)),
+                (ms(200), down(1, 14., 10.)),
+                (ms(260), up(1, 14., 10.)),
+            ],
+        );
+        let counts: Vec<u8> = events
+            .iter()
+            .map(|event| match event {
+                Event::Tap(tap) => tap.count,
+                event => panic!("unexpected {event:?}"),
+            })
+            .collect();
+        assert_eq!(counts, [1, 2]);
+    }
+
+    #[test]
+    fn long_press_is_not_a_tap() {
+        let start = Instant::now();
+        let mut recognizer = GestureRecognizer::new();
+        let events = recognizer.replay(start, [(ms(0), down(0, 10., 10.))]);
+        assert!(events.is_empty());
+        assert_eq!(
+            recognizer.next_deadline(),
+            Some(start + LONG_PRESS_DURATION)
+        );
+
+        let events = recognizer.poll(start + ms(600));
+        assert!(matches!(events[..], [Event::LongPress(_)]));
+
+        let events = recognizer.replay(start, [(ms(700), up(0, 10., 10.))]);
+        assert!(events.is_empty());
+    }
+
+    #[test]
+    fn pan_with_release_velocity() {
+        let events = GestureRecognizer::new().replay(
+            Instant::now(),
+            [
+                (ms(0), down(0, 100., 100.)),
+                (ms(16), moved(0, 100., 80.)),
+                (ms(32), moved(0, 100., 60.)),
+                (ms(48), moved(0, 100., 40.)),
+                (ms(50), up(0, 100., 40.)),
+            ],
+        );
+        let pans: Vec<&PanEvent> = events
+            .iter()
+            .map(|event| match event {
+                Event::Pan(pan) => pan,
+                event => panic!("unexpected {event:?}"),
+            })
+            .collect();
+        let phases: Vec<GesturePhase> = pans.iter().map(|pan| pan.phase).collect();
+        assert_eq!(
+            phases,
+            [
+                GesturePhase::Started,
+                GesturePhase::Changed,
+                GesturePhase::Changed,
+                GesturePhase::Ended
+            ]
+        );
+        let end = pans.last().unwrap();
+        assert_eq!(end.origin, Point::new(100., 100.));
+        assert_eq!(end.translation, Vec2::new(0., -60.));
+        // Moving up by 20px every 16ms.
+        assert!(end.velocity.y < -1000.0);
+    }
+
+    #[test]
+    fn pan_released_after_pause_has_no_velocity() {
+        let events = GestureRecognizer::new().replay(
+            Instant::now(),
+            [
+                (ms(0), down(0, 100., 100.)),
+                (ms(16), moved(0, 100., 60.)),
+                (ms(400), up(0, 100., 60.)),
+            ],
+        );
+        match events.last() {
+            Some(Event::Pan(pan)) => {
+                assert_eq!(pan.phase, GesturePhase::Ended);
+                assert_eq!(pan.velocity, Vec2::ZERO);
+            }
+            event => panic!("unexpected {event:?}"),
+        }
+    }
+
+    #[test]
+    fn pinch_and_rotate() {
+        let events = GestureRecognizer::new().replay(
+            Instant::now(),
+            [
+                (ms(0), down(0, 0., 0.)),
+                (ms(5), down(1, 100., 0.)),
+                // Spread the fingers to twice the distance.
+                (ms(20), moved(1, 200., 0.)),
+                // Rotate the second finger a quarter turn clockwise around the first.
+                (ms(40), moved(1, 0., 200.)),
+                (ms(60), up(1, 0., 200.)),
+                (ms(70), up(0, 0., 0.)),
+            ],
+        );
+
+        let pinches: Vec<&PinchEvent> = events
+            .iter()
+            .filter_map(|event| match event {
+                Event::Pinch(pinch) => Some(pinch),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(pinches[0].phase, GesturePhase::Started);
+        assert_eq!(pinches[0].origin, Point::new(50., 0.));
+        assert!((pinches[0].scale - 2.0).abs() < 1e-9);
+        assert_eq!(pinches.last().unwrap().phase, GesturePhase::Ended);
+
+        let rotations: Vec<&RotateEvent> = events
+            .iter()
+            .filter_map(|event| match event {
+                Event::Rotate(rotate) => Some(rotate),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(rotations[0].phase, GesturePhase::Started);
+        assert!((rotations[0].rotation - PI / 2.0).abs() < 1e-9);
+        assert_eq!(rotations.last().unwrap().phase, GesturePhase::Ended);
+
+        // Neither a tap nor a pan.
+        assert!(events
+            .iter()
+            .all(|event| matches!(event, Event::Pinch(_) | Event::Rotate(_))));
+    }
+
+    #[test]
+    fn touchpad_magnify() {
+        let pos = Point::new(5., 5.);
+        let events = GestureRecognizer::new().replay(
+            Instant::now(),
+            [
+                (
+                    ms(0),
+                    GestureInput::Magnify {
+                        pos,
+                        delta: 0.5,
+                        phase: GesturePhase::Started,
+                    },
+                ),
+                (
+                    ms(10),
+                    GestureInput::Magnify {
+                        pos,
+                        delta: 1.0,
+                        phase: GesturePhase::Ended,
+                    },
+                ),
+            ],
+        );
+        match &events[..] {
+            [Event::Pinch(start), Event::Pinch(end)] => {
+                assert_eq!(start.scale, 1.5);
+                assert_eq!(end.scale, 3.0);
+                assert_eq!(end.scale_delta, 2.0);
+                assert_eq!(end.phase, GesturePhase::Ended);
+            }
+            events => panic!("unexpected {events:?}"),
+        }
+    }
+}
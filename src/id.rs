@@ -504,6 +504,17 @@ impl ViewId {
         });
     }
 
+    /// Set whether this view should be marked as invalid or not, like a form field with a value
+    /// that doesn't pass validation.
+    ///
+    /// An invalid view and its children can be styled with the invalid style.
+    pub fn update_invalid(&self, is_invalid: bool) {
+        self.add_update_message(UpdateMessage::Invalid {
+            id: *self,
+            is_invalid,
+        });
+    }
+
     /// Mark this view as a view that can be navigated to using the keyboard
     pub fn keyboard_navigable(&self) {
         self.add_update_message(UpdateMessage::KeyboardNavigable { id: *self });
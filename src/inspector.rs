@@ -1,5 +1,6 @@
 use crate::app::{add_app_update_event, AppUpdateEvent};
 use crate::app_state::AppState;
+use crate::clock;
 use crate::context::StyleCx;
 use crate::event::{Event, EventListener, EventPropagation};
 use crate::id::ViewId;
@@ -1075,6 +1076,67 @@ thread_local! {
     };
 }
 
+/// Controls for the animation clock, to slow down, pause and step through animations.
+fn animation_controls() -> impl IntoView {
+    let time_scale = create_rw_signal(clock::time_scale());
+    let paused = create_rw_signal(clock::is_paused());
+
+    let control =
+        |name: &'static str, is_active: Box<dyn Fn() -> bool>, on_click: Box<dyn Fn()>| {
+            text(name)
+                .on_click_stop(move |_| on_click())
+                .style(move |s| {
+                    s.padding(5.0)
+                        .border_left(1.)
+                        .border_color(Color::BLACK.multiply_alpha(0.2))
+                        .hover(|s| s.background(Color::rgba8(228, 237, 216, 160)))
+                        .apply_if(is_active(), |s| s.background(Color::rgb8(213, 208, 216)))
+                })
+        };
+    let speed = move |name, scale: f64| {
+        control(
+            name,
+            Box::new(move || time_scale.get() == scale),
+            Box::new(move || {
+                clock::set_time_scale(scale);
+                time_scale.set(scale);
+            }),
+        )
+    };
+
+    h_stack((
+        text("Animation speed").style(|s| s.padding(5.0)),
+        speed("1x", 1.0),
+        speed("0.5x", 0.5),
+        speed("0.25x", 0.25),
+        speed("0.1x", 0.1),
+        control(
+            "Pause",
+            Box::new(move || paused.get()),
+            Box::new(move || {
+                if clock::is_paused() {
+                    clock::resume();
+                } else {
+                    clock::pause();
+                }
+                paused.set(clock::is_paused());
+            }),
+        ),
+        control(
+            "Step",
+            Box::new(|| false),
+            Box::new(move || {
+                if !clock::is_paused() {
+                    clock::pause();
+                    paused.set(true);
+                }
+                clock::step_frame();
+            }),
+        ),
+    ))
+    .style(|s| s.items_center())
+}
+
 pub fn capture(window_id: WindowId) {
     let capture = CAPTURE.with(|c| *c);
 
@@ -1103,8 +1165,13 @@ pub fn capture(window_id: WindowId) {
                         })
                 };
 
-                let tabs = h_stack((tab_item("Views", 0), tab_item("Profiler", 1)))
-                    .style(|s| s.background(Color::WHITE));
+                let tabs = h_stack((
+                    tab_item("Views", 0),
+                    tab_item("Profiler", 1),
+                    empty().style(|s| s.flex_grow(1.0)),
+                    animation_controls(),
+                ))
+                .style(|s| s.background(Color::WHITE));
 
                 let tab = tab(
                     move || selected.get(),
@@ -71,3 +71,287 @@ impl From<ModifiersState> for Modifiers {
         modifiers
     }
 }
+
+/// A keyboard shortcut, such as `Ctrl+S`.
+///
+/// The character of the key is kept as it's given, but shortcuts are compared ignoring its case.
+#[derive(Debug, Clone)]
+pub struct HotKey {
+    pub mods: Modifiers,
+    pub key: Key,
+}
+
+impl PartialEq for HotKey {
+    fn eq(&self, other: &Self) -> bool {
+        self.mods == other.mods
+            && match (&self.key, &other.key) {
+                (Key::Character(key), Key::Character(other)) => {
+                    key.to_lowercase() == other.to_lowercase()
+                }
+                (key, other) => key == other,
+            }
+    }
+}
+
+impl Eq for HotKey {}
+
+impl std::hash::Hash for HotKey {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        self.mods.hash(state);
+        match &self.key {
+            Key::Character(c) => c.to_lowercase().hash(state),
+            key => key.hash(state),
+        }
+    }
+}
+
+impl HotKey {
+    pub fn new(mods: Modifiers, key: Key) -> Self {
+        Self { mods, key }
+    }
+
+    /// Parses a shortcut such as `Ctrl+Shift+P` or `Alt+F4`.
+    ///
+    /// The modifiers are those of [`split_key_press`], which the editor's key bindings share. The
+    /// key is either a single character or the name of a [`NamedKey`], like `Enter`, `Escape`,
+    /// `ArrowUp` or `F5`. Case is ignored.
+    pub fn parse(hotkey: &str) -> Option<Self> {
+        let (mods, key) = split_key_press(hotkey)?;
+        let key = if key.chars().count() == 1 {
+            Key::Character(key.into())
+        } else {
+            Key::Named(named_key_from_str(key)?)
+        };
+        Some(Self::new(mods, key))
+    }
+
+    /// Returns `true` if the key event triggers this shortcut.
+    pub fn matches(&self, event: &KeyEvent) -> bool {
+        *self == HotKey::from(event)
+    }
+}
+
+impl From<&KeyEvent> for HotKey {
+    fn from(event: &KeyEvent) -> Self {
+        let mut mods = event.modifiers;
+        mods.remove(Modifiers::ALTGR);
+        // With Shift, the character is the shifted one, like `!` for `Shift+1`, so the key is
+        // taken without the modifiers to match the shortcut.
+        let key = match &event.key.logical_key {
+            #[cfg(not(target_arch = "wasm32"))]
+            Key::Character(_) if mods.shift() => event.key.key_without_modifiers(),
+            key => key.clone(),
+        };
+        Self::new(mods, key)
+    }
+}
+
+impl std::fmt::Display for HotKey {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.mods.control() {
+            f.write_str("Ctrl+")?;
+        }
+        if self.mods.alt() {
+            f.write_str(if cfg!(target_os = "macos") {
+                "Option+"
+            } else {
+                "Alt+"
+            })?;
+        }
+        if self.mods.meta() {
+            f.write_str(match std::env::consts::OS {
+                "macos" => "Cmd+",
+                "windows" => "Win+",
+                _ => "Meta+",
+            })?;
+        }
+        if self.mods.shift() {
+            f.write_str("Shift+")?;
+        }
+        match &self.key {
+            Key::Character(c) => f.write_str(&c.to_uppercase()),
+            Key::Named(NamedKey::Space) => f.write_str("Space"),
+            Key::Named(named) => write!(f, "{named:?}"),
+            key => write!(f, "{key:?}"),
+        }
+    }
+}
+
+/// A sequence of one or more shortcuts that are pressed one after the other, such as
+/// `Ctrl+K Ctrl+S`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct KeyBinding(pub Vec<HotKey>);
+
+impl KeyBinding {
+    /// Parses shortcuts separated by spaces, using the syntax of [`HotKey::parse`].
+    pub fn parse(binding: &str) -> Option<Self> {
+        let keys = binding
+            .split_whitespace()
+            .map(HotKey::parse)
+            .collect::<Option<Vec<_>>>()?;
+        (!keys.is_empty()).then_some(Self(keys))
+    }
+
+    /// The shortcut if the binding is a single one, which is what menus can show.
+    pub fn single(&self) -> Option<&HotKey> {
+        match self.0.as_slice() {
+            [key] => Some(key),
+            _ => None,
+        }
+    }
+}
+
+impl From<HotKey> for KeyBinding {
+    fn from(key: HotKey) -> Self {
+        Self(vec![key])
+    }
+}
+
+impl std::fmt::Display for KeyBinding {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for (i, key) in self.0.iter().enumerate() {
+            if i > 0 {
+                f.write_str(" ")?;
+            }
+            write!(f, "{key}")?;
+        }
+        Ok(())
+    }
+}
+
+/// Splits a key press such as `Ctrl+Shift+P` into its modifiers and the name of its key. This is
+/// the syntax of both [`HotKey::parse`] and the editor's key bindings.
+///
+/// The modifiers are `Ctrl`, `Shift`, `Alt` (or `Option`, `AltGr`), `Meta` (or `Cmd`, `Super`),
+/// and `Primary`, which is `Cmd` on macOS and `Ctrl` elsewhere. `Ctrl++` is the plus key with
+/// `Ctrl`. Returns `None` if a modifier isn't one of these.
+pub(crate) fn split_key_press(press: &str) -> Option<(Modifiers, &str)> {
+    let (modifiers, key) = split_modifiers(press)?;
+    let mods = modifiers
+        .map(modifier_from_str)
+        .try_fold(Modifiers::empty(), |mods, modifier| Some(mods | modifier?))?;
+    Some((mods, key))
+}
+
+/// Like [`split_key_press`], but skips the modifiers that it doesn't know, which is how the
+/// editor's key bindings have always been parsed.
+pub(crate) fn split_key_press_lenient(press: &str) -> Option<(Modifiers, &str)> {
+    let (modifiers, key) = split_modifiers(press)?;
+    let mods = modifiers
+        .filter_map(modifier_from_str)
+        .fold(Modifiers::empty(), |mods, modifier| mods | modifier);
+    Some((mods, key))
+}
+
+fn split_modifiers(press: &str) -> Option<(impl Iterator<Item = &str> + '_, &str)> {
+    let (modifiers, key) = match press.rsplit_once('+') {
+        Some((modifiers, "")) => (modifiers.strip_suffix('+')?, "+"),
+        Some(pair) => pair,
+        None => ("", press),
+    };
+    let modifiers = modifiers.split('+').filter(|part| !part.is_empty());
+    Some((modifiers, key))
+}
+
+fn modifier_from_str(name: &str) -> Option<Modifiers> {
+    let modifier = match name.trim().to_lowercase().as_str() {
+        "ctrl" | "control" => Modifiers::CONTROL,
+        "shift" => Modifiers::SHIFT,
+        "alt" | "option" | "altgr" => Modifiers::ALT,
+        "meta" | "cmd" | "command" | "super" => Modifiers::META,
+        "primary" | "cmdorctrl" if cfg!(target_os = "macos") => Modifiers::META,
+        "primary" | "cmdorctrl" => Modifiers::CONTROL,
+        _ => return None,
+    };
+    Some(modifier)
+}
+
+fn named_key_from_str(name: &str) -> Option<NamedKey> {
+    let name = name.to_lowercase();
+    let key = match name.as_str() {
+        "enter" | "return" => NamedKey::Enter,
+        "escape" | "esc" => NamedKey::Escape,
+        "tab" => NamedKey::Tab,
+        "space" => NamedKey::Space,
+        "backspace" => NamedKey::Backspace,
+        "delete" | "del" => NamedKey::Delete,
+        "insert" => NamedKey::Insert,
+        "home" => NamedKey::Home,
+        "end" => NamedKey::End,
+        "pageup" => NamedKey::PageUp,
+        "pagedown" => NamedKey::PageDown,
+        "arrowup" | "up" => NamedKey::ArrowUp,
+        "arrowdown" | "down" => NamedKey::ArrowDown,
+        "arrowleft" | "left" => NamedKey::ArrowLeft,
+        "arrowright" | "right" => NamedKey::ArrowRight,
+        "f1" => NamedKey::F1,
+        "f2" => NamedKey::F2,
+        "f3" => NamedKey::F3,
+        "f4" => NamedKey::F4,
+        "f5" => NamedKey::F5,
+        "f6" => NamedKey::F6,
+        "f7" => NamedKey::F7,
+        "f8" => NamedKey::F8,
+        "f9" => NamedKey::F9,
+        "f10" => NamedKey::F10,
+        "f11" => NamedKey::F11,
+        "f12" => NamedKey::F12,
+        _ => return None,
+    };
+    Some(key)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_hotkeys() {
+        let hotkey = HotKey::parse("Ctrl+Shift+P").unwrap();
+        assert_eq!(hotkey.mods, Modifiers::CONTROL | Modifiers::SHIFT);
+        assert_eq!(hotkey.key, Key::Character("P".into()));
+        assert_eq!(hotkey.to_string(), "Ctrl+Shift+P");
+        assert_eq!(hotkey, HotKey::parse("ctrl+shift+p").unwrap());
+
+        let hotkey = HotKey::parse("alt+f4").unwrap();
+        assert_eq!(
+            hotkey,
+            HotKey::new(Modifiers::ALT, Key::Named(NamedKey::F4))
+        );
+
+        let hotkey = HotKey::parse("Ctrl++").unwrap();
+        assert_eq!(hotkey.key, Key::Character("+".into()));
+
+        assert_eq!(HotKey::parse("Escape").unwrap().mods, Modifiers::empty());
+        assert_eq!(HotKey::parse("Hyper+K"), None);
+        assert_eq!(HotKey::parse("Ctrl+Nope"), None);
+
+        assert_eq!(
+            split_key_press("AltGr+Shift++"),
+            Some((Modifiers::ALT | Modifiers::SHIFT, "+"))
+        );
+        assert_eq!(split_key_press("k"), Some((Modifiers::empty(), "k")));
+        assert_eq!(split_key_press("Hyper+Ctrl+k"), None);
+        assert_eq!(
+            split_key_press_lenient("Hyper+Ctrl+k"),
+            Some((Modifiers::CONTROL, "k"))
+        );
+    }
+
+    #[test]
+    fn parse_key_bindings() {
+        let binding = KeyBinding::parse("ctrl+k ctrl+s").unwrap();
+        assert_eq!(binding.0.len(), 2);
+        assert_eq!(binding.single(), None);
+        assert_eq!(binding.to_string(), "Ctrl+K Ctrl+S");
+
+        let binding = KeyBinding::parse("Shift+F5").unwrap();
+        assert_eq!(
+            binding.single(),
+            Some(&HotKey::new(Modifiers::SHIFT, Key::Named(NamedKey::F5)))
+        );
+
+        assert_eq!(KeyBinding::parse(""), None);
+        assert_eq!(KeyBinding::parse("ctrl+k nope"), None);
+    }
+}
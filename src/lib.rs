@@ -182,6 +182,8 @@ mod app;
 mod app_handle;
 pub(crate) mod app_state;
 mod clipboard;
+pub mod clock;
+pub mod command;
 pub mod context;
 pub mod dropped_file;
 pub mod easing;
@@ -190,11 +192,14 @@ pub mod ext_event;
 pub mod file;
 #[cfg(any(feature = "rfd-async-std", feature = "rfd-tokio"))]
 pub mod file_action;
+pub mod gesture;
 pub(crate) mod id;
 mod inspector;
 pub mod keyboard;
 pub mod menu;
 mod nav;
+#[cfg(feature = "serde")]
+pub mod persist;
 pub mod pointer;
 mod profiler;
 mod renderer;
@@ -219,9 +224,9 @@ pub use app_state::AppState;
 pub use clipboard::{Clipboard, ClipboardError};
 pub use floem_reactive as reactive;
 pub use floem_renderer::text;
-use floem_renderer::Renderer;
+pub use floem_renderer::Renderer;
 pub use id::ViewId;
-pub use macros::State;
+pub use macros::{FormFields, State};
 pub use peniko;
 pub use peniko::kurbo;
 pub use screen_layout::ScreenLayout;
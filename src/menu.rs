@@ -1,5 +1,7 @@
 use std::sync::atomic::AtomicU64;
 
+use crate::keyboard::{HotKey, KeyBinding};
+
 /// An entry in a menu.
 ///
 /// An entry is either a [`MenuItem`], a submenu (i.e. [`Menu`]).
@@ -60,9 +62,8 @@ impl Menu {
                 MenuEntry::Item(item) => {
                     menu.add_item(
                         item.id as u32,
-                        &item.title,
-                        // item.key.as_ref(),
-                        item.selected,
+                        &item.platform_title(),
+                        item.platform_check(),
                         item.enabled,
                     );
                 }
@@ -77,11 +78,29 @@ impl Menu {
     }
 }
 
+/// The check state shown next to a [`MenuItem`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum MenuCheck {
+    /// An item that toggles something on or off.
+    Checkbox(bool),
+    /// An item that's one of a group of mutually exclusive options.
+    Radio(bool),
+}
+
+impl MenuCheck {
+    pub fn is_checked(self) -> bool {
+        match self {
+            MenuCheck::Checkbox(checked) | MenuCheck::Radio(checked) => checked,
+        }
+    }
+}
+
 pub struct MenuItem {
     pub(crate) id: u64,
     pub(crate) title: String,
-    // key: Option<HotKey>,
-    selected: Option<bool>,
+    pub(crate) key: Option<HotKey>,
+    pub(crate) check: Option<MenuCheck>,
+    pub(crate) icon: Option<String>,
     pub(crate) enabled: bool,
     pub(crate) action: Option<Box<dyn Fn()>>,
 }
@@ -99,13 +118,31 @@ impl MenuItem {
         Self {
             id,
             title: title.into(),
-            // key: None,
-            selected: None,
+            key: None,
+            check: None,
+            icon: None,
             enabled: true,
             action: None,
         }
     }
 
+    /// Creates an item that runs the registered [`Command`](crate::command::Command) with the
+    /// id.
+    ///
+    /// The item shows the title of the command and its key binding if that's a single shortcut.
+    /// It's disabled if the command isn't registered or enabled when the menu is created.
+    pub fn command(id: &str) -> Self {
+        let Some(command) = crate::command::command(id) else {
+            return Self::new(id).enabled(false);
+        };
+        let mut item = Self::new(command.title()).enabled(command.is_enabled());
+        item.key = command.key_binding().and_then(KeyBinding::single).cloned();
+        let id = id.to_string();
+        item.action(move || {
+            crate::command::run_command(&id);
+        })
+    }
+
     pub fn action(mut self, action: impl Fn() + 'static) -> Self {
         self.action = Some(Box::new(action));
         self
@@ -115,4 +152,78 @@ impl MenuItem {
         self.enabled = enabled;
         self
     }
+
+    /// Sets the keyboard shortcut of the item.
+    ///
+    /// The shortcut is shown in the menu. For items of the window menu, the action is also run
+    /// when the shortcut is pressed in the window and the focused view doesn't handle it, without
+    /// opening the menu.
+    pub fn key(mut self, key: HotKey) -> Self {
+        self.key = Some(key);
+        self
+    }
+
+    /// Shows a checkmark next to the item when `checked` is `true`.
+    pub fn checked(mut self, checked: bool) -> Self {
+        self.check = Some(MenuCheck::Checkbox(checked));
+        self
+    }
+
+    /// Shows the item as an option of a group, which is marked when `selected` is `true`.
+    pub fn radio(mut self, selected: bool) -> Self {
+        self.check = Some(MenuCheck::Radio(selected));
+        self
+    }
+
+    /// Sets an SVG icon that's shown before the title.
+    ///
+    /// Native menus don't show icons.
+    pub fn icon(mut self, svg: impl Into<String>) -> Self {
+        self.icon = Some(svg.into());
+        self
+    }
+
+    /// The title used for native menus. On Windows, the shortcut follows a tab, which is how
+    /// Win32 menus take the accelerator shown in their shortcut column. The menus of
+    /// `floem_winit` have no accelerator field on the other platforms, so the shortcut isn't
+    /// shown there, though it still works while the window is focused.
+    fn platform_title(&self) -> String {
+        match &self.key {
+            Some(key) if cfg!(target_os = "windows") => format!("{}\t{key}", self.title),
+            _ => self.title.clone(),
+        }
+    }
+
+    /// The native check state of the item. Native menus mark the selected option of a group of
+    /// radio items with the same checkmark as checkbox items.
+    fn platform_check(&self) -> Option<bool> {
+        self.check.map(MenuCheck::is_checked)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn platform_titles() {
+        let key = HotKey::parse("ctrl+s").unwrap();
+        let item = MenuItem::new("Save").key(key.clone());
+        if cfg!(target_os = "windows") {
+            assert_eq!(item.platform_title(), format!("Save\t{key}"));
+        } else {
+            assert_eq!(item.platform_title(), "Save");
+        }
+        assert_eq!(item.platform_check(), None);
+
+        let item = MenuItem::new("Wrap").checked(true);
+        assert_eq!(item.platform_title(), "Wrap");
+        assert_eq!(item.platform_check(), Some(true));
+
+        let selected = MenuItem::new("Light").radio(true);
+        let other = MenuItem::new("Dark").radio(false);
+        assert_eq!(selected.platform_title(), "Light");
+        assert_eq!(selected.platform_check(), Some(true));
+        assert_eq!(other.platform_check(), Some(false));
+    }
 }
@@ -1,10 +1,14 @@
 use floem_winit::keyboard::NamedKey;
 use peniko::kurbo::{Point, Rect};
 
-use crate::{app_state::AppState, id::ViewId, view::view_tab_navigation};
+use crate::{
+    app_state::AppState,
+    id::ViewId,
+    view::{view_is_within, view_tab_navigation},
+};
 
 pub(crate) fn view_arrow_navigation(key: NamedKey, app_state: &mut AppState, view: ViewId) {
-    let focused = match app_state.focus {
+    let focused = match app_state.focus.filter(|id| view_is_within(view, *id)) {
         Some(id) => id,
         None => {
             view_tab_navigation(
@@ -44,6 +48,7 @@ pub(crate) fn view_arrow_navigation(key: NamedKey, app_state: &mut AppState, vie
             && center_target.contains(layout.center())
             && app_state.can_focus(*id)
             && *id != focused
+            && view_is_within(view, *id)
     });
 
     let mut new_focus = None;
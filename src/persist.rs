@@ -0,0 +1,308 @@
+//! State that survives a restart of the application.
+//!
+//! A [`persisted_signal`] is a signal whose value is saved under a key in the
+//! [persistence store](PersistStore) whenever it changes, and read back from the store when the
+//! signal is created again on the next run. It's meant for the state of the user interface that
+//! users expect to be kept, like split ratios, selected tabs and expanded tree nodes.
+//! [`WindowConfig::persisted`](crate::window::WindowConfig::persisted) does the same for the size
+//! and position of a window.
+//!
+//! By default the values are kept in a JSON file in the data directory of the user, named after
+//! the executable, or only in memory when the user has no data directory.
+//! [`set_persist_store`] replaces the store, like with a [`JsonFileStore`] at another path, or
+//! with a [`MemoryStore`] in tests.
+//!
+//! This module needs the `serde` feature.
+//!
+//! ```rust
+//! # use floem::persist::{persisted_signal, set_persist_store, MemoryStore};
+//! # use floem::reactive::{SignalGet, SignalUpdate};
+//! set_persist_store(MemoryStore::default());
+//!
+//! let ratio = persisted_signal("sidebar.ratio", 0.25);
+//! ratio.set(0.4);
+//!
+//! // The next time the signal is created, it starts with the saved value.
+//! let ratio = persisted_signal("sidebar.ratio", 0.25);
+//! assert_eq!(ratio.get(), 0.4);
+//! ```
+
+use std::{
+    cell::{Cell, RefCell},
+    collections::HashMap,
+    path::{Path, PathBuf},
+    rc::Rc,
+    time::Duration,
+};
+
+use floem_reactive::{create_effect, create_rw_signal, RwSignal, SignalWith};
+use serde::{de::DeserializeOwned, Serialize};
+use serde_json::{Map, Value};
+
+use crate::action::exec_after;
+
+/// How long after a persisted signal changes the store is flushed, so that a burst of changes is
+/// written once.
+const FLUSH_DELAY: Duration = Duration::from_millis(500);
+
+/// Where persisted values are kept. See the [module documentation](self).
+pub trait PersistStore {
+    /// The value saved under the key, if there is one.
+    fn get(&self, key: &str) -> Option<Value>;
+
+    /// Saves the value under the key, replacing any value that's already saved there.
+    fn set(&self, key: &str, value: Value);
+
+    /// Removes the value saved under the key.
+    fn remove(&self, key: &str);
+
+    /// Writes the values that have changed to permanent storage, if the store has any.
+    fn flush(&self) {}
+}
+
+/// A [`PersistStore`] that keeps the values in memory, for tests.
+#[derive(Debug, Default)]
+pub struct MemoryStore {
+    values: RefCell<HashMap<String, Value>>,
+}
+
+impl PersistStore for MemoryStore {
+    fn get(&self, key: &str) -> Option<Value> {
+        self.values.borrow().get(key).cloned()
+    }
+
+    fn set(&self, key: &str, value: Value) {
+        self.values.borrow_mut().insert(key.to_string(), value);
+    }
+
+    fn remove(&self, key: &str) {
+        self.values.borrow_mut().remove(key);
+    }
+}
+
+/// A [`PersistStore`] that keeps the values in a JSON file, as an object with a field for each
+/// key.
+///
+/// The file is read when the store is opened. Changes are kept in memory until the store is
+/// [flushed](PersistStore::flush), which replaces the file in a single step so that it isn't left
+/// half written.
+#[derive(Debug)]
+pub struct JsonFileStore {
+    path: PathBuf,
+    values: RefCell<Map<String, Value>>,
+    dirty: Cell<bool>,
+}
+
+impl JsonFileStore {
+    /// Opens the store in the file at the path. A missing or malformed file is treated as an
+    /// empty store, and is replaced on the next flush.
+    pub fn open(path: impl Into<PathBuf>) -> Self {
+        let path = path.into();
+        let values = std::fs::read(&path)
+            .ok()
+            .and_then(|bytes| serde_json::from_slice::<Value>(&bytes).ok())
+            .and_then(|value| match value {
+                Value::Object(values) => Some(values),
+                _ => None,
+            })
+            .unwrap_or_default();
+        Self {
+            path,
+            values: RefCell::new(values),
+            dirty: Cell::new(false),
+        }
+    }
+
+    /// Opens the store of the application named `app_name`, in `state.json` of its directory in
+    /// the data directory of the user. Returns `None` when the data directory isn't known, like
+    /// when `HOME` isn't set.
+    pub fn for_app(app_name: &str) -> Option<Self> {
+        let dir = data_dir()?.join(app_name);
+        Some(Self::open(dir.join("state.json")))
+    }
+
+    /// The path of the file.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    fn write(&self) -> std::io::Result<()> {
+        if let Some(dir) = self.path.parent() {
+            std::fs::create_dir_all(dir)?;
+        }
+        let json = serde_json::to_vec_pretty(&*self.values.borrow())?;
+        let temp = self.path.with_extension("json.tmp");
+        std::fs::write(&temp, json)?;
+        std::fs::rename(&temp, &self.path)
+    }
+}
+
+impl PersistStore for JsonFileStore {
+    fn get(&self, key: &str) -> Option<Value> {
+        self.values.borrow().get(key).cloned()
+    }
+
+    fn set(&self, key: &str, value: Value) {
+        let previous = self
+            .values
+            .borrow_mut()
+            .insert(key.to_string(), value.clone());
+        if previous.as_ref() != Some(&value) {
+            self.dirty.set(true);
+        }
+    }
+
+    fn remove(&self, key: &str) {
+        if self.values.borrow_mut().remove(key).is_some() {
+            self.dirty.set(true);
+        }
+    }
+
+    fn flush(&self) {
+        if self.dirty.get() && self.write().is_ok() {
+            self.dirty.set(false);
+        }
+    }
+}
+
+/// The directory where applications keep the data of the user.
+fn data_dir() -> Option<PathBuf> {
+    // A relative directory would be resolved against the working directory.
+    let var = |name: &str| {
+        std::env::var_os(name)
+            .map(PathBuf::from)
+            .filter(|dir| dir.is_absolute())
+    };
+    if cfg!(target_os = "windows") {
+        var("APPDATA")
+    } else if cfg!(target_os = "macos") {
+        var("HOME").map(|home| home.join("Library").join("Application Support"))
+    } else {
+        var("XDG_DATA_HOME").or_else(|| var("HOME").map(|home| home.join(".local").join("share")))
+    }
+}
+
+thread_local! {
+    static STORE: RefCell<Option<Rc<dyn PersistStore>>> = const { RefCell::new(None) };
+    static FLUSH_SCHEDULED: Cell<bool> = const { Cell::new(false) };
+}
+
+/// Replaces the store of persisted values. This should be done before any persisted signal is
+/// created, since signals read their value from the store when they're created.
+pub fn set_persist_store(store: impl PersistStore + 'static) {
+    let store: Rc<dyn PersistStore> = Rc::new(store);
+    if let Some(previous) = STORE.replace(Some(store)) {
+        previous.flush();
+    }
+}
+
+/// The store of persisted values, which is a [`JsonFileStore`] named after the executable unless
+/// it's replaced with [`set_persist_store`]. Without a data directory, it's a [`MemoryStore`].
+pub fn persist_store() -> Rc<dyn PersistStore> {
+    STORE.with_borrow_mut(|store| {
+        store
+            .get_or_insert_with(|| {
+                let app_name = std::env::current_exe()
+                    .ok()
+                    .and_then(|exe| exe.file_stem().map(|name| name.to_string_lossy().into()))
+                    .unwrap_or_else(|| "floem".to_string());
+                match JsonFileStore::for_app(&app_name) {
+                    Some(store) => Rc::new(store) as Rc<dyn PersistStore>,
+                    None => Rc::new(MemoryStore::default()),
+                }
+            })
+            .clone()
+    })
+}
+
+/// Writes the persisted values that have changed. This is done shortly after a persisted signal
+/// changes, and when the application exits.
+pub fn flush_persisted() {
+    FLUSH_SCHEDULED.set(false);
+    if let Some(store) = STORE.with_borrow(|store| store.clone()) {
+        store.flush();
+    }
+}
+
+/// Reads the value saved under the key in the [store](persist_store), if there is one and it can
+/// be deserialized.
+pub fn load_persisted<T: DeserializeOwned>(key: &str) -> Option<T> {
+    persist_store()
+        .get(key)
+        .and_then(|value| serde_json::from_value(value).ok())
+}
+
+/// Saves the value under the key in the [store](persist_store), and schedules a flush.
+pub fn save_persisted<T: Serialize + ?Sized>(key: &str, value: &T) {
+    let Ok(value) = serde_json::to_value(value) else {
+        return;
+    };
+    persist_store().set(key, value);
+    if !FLUSH_SCHEDULED.replace(true) {
+        exec_after(FLUSH_DELAY, |_| flush_persisted());
+    }
+}
+
+/// Creates a signal that starts with the value saved under the key in the
+/// [store](persist_store), or with `default` if there is none, and saves its value there whenever
+/// it changes. See the [module documentation](self).
+pub fn persisted_signal<T>(key: impl Into<String>, default: T) -> RwSignal<T>
+where
+    T: Serialize + DeserializeOwned + 'static,
+{
+    let key = key.into();
+    let signal = create_rw_signal(load_persisted(&key).unwrap_or(default));
+    create_effect(move |first: Option<()>| {
+        signal.with(|value| {
+            // The first run only subscribes to the signal, since the value came from the store.
+            if first.is_some() {
+                save_persisted(&key, value);
+            }
+        });
+    });
+    signal
+}
+
+#[cfg(test)]
+mod tests {
+    use floem_reactive::{SignalGet, SignalUpdate};
+
+    use super::*;
+
+    #[test]
+    fn persisted_signals_restore_their_values() {
+        set_persist_store(MemoryStore::default());
+
+        let tabs = persisted_signal("test.tabs", vec!["a".to_string()]);
+        assert_eq!(tabs.get(), ["a"]);
+        tabs.update(|tabs| tabs.push("b".to_string()));
+        assert_eq!(
+            persisted_signal("test.tabs", Vec::<String>::new()).get(),
+            ["a", "b"]
+        );
+
+        // A saved value of another type is ignored.
+        assert_eq!(persisted_signal("test.tabs", 3).get(), 3);
+    }
+
+    #[test]
+    fn json_file_store_round_trip() {
+        let dir = std::env::temp_dir().join(format!("floem-persist-{}", std::process::id()));
+        let path = dir.join("state.json");
+        let _ = std::fs::remove_file(&path);
+
+        let store = JsonFileStore::open(&path);
+        assert_eq!(store.get("ratio"), None);
+        store.set("ratio", Value::from(0.5));
+        store.set("tab", Value::from("files"));
+        store.remove("tab");
+        // Nothing is written until the store is flushed.
+        assert!(!path.exists());
+        store.flush();
+
+        let store = JsonFileStore::open(&path);
+        assert_eq!(store.get("ratio"), Some(Value::from(0.5)));
+        assert_eq!(store.get("tab"), None);
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
@@ -55,16 +55,72 @@ impl PointerButton {
     }
 }
 
+/// Identifies the pointer that produced an event, so that simultaneous touches can be told
+/// apart.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum PointerId {
+    #[default]
+    Mouse,
+    /// A finger on a touch screen, with the id the platform gave the touch.
+    Touch(u64),
+}
+
+impl PointerId {
+    pub fn is_touch(self) -> bool {
+        matches!(self, PointerId::Touch(_))
+    }
+}
+
 #[derive(Debug, Clone)]
+#[non_exhaustive]
 pub struct PointerInputEvent {
     pub pos: Point,
     pub button: PointerButton,
     pub modifiers: Modifiers,
     pub count: u8,
+    pub pointer_id: PointerId,
 }
 
 #[derive(Debug, Clone)]
+#[non_exhaustive]
 pub struct PointerMoveEvent {
     pub pos: Point,
     pub modifiers: Modifiers,
+    pub pointer_id: PointerId,
+}
+
+impl PointerInputEvent {
+    /// A press or release of a mouse button.
+    pub fn new(pos: Point, button: PointerButton, modifiers: Modifiers, count: u8) -> Self {
+        Self {
+            pos,
+            button,
+            modifiers,
+            count,
+            pointer_id: PointerId::Mouse,
+        }
+    }
+
+    /// Sets the pointer that produced the event, like a finger on a touch screen.
+    pub fn with_pointer_id(mut self, pointer_id: PointerId) -> Self {
+        self.pointer_id = pointer_id;
+        self
+    }
+}
+
+impl PointerMoveEvent {
+    /// A movement of the mouse.
+    pub fn new(pos: Point, modifiers: Modifiers) -> Self {
+        Self {
+            pos,
+            modifiers,
+            pointer_id: PointerId::Mouse,
+        }
+    }
+
+    /// Sets the pointer that produced the event, like a finger on a touch screen.
+    pub fn with_pointer_id(mut self, pointer_id: PointerId) -> Self {
+        self.pointer_id = pointer_id;
+        self
+    }
 }
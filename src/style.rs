@@ -46,18 +46,31 @@ pub trait StylePropValue: Clone + PartialEq + Debug {
     fn interpolate(&self, _other: &Self, _value: f64) -> Option<Self> {
         None
     }
+
+    /// The distance between two values, if they can be measured.
+    ///
+    /// This is used to carry velocity over when an animated value is given a new target.
+    fn distance(&self, _other: &Self) -> Option<f64> {
+        None
+    }
 }
 
 impl StylePropValue for i32 {
     fn interpolate(&self, other: &Self, value: f64) -> Option<Self> {
         Some((*self as f64 + (*other as f64 - *self as f64) * value).round() as i32)
     }
+    fn distance(&self, other: &Self) -> Option<f64> {
+        Some((*other as f64 - *self as f64).abs())
+    }
 }
 impl StylePropValue for bool {}
 impl StylePropValue for f32 {
     fn interpolate(&self, other: &Self, value: f64) -> Option<Self> {
         Some(*self * (1.0 - value as f32) + *other * value as f32)
     }
+    fn distance(&self, other: &Self) -> Option<f64> {
+        Some((*other as f64 - *self as f64).abs())
+    }
 }
 impl StylePropValue for u16 {
     fn interpolate(&self, other: &Self, value: f64) -> Option<Self> {
@@ -73,6 +86,9 @@ impl StylePropValue for f64 {
     fn interpolate(&self, other: &Self, value: f64) -> Option<Self> {
         Some(*self * (1.0 - value) + *other * value)
     }
+    fn distance(&self, other: &Self) -> Option<f64> {
+        Some((other - self).abs())
+    }
 }
 impl StylePropValue for Display {}
 impl StylePropValue for Position {}
@@ -163,6 +179,9 @@ impl StylePropValue for Px {
     fn interpolate(&self, other: &Self, value: f64) -> Option<Self> {
         self.0.interpolate(&other.0, value).map(Px)
     }
+    fn distance(&self, other: &Self) -> Option<f64> {
+        self.0.distance(&other.0)
+    }
 }
 impl StylePropValue for PxPctAuto {
     fn debug_view(&self) -> Option<Box<dyn View>> {
@@ -240,6 +259,17 @@ impl StylePropValue for Color {
             .round() as u8;
         Some(Color { r, g, b, a })
     }
+
+    fn distance(&self, other: &Self) -> Option<f64> {
+        let channel = |a: u8, b: u8| (b as f64 - a as f64).powi(2);
+        Some(
+            (channel(self.r, other.r)
+                + channel(self.g, other.g)
+                + channel(self.b, other.b)
+                + channel(self.a, other.a))
+            .sqrt(),
+        )
+    }
 }
 
 impl StylePropValue for Gradient {
@@ -932,7 +962,7 @@ impl<T: StylePropValue> TransitionState<T> {
         }
         if self.transition.is_some() {
             self.active = Some(ActiveTransition {
-                start: Instant::now(),
+                start: crate::clock::now(),
                 before: before.clone(),
                 current: before.clone(),
                 after: after.clone(),
@@ -1211,6 +1241,12 @@ impl Style {
                 self.apply_mut(map);
             }
         }
+        if interact_state.is_invalid {
+            if let Some(mut map) = self.get_nested_map(StyleSelector::Invalid.to_key()) {
+                map.apply_interact_state(interact_state, screen_size_bp);
+                self.apply_mut(map);
+            }
+        }
         if interact_state.is_disabled {
             if let Some(mut map) = self.get_nested_map(StyleSelector::Disabled.to_key()) {
                 map.apply_interact_state(interact_state, screen_size_bp);
@@ -1363,6 +1399,7 @@ pub enum StyleSelector {
     Active,
     Dragging,
     Selected,
+    Invalid,
 }
 
 style_key_selector!(hover, StyleSelectors::new().set(StyleSelector::Hover, true));
@@ -1387,6 +1424,10 @@ style_key_selector!(
     selected,
     StyleSelectors::new().set(StyleSelector::Selected, true)
 );
+style_key_selector!(
+    invalid,
+    StyleSelectors::new().set(StyleSelector::Invalid, true)
+);
 
 impl StyleSelector {
     fn to_key(self) -> StyleKey {
@@ -1398,6 +1439,7 @@ impl StyleSelector {
             StyleSelector::Active => active(),
             StyleSelector::Dragging => dragging(),
             StyleSelector::Selected => selected(),
+            StyleSelector::Invalid => invalid(),
         }
     }
 }
@@ -1814,6 +1856,13 @@ impl Style {
         self.selector(StyleSelector::Disabled, style)
     }
 
+    /// The style to apply when the view or one of its parents is marked as
+    /// [invalid](crate::views::Decorators::invalid), like a form field that doesn't pass
+    /// validation.
+    pub fn invalid(self, style: impl FnOnce(Style) -> Style) -> Self {
+        self.selector(StyleSelector::Invalid, style)
+    }
+
     pub fn active(self, style: impl FnOnce(Style) -> Style) -> Self {
         self.selector(StyleSelector::Active, style)
     }
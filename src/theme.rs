@@ -3,12 +3,16 @@ use crate::{
     unit::{DurationUnitExt, UnitExt},
     views::{
         dropdown::{self},
-        scroll,
+        form, scroll,
         slider::{self, SliderClass},
-        ButtonClass, CheckboxClass, LabelClass, LabelCustomStyle, LabeledCheckboxClass,
+        ButtonClass, CheckboxClass, CommandPaletteClass, CommandPaletteItemClass,
+        CommandPaletteKeyClass, DialogClass, DialogScrimClass, DockActiveTabClass,
+        DockDropPreviewClass, DockInsertAfterClass, DockInsertBeforeClass, DockTabClass,
+        DockTabStripClass, LabelClass, LabelCustomStyle, LabeledCheckboxClass,
         LabeledRadioButtonClass, ListClass, ListItemClass, PlaceholderTextClass, RadioButtonClass,
-        RadioButtonDotClass, TextInputClass, ToggleButtonCircleRad, ToggleButtonClass,
-        ToggleButtonInset, TooltipClass,
+        RadioButtonDotClass, SplitDividerClass, TextInputClass, ToggleButtonCircleRad,
+        ToggleButtonClass, ToggleButtonInset, TooltipClass, TreeChevronClass, TreeItemClass,
+        TreeViewClass,
     },
 };
 use peniko::{Brush, Color};
@@ -187,6 +191,8 @@ pub(crate) fn default_theme() -> Theme {
 
     const FONT_SIZE: f32 = 12.0;
 
+    let invalid_color = Color::rgb8(196, 43, 28);
+
     let input_style = Style::new()
         .background(Color::WHITE)
         .hover(|s| s.background(light_hover_bg_color))
@@ -195,6 +201,7 @@ pub(crate) fn default_theme() -> Theme {
         .apply(focus_style.clone())
         .cursor(CursorStyle::Text)
         .padding(padding)
+        .invalid(|s| s.border_color(invalid_color))
         .disabled(|s| {
             s.background(Color::rgb8(180, 188, 175).multiply_alpha(0.3))
                 .color(Color::GRAY)
@@ -214,8 +221,15 @@ pub(crate) fn default_theme() -> Theme {
 
     let theme = Style::new()
         .class(ListClass, |s| {
-            s.focus(|s| s.class(ListItemClass, |_| item_focused_style))
-                .class(ListItemClass, |_| item_unfocused_style)
+            s.focus(|s| s.class(ListItemClass, |_| item_focused_style.clone()))
+                .class(ListItemClass, |_| item_unfocused_style.clone())
+        })
+        .class(TreeViewClass, |s| {
+            s.focus(|s| s.class(TreeItemClass, |_| item_focused_style))
+                .class(TreeItemClass, |_| item_unfocused_style)
+                .class(TreeChevronClass, |s| {
+                    s.size(12, 12).margin_right(4).flex_shrink(0.)
+                })
         })
         .class(LabeledCheckboxClass, |_| labeled_checkbox_style)
         .class(CheckboxClass, |_| checkbox_style)
@@ -223,6 +237,9 @@ pub(crate) fn default_theme() -> Theme {
         .class(RadioButtonDotClass, |_| radio_button_dot_style)
         .class(LabeledRadioButtonClass, |_| labeled_radio_button_style)
         .class(TextInputClass, |_| input_style)
+        .class(form::FormErrorClass, |s| {
+            s.color(invalid_color).font_size(12.).margin_top(2.)
+        })
         .class(ButtonClass, |_| button_style)
         .apply_custom(
             scroll::ScrollCustomStyle::new()
@@ -270,6 +287,62 @@ pub(crate) fn default_theme() -> Theme {
                 .box_shadow_v_offset(2.0)
                 .box_shadow_color(Color::BLACK.multiply_alpha(0.2))
         })
+        .class(DialogScrimClass, |s| {
+            s.background(Color::BLACK.multiply_alpha(0.3))
+        })
+        .class(DialogClass, |s| {
+            s.border(0.5)
+                .border_color(Color::rgb8(140, 140, 140))
+                .border_radius(border_radius)
+                .padding(padding * 3.0)
+                .background(Color::WHITE_SMOKE)
+                .box_shadow_blur(10.0)
+                .box_shadow_v_offset(4.0)
+                .box_shadow_color(Color::BLACK.multiply_alpha(0.3))
+        })
+        .class(CommandPaletteClass, |s| s.width(500).row_gap(padding))
+        .class(CommandPaletteItemClass, |s| {
+            s.width_full()
+                .justify_between()
+                .padding(padding)
+                .border_radius(border_radius)
+                .hover(|s| s.background(hover_bg_color))
+        })
+        .class(CommandPaletteKeyClass, |s| {
+            s.margin_left(20).color(Color::GRAY)
+        })
+        .class(SplitDividerClass, |s| {
+            s.width(4)
+                .height(4)
+                .flex_shrink(0.)
+                .background(Color::rgb8(205, 205, 205))
+                .hover(|s| s.background(Color::rgb8(140, 140, 140)))
+                .focus_visible(|s| s.background(Color::rgb8(114, 74, 140)))
+        })
+        .class(DockTabStripClass, |s| {
+            s.width_full()
+                .flex_shrink(0.)
+                .background(Color::rgb8(225, 225, 225))
+        })
+        .class(DockTabClass, |s| {
+            s.padding_horiz(padding * 2.0)
+                .padding_vert(padding)
+                .border_right(0.5)
+                .border_color(Color::rgb8(205, 205, 205))
+                .hover(|s| s.background(hover_bg_color))
+        })
+        .class(DockActiveTabClass, |s| s.background(Color::WHITE))
+        .class(DockInsertBeforeClass, |s| {
+            s.border_left(2.0).border_color(Color::rgb8(114, 74, 140))
+        })
+        .class(DockInsertAfterClass, |s| {
+            s.border_right(2.0).border_color(Color::rgb8(114, 74, 140))
+        })
+        .class(DockDropPreviewClass, |s| {
+            s.background(Color::rgb8(114, 74, 140).multiply_alpha(0.2))
+                .border(1.0)
+                .border_color(Color::rgb8(114, 74, 140))
+        })
         .class(dropdown::DropdownClass, |s| {
             s.width(75)
                 .padding(3)
@@ -293,6 +366,12 @@ pub(crate) fn default_theme() -> Theme {
         .font_size(FONT_SIZE)
         .color(Color::BLACK);
 
+    #[cfg(feature = "editor")]
+    let theme = theme.class(
+        crate::views::editor::symbols::OutlineCurrentItemClass,
+        |s| s.background(selected_bg_color),
+    );
+
     Theme {
         background: Color::rgb8(248, 248, 248),
         style: Rc::new(theme),
@@ -37,6 +37,10 @@ pub(crate) enum UpdateMessage {
         id: ViewId,
         is_disabled: bool,
     },
+    Invalid {
+        id: ViewId,
+        is_invalid: bool,
+    },
     RequestPaint,
     State {
         id: ViewId,
@@ -547,17 +547,21 @@ pub(crate) fn paint_border(
 }
 
 /// Tab navigation finds the next or previous view with the `keyboard_navigatable` status in the tree.
+///
+/// Only views within `root_view` are considered, which lets modal dialogs trap the focus.
 #[allow(dead_code)]
 pub(crate) fn view_tab_navigation(root_view: ViewId, app_state: &mut AppState, backwards: bool) {
     let start = app_state
         .focus
-        .unwrap_or(app_state.prev_focus.unwrap_or(root_view));
+        .or(app_state.prev_focus)
+        .filter(|id| view_is_within(root_view, *id))
+        .unwrap_or(root_view);
 
     let tree_iter = |id: ViewId| {
         if backwards {
             view_tree_previous(root_view, id).unwrap_or_else(|| view_nested_last_child(root_view))
         } else {
-            view_tree_next(id).unwrap_or(root_view)
+            view_tree_next(root_view, id).unwrap_or(root_view)
         }
     };
 
@@ -570,19 +574,32 @@ pub(crate) fn view_tab_navigation(root_view: ViewId, app_state: &mut AppState, b
     app_state.update_focus(new_focus, true);
 }
 
+/// Returns `true` if `id` is `root_view` or one of its direct or indirect children.
+pub(crate) fn view_is_within(root_view: ViewId, id: ViewId) -> bool {
+    let mut ancestor = Some(id);
+    while let Some(id) = ancestor {
+        if id == root_view {
+            return true;
+        }
+        ancestor = id.parent();
+    }
+    false
+}
+
 /// Get the next item in the tree, either the first child or the next sibling of this view or of the first parent view
-fn view_tree_next(id: ViewId) -> Option<ViewId> {
+fn view_tree_next(root_view: ViewId, id: ViewId) -> Option<ViewId> {
     if let Some(child) = id.children().into_iter().next() {
         return Some(child);
     }
 
     let mut ancestor = id;
-    loop {
+    while ancestor != root_view {
         if let Some(next_sibling) = view_next_sibling(ancestor) {
             return Some(next_sibling);
         }
         ancestor = ancestor.parent()?;
     }
+    None
 }
 
 /// Get the id of the view after this one (but with the same parent and level of nesting)
@@ -607,6 +624,9 @@ fn view_next_sibling(id: ViewId) -> Option<ViewId> {
 
 /// Get the next item in the tree, the deepest last child of the previous sibling of this view or the parent
 fn view_tree_previous(root_view: ViewId, id: ViewId) -> Option<ViewId> {
+    if id == root_view {
+        return None;
+    }
     view_previous_sibling(id)
         .map(view_nested_last_child)
         .or_else(|| {
@@ -9,7 +9,7 @@ use crate::{
     responsive::ScreenSizeBp,
     style::{
         Background, BorderColor, BorderRadius, BoxShadowProp, LayoutProps, Outline, OutlineColor,
-        Style, StyleClassRef, StyleSelectors,
+        Style, StyleClassRef, StyleSelectors, Transition,
     },
 };
 use bitflags::bitflags;
@@ -18,6 +18,11 @@ use smallvec::SmallVec;
 use std::{cell::RefCell, collections::HashMap, marker::PhantomData, rc::Rc};
 use taffy::tree::NodeId;
 
+#[cfg(not(target_arch = "wasm32"))]
+use std::time::Instant;
+#[cfg(target_arch = "wasm32")]
+use web_time::Instant;
+
 /// A stack of view attributes. Each entry is associated with a view decorator call.
 #[derive(Debug)]
 pub(crate) struct Stack<T> {
@@ -145,6 +150,77 @@ impl IsHiddenState {
     }
 }
 
+#[derive(Debug, Clone, Copy)]
+struct ActiveLayoutAnimation {
+    start: Instant,
+    /// The box the view was painted at when the animation started.
+    from: Rect,
+}
+
+/// Animates a view from its previous layout box to its new one whenever layout moves or resizes it.
+/// See [`Decorators::animate_layout`](crate::views::Decorators::animate_layout).
+pub(crate) struct LayoutAnimation {
+    transition: Transition,
+    /// The box of the view relative to its parent, as of the last `compute_layout`.
+    target: Option<Rect>,
+    active: Option<ActiveLayoutAnimation>,
+}
+
+impl LayoutAnimation {
+    pub(crate) fn new(transition: Transition) -> Self {
+        Self {
+            transition,
+            target: None,
+            active: None,
+        }
+    }
+
+    /// Record the box computed by layout. If it differs from the previous one, an animation is
+    /// started from wherever the view is currently painted.
+    ///
+    /// Returns `true` if an animation was started.
+    pub(crate) fn update(&mut self, rect: Rect, now: Instant) -> bool {
+        let started = match self.target {
+            Some(target) if target != rect => {
+                let from = self.current(target, now).unwrap_or(target);
+                self.active = Some(ActiveLayoutAnimation { start: now, from });
+                true
+            }
+            _ => false,
+        };
+        self.target = Some(rect);
+        started
+    }
+
+    /// Returns the box the view should be painted at, relative to its parent,
+    /// or `None` if the view is not animating.
+    pub(crate) fn step(&mut self, now: Instant) -> Option<Rect> {
+        let target = self.target?;
+        let current = self.current(target, now);
+        if current.is_none() {
+            self.active = None;
+        }
+        current
+    }
+
+    fn current(&self, target: Rect, now: Instant) -> Option<Rect> {
+        let active = self.active?;
+        let time = now.saturating_duration_since(active.start);
+        let time_percent = time.as_secs_f64() / self.transition.duration.as_secs_f64();
+        if time >= self.transition.duration && self.transition.easing.finished(time_percent) {
+            return None;
+        }
+        let t = self.transition.easing.eval(time_percent);
+        let lerp = |from: f64, to: f64| from + (to - from) * t;
+        Some(Rect::new(
+            lerp(active.from.x0, target.x0),
+            lerp(active.from.y0, target.y0),
+            lerp(active.from.x1, target.x1),
+            lerp(active.from.y1, target.y1),
+        ))
+    }
+}
+
 /// View state stores internal state associated with a view which is owned and managed by Floem.
 pub struct ViewState {
     pub(crate) node: NodeId,
@@ -158,6 +234,7 @@ pub struct ViewState {
     pub(crate) layout_props: LayoutProps,
     pub(crate) view_style_props: ViewStyleProps,
     pub(crate) animations: Stack<Animation>,
+    pub(crate) layout_animation: Option<LayoutAnimation>,
     pub(crate) classes: Vec<StyleClassRef>,
     pub(crate) dragging_style: Option<Style>,
     pub(crate) combined_style: Style,
@@ -188,6 +265,7 @@ impl ViewState {
             request_style_recursive: false,
             has_style_selectors: StyleSelectors::default(),
             animations: Default::default(),
+            layout_animation: None,
             classes: Vec::new(),
             combined_style: Style::new(),
             taffy_style: taffy::style::Style::DEFAULT,
@@ -301,3 +379,56 @@ impl ViewState {
         self.cleanup_listener = Some(Rc::new(action));
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::*;
+
+    fn animation() -> LayoutAnimation {
+        LayoutAnimation::new(Transition::linear(Duration::from_millis(100)))
+    }
+
+    #[test]
+    fn layout_animation_interpolates_between_boxes() {
+        let start = Instant::now();
+        let mut animation = animation();
+        // The first layout isn't animated.
+        assert!(!animation.update(Rect::new(0.0, 0.0, 10.0, 10.0), start));
+        assert_eq!(animation.step(start), None);
+        // Neither is a layout that doesn't change the box.
+        assert!(!animation.update(Rect::new(0.0, 0.0, 10.0, 10.0), start));
+        assert_eq!(animation.step(start), None);
+
+        assert!(animation.update(Rect::new(100.0, 0.0, 120.0, 20.0), start));
+        assert_eq!(animation.step(start), Some(Rect::new(0.0, 0.0, 10.0, 10.0)));
+        assert_eq!(
+            animation.step(start + Duration::from_millis(50)),
+            Some(Rect::new(50.0, 0.0, 65.0, 15.0))
+        );
+        assert_eq!(animation.step(start + Duration::from_millis(100)), None);
+        assert!(animation.active.is_none());
+    }
+
+    #[test]
+    fn layout_animation_retargets_from_the_painted_box() {
+        let start = Instant::now();
+        let mut animation = animation();
+        animation.update(Rect::new(0.0, 0.0, 10.0, 10.0), start);
+        animation.update(Rect::new(100.0, 0.0, 110.0, 10.0), start);
+
+        // Halfway there, the view is moved back, so it turns around where it's painted.
+        let halfway = start + Duration::from_millis(50);
+        assert!(animation.update(Rect::new(0.0, 0.0, 10.0, 10.0), halfway));
+        assert_eq!(
+            animation.step(halfway),
+            Some(Rect::new(50.0, 0.0, 60.0, 10.0))
+        );
+        assert_eq!(
+            animation.step(halfway + Duration::from_millis(50)),
+            Some(Rect::new(25.0, 0.0, 35.0, 10.0))
+        );
+        assert_eq!(animation.step(halfway + Duration::from_millis(100)), None);
+    }
+}
@@ -1,5 +1,11 @@
-use crate::{style_class, views::Decorators, IntoView, View, ViewId};
+use crate::{
+    command::{is_command_enabled, run_command},
+    style_class,
+    views::Decorators,
+    IntoView, View, ViewId,
+};
 use core::ops::FnMut;
+use std::rc::Rc;
 
 style_class!(pub ButtonClass);
 
@@ -27,6 +33,17 @@ impl Button {
             on_press();
         })
     }
+
+    /// Runs the registered [`Command`](crate::command::Command) with the id when the button is
+    /// clicked. The button is disabled while the command isn't registered or enabled.
+    pub fn command(self, id: impl Into<String>) -> Self {
+        let id: Rc<str> = id.into().into();
+        let enabled_id = id.clone();
+        self.disabled(move || !is_command_enabled(&enabled_id))
+            .on_click_stop(move |_| {
+                run_command(&id);
+            })
+    }
 }
 
 pub trait ButtonExt {
@@ -0,0 +1,108 @@
+use floem_reactive::Tracker;
+use peniko::kurbo::Size;
+
+use crate::{
+    context::PaintCx,
+    event::{Event, EventListener, EventPropagation},
+    id::ViewId,
+    pointer::{PointerInputEvent, PointerMoveEvent},
+    style_class,
+    view::View,
+};
+
+use super::Decorators;
+
+style_class!(pub CanvasClass);
+
+/// A view that is painted by a closure. See [`canvas`].
+pub struct Canvas {
+    id: ViewId,
+    paint_fn: Box<dyn Fn(&mut PaintCx, Size)>,
+    tracker: Tracker,
+}
+
+/// A view that is painted by `paint_fn`, for charts, graphs and other custom drawings that
+/// don't need a view type of their own.
+///
+/// `paint_fn` receives the [`PaintCx`], which dereferences to the renderer, and the size of the
+/// canvas. It draws in the canvas' local coordinates and can use the renderer operations
+/// such as `fill`, `stroke`, `draw_text` and `draw_img`, along with [`PaintCx::clip`] and
+/// [`PaintCx::apply_transform`]. Any clip or transform is undone after `paint_fn` returns.
+///
+/// The canvas is repainted whenever a signal that `paint_fn` read during the last paint changes.
+///
+/// ```rust
+/// # use floem::kurbo::Circle;
+/// # use floem::peniko::Color;
+/// # use floem::reactive::{create_rw_signal, SignalGet, SignalUpdate};
+/// # use floem::views::{canvas, Decorators};
+/// # use floem::Renderer;
+/// let radius = create_rw_signal(10.0);
+/// canvas(move |cx, size| {
+///     let circle = Circle::new(size.to_rect().center(), radius.get());
+///     cx.fill(&circle, Color::ORANGE_RED, 0.0);
+/// })
+/// .on_pointer_down(move |_| radius.update(|radius| *radius += 5.0))
+/// .style(|s| s.size(200, 200));
+/// ```
+pub fn canvas(paint_fn: impl Fn(&mut PaintCx, Size) + 'static) -> Canvas {
+    let id = ViewId::new();
+    Canvas {
+        id,
+        paint_fn: Box::new(paint_fn),
+        tracker: Tracker::new(move || id.request_paint()),
+    }
+    .class(CanvasClass)
+}
+
+impl Canvas {
+    /// Called when a pointer button is pressed over the canvas, with the position relative
+    /// to the canvas.
+    pub fn on_pointer_down(self, action: impl Fn(&PointerInputEvent) + 'static) -> Self {
+        self.on_event(EventListener::PointerDown, move |e| {
+            if let Event::PointerDown(e) = e {
+                action(e);
+            }
+            EventPropagation::Continue
+        })
+    }
+
+    /// Called when a pointer moves over the canvas, with the position relative to the canvas.
+    pub fn on_pointer_move(self, action: impl Fn(&PointerMoveEvent) + 'static) -> Self {
+        self.on_event(EventListener::PointerMove, move |e| {
+            if let Event::PointerMove(e) = e {
+                action(e);
+            }
+            EventPropagation::Continue
+        })
+    }
+
+    /// Called when a pointer button is released over the canvas, with the position relative
+    /// to the canvas.
+    pub fn on_pointer_up(self, action: impl Fn(&PointerInputEvent) + 'static) -> Self {
+        self.on_event(EventListener::PointerUp, move |e| {
+            if let Event::PointerUp(e) = e {
+                action(e);
+            }
+            EventPropagation::Continue
+        })
+    }
+}
+
+impl View for Canvas {
+    fn id(&self) -> ViewId {
+        self.id
+    }
+
+    fn debug_name(&self) -> std::borrow::Cow<'static, str> {
+        "Canvas".into()
+    }
+
+    fn paint(&mut self, cx: &mut PaintCx) {
+        let size = self.id.get_size().unwrap_or_default();
+        let paint_fn = &self.paint_fn;
+        cx.save();
+        self.tracker.track(|| paint_fn(cx, size));
+        cx.restore();
+    }
+}
@@ -0,0 +1,154 @@
+use std::rc::Rc;
+
+use floem_reactive::{
+    create_effect, create_memo, create_rw_signal, SignalGet, SignalTrack, SignalUpdate, SignalWith,
+};
+use peniko::Color;
+
+use crate::{
+    command::{commands, fuzzy_score, Command},
+    event::EventListener,
+    keyboard::{Key, NamedKey},
+    style_class,
+    views::{dialog, dyn_stack, h_stack, label, scroll, text_input, v_stack, Decorators, Dialog},
+};
+
+style_class!(pub CommandPaletteClass);
+style_class!(pub CommandPaletteItemClass);
+style_class!(pub CommandPaletteKeyClass);
+
+/// Creates a [`Dialog`] that lists the enabled commands of the
+/// [command registry](crate::command) and runs the chosen one.
+///
+/// Typing filters the commands by fuzzy matching their category and title. The arrow keys move
+/// the selection, and Enter or a click runs the selected command. The palette closes when it
+/// loses the focus, like when Escape is pressed.
+///
+/// ```rust
+/// # use floem::command::Command;
+/// # use floem::views::command_palette;
+/// Command::new("palette.show", "Show All Commands", || {
+///     command_palette().show();
+/// })
+/// .key("Primary+Shift+P")
+/// .register();
+/// ```
+pub fn command_palette() -> Dialog {
+    dialog(|dialog| {
+        let commands: Rc<Vec<_>> = Rc::new(
+            commands()
+                .into_iter()
+                .filter(|command| command.is_enabled())
+                .collect(),
+        );
+        let query = create_rw_signal(String::new());
+        let selected = create_rw_signal(0);
+
+        // The indices of the commands that match the query, best match first.
+        let matches = {
+            let commands = commands.clone();
+            create_memo(move |_| {
+                let query = query.get();
+                let mut matches = commands
+                    .iter()
+                    .enumerate()
+                    .filter_map(|(i, command)| {
+                        fuzzy_score(&query, &full_title(command)).map(|score| (score, i))
+                    })
+                    .collect::<Vec<_>>();
+                // The sort is stable, so equal matches keep the order of registration.
+                matches.sort_by_key(|(score, _)| -score);
+                matches.into_iter().map(|(_, i)| i).collect::<Vec<_>>()
+            })
+        };
+        create_effect(move |_| {
+            query.track();
+            selected.set(0);
+        });
+
+        let run = {
+            let commands = commands.clone();
+            move |index: usize| {
+                if let Some(&i) = matches.get_untracked().get(index) {
+                    dialog.close();
+                    commands[i].run();
+                }
+            }
+        };
+        let run = Rc::new(run);
+
+        let input = {
+            let run = run.clone();
+            text_input(query)
+                .placeholder("Type a command")
+                .on_key_down(
+                    Key::Named(NamedKey::ArrowDown),
+                    |m| m.is_empty(),
+                    move |_| {
+                        let len = matches.with_untracked(|matches| matches.len());
+                        selected.update(|selected| *selected = (*selected + 1).min(len.max(1) - 1));
+                    },
+                )
+                .on_key_down(
+                    Key::Named(NamedKey::ArrowUp),
+                    |m| m.is_empty(),
+                    move |_| selected.update(|selected| *selected = selected.saturating_sub(1)),
+                )
+                .on_key_down(
+                    Key::Named(NamedKey::Enter),
+                    |m| m.is_empty(),
+                    move |_| run(selected.get_untracked()),
+                )
+                // The text input takes Escape to give up the focus.
+                .on_event_stop(EventListener::FocusLost, move |_| dialog.close())
+                .style(|s| s.width_full())
+        };
+
+        let list = scroll(
+            dyn_stack(
+                move || matches.get().into_iter().enumerate(),
+                |(index, i)| (*index, *i),
+                move |(index, i)| {
+                    let command = &commands[i];
+                    let title = full_title(command);
+                    let key = command
+                        .key_binding()
+                        .map(|key| key.to_string())
+                        .unwrap_or_default();
+                    let run = run.clone();
+                    let item = h_stack((
+                        label(move || title.clone()),
+                        label(move || key.clone()).class(CommandPaletteKeyClass),
+                    ))
+                    .class(CommandPaletteItemClass)
+                    .on_event_stop(EventListener::PointerDown, move |_| run(index))
+                    .style(move |s| {
+                        s.apply_if(selected.get() == index, |s| {
+                            s.background(Color::rgb8(213, 208, 216))
+                        })
+                    });
+                    let item_id = item.id();
+                    create_effect(move |_| {
+                        if selected.get() == index {
+                            item_id.scroll_to(None);
+                        }
+                    });
+                    item
+                },
+            )
+            .style(|s| s.flex_col().width_full()),
+        )
+        .style(|s| s.width_full().max_height(300.0));
+
+        v_stack((input, list)).class(CommandPaletteClass)
+    })
+    .close_on_scrim_click(true)
+    .style(|s| s.justify_start().padding_top(60.0))
+}
+
+fn full_title(command: &Command) -> String {
+    match command.category_name() {
+        Some(category) => format!("{category}: {}", command.title()),
+        None => command.title().to_string(),
+    }
+}
@@ -12,8 +12,9 @@ use crate::{
     event::{Event, EventListener, EventPropagation},
     keyboard::Modifiers,
     menu::Menu,
-    style::{Style, StyleClass, StyleSelector},
+    style::{Style, StyleClass, StyleSelector, Transition},
     view::{IntoView, View},
+    view_state::LayoutAnimation,
 };
 
 /// A trait that extends the appearance and functionality of Views through styling and event handling.
@@ -148,6 +149,20 @@ pub trait Decorators: IntoView<V = Self::DV> + Sized {
         view
     }
 
+    /// Marks the view as invalid while `invalid_fn` returns `true`, so that it and its children
+    /// are styled with their [`invalid`](crate::style::Style::invalid) styles.
+    fn invalid(self, invalid_fn: impl Fn() -> bool + 'static) -> Self::DV {
+        let view = self.into_view();
+        let id = view.id();
+
+        create_effect(move |_| {
+            let is_invalid = invalid_fn();
+            id.update_invalid(is_invalid);
+        });
+
+        view
+    }
+
     /// Add an event handler for the given [EventListener].
     fn on_event(
         self,
@@ -331,6 +346,21 @@ pub trait Decorators: IntoView<V = Self::DV> + Sized {
         view
     }
 
+    /// Animate the position and size of this view when a layout change moves or resizes it.
+    ///
+    /// After each layout, the view's new box is compared to its previous one, and the view is
+    /// painted moving from the old box to the new one using the given transition. This is
+    /// useful for smoothly reordering items in a [`dyn_stack`](super::dyn_stack()) or for
+    /// expanding panels.
+    ///
+    /// Only the painting is animated. Events are delivered using the final layout.
+    fn animate_layout(self, transition: Transition) -> Self::DV {
+        let view = self.into_view();
+        let id = view.id();
+        id.state().borrow_mut().layout_animation = Some(LayoutAnimation::new(transition));
+        view
+    }
+
     fn clear_focus(self, when: impl Fn() + 'static) -> Self::DV {
         let view = self.into_view();
         let id = view.id();
@@ -0,0 +1,301 @@
+//! Modal dialogs.
+//!
+//! A dialog is shown above the rest of the window on a scrim which blocks pointer events to the
+//! views behind it. While a dialog is open, Tab and Shift-Tab only move the focus between the
+//! views of the topmost dialog, and Escape closes it. When it closes, the focus returns to the
+//! view that was focused before it opened.
+//!
+//! The content of a dialog is animated in and out by its [animations](crate::animate::Animation)
+//! that run on creation and removal, such as [`Animation::view_transition`](crate::animate::Animation::view_transition).
+//!
+//! ```rust
+//! # use floem::views::{button, dialog, label, v_stack, Decorators};
+//! let open = move || {
+//!     dialog(|dialog| {
+//!         v_stack((
+//!             label(|| "Delete this file?"),
+//!             button("Delete").on_click_stop(move |_| dialog.close()),
+//!         ))
+//!         .animation(|a| a.scale_effect())
+//!     })
+//!     .on_close(|| println!("closed"))
+//!     .show()
+//! };
+//! ```
+
+use std::cell::RefCell;
+
+use floem_reactive::Scope;
+use peniko::kurbo::{Rect, Size};
+
+use crate::{
+    action::{add_overlay, remove_overlay},
+    context::{ComputeLayoutCx, EventCx, UpdateCx},
+    event::{Event, EventPropagation},
+    id::ViewId,
+    style::Style,
+    style_class,
+    view::{default_compute_layout, view_tab_navigation, IntoView, View},
+    views::{
+        dyn_container::{animations_recursive_on_create, animations_recursive_on_remove},
+        Decorators,
+    },
+};
+
+style_class!(pub DialogClass);
+style_class!(pub DialogScrimClass);
+
+struct DialogEntry {
+    id: ViewId,
+    close_on_escape: bool,
+}
+
+thread_local! {
+    /// The dialogs that are open, in the order they were opened.
+    static DIALOGS: RefCell<Vec<DialogEntry>> = const { RefCell::new(Vec::new()) };
+}
+
+/// The topmost open dialog of the window with the `root` view.
+pub(crate) fn top_dialog(root: ViewId) -> Option<ViewId> {
+    DIALOGS.with_borrow(|dialogs| {
+        dialogs
+            .iter()
+            .rev()
+            .find(|entry| entry.id.root() == Some(root))
+            .map(|entry| entry.id)
+    })
+}
+
+/// Closes the topmost dialog of the window with the `root` view if it closes on Escape.
+/// Returns `true` if there is an open dialog, which means Escape is handled either way.
+pub(crate) fn escape_dialog(root: ViewId) -> bool {
+    DIALOGS.with_borrow(|dialogs| {
+        let Some(entry) = dialogs
+            .iter()
+            .rev()
+            .find(|entry| entry.id.root() == Some(root))
+        else {
+            return false;
+        };
+        if entry.close_on_escape {
+            DialogId(entry.id).close();
+        }
+        true
+    })
+}
+
+enum DialogMessage {
+    Close,
+    ExitAnimationComplete,
+}
+
+/// A handle to a dialog, which can be used to close it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct DialogId(ViewId);
+
+impl DialogId {
+    /// Closes the dialog, running the exit animations of its content before removing it.
+    pub fn close(&self) {
+        self.0.update_state(DialogMessage::Close);
+    }
+
+    /// The id of the scrim view that contains the dialog content.
+    pub fn id(&self) -> ViewId {
+        self.0
+    }
+}
+
+/// A modal dialog. See the [module documentation](self) and [`dialog`].
+pub struct Dialog {
+    id: ViewId,
+    content: ViewId,
+    scope: Scope,
+    overlay: Option<ViewId>,
+    close_on_escape: bool,
+    close_on_scrim_click: bool,
+    on_close: Option<Box<dyn Fn()>>,
+    /// `None` until the dialog has moved the focus into itself.
+    prev_focus: Option<Option<ViewId>>,
+    /// The number of exit animations that are still running, once the dialog is closing.
+    closing: Option<u16>,
+    /// The scrim covers the window.
+    window_size: Size,
+}
+
+/// Creates a modal [`Dialog`] with the content returned by `content`.
+///
+/// The dialog isn't visible until [`Dialog::show`] is called.
+pub fn dialog<V: IntoView + 'static>(content: impl FnOnce(DialogId) -> V) -> Dialog {
+    let id = ViewId::new();
+    let content = content(DialogId(id)).class(DialogClass).into_any();
+    let content_id = content.id();
+    id.set_children(vec![content]);
+    Dialog {
+        id,
+        content: content_id,
+        scope: Scope::current(),
+        overlay: None,
+        close_on_escape: true,
+        close_on_scrim_click: false,
+        on_close: None,
+        prev_focus: None,
+        closing: None,
+        window_size: Size::ZERO,
+    }
+    .class(DialogScrimClass)
+}
+
+impl Dialog {
+    /// Close the dialog when Escape is pressed and the focused view doesn't handle it.
+    /// This is enabled by default.
+    pub fn close_on_escape(mut self, close_on_escape: bool) -> Self {
+        self.close_on_escape = close_on_escape;
+        self
+    }
+
+    /// Close the dialog when the scrim around its content is clicked.
+    pub fn close_on_scrim_click(mut self, close_on_scrim_click: bool) -> Self {
+        self.close_on_scrim_click = close_on_scrim_click;
+        self
+    }
+
+    /// Sets a callback that's run when the dialog starts closing.
+    pub fn on_close(mut self, on_close: impl Fn() + 'static) -> Self {
+        self.on_close = Some(Box::new(on_close));
+        self
+    }
+
+    /// Shows the dialog on the current window, above any dialogs that are already open.
+    pub fn show(mut self) -> DialogId {
+        let id = DialogId(self.id);
+        DIALOGS.with_borrow_mut(|dialogs| {
+            dialogs.push(DialogEntry {
+                id: self.id,
+                close_on_escape: self.close_on_escape,
+            })
+        });
+        add_overlay(Default::default(), move |overlay| {
+            self.overlay = Some(overlay);
+            self.scope = Scope::current();
+            animations_recursive_on_create(self.content);
+            self
+        });
+        id
+    }
+
+    fn close(&mut self, cx: &mut UpdateCx) {
+        if self.closing.is_some() {
+            return;
+        }
+        let id = self.id;
+        DIALOGS.with_borrow_mut(|dialogs| dialogs.retain(|entry| entry.id != id));
+
+        match self.prev_focus.take().flatten() {
+            Some(prev_focus) if prev_focus.window_id().is_some() => prev_focus.request_focus(),
+            _ => cx.app_state.clear_focus(),
+        }
+
+        if let Some(on_close) = &self.on_close {
+            on_close();
+        }
+
+        let waiting = animations_recursive_on_remove(self.content, self.scope, move || {
+            id.update_state(DialogMessage::ExitAnimationComplete);
+        });
+        self.closing = Some(waiting);
+        if waiting == 0 {
+            self.remove();
+        }
+    }
+
+    fn remove(&mut self) {
+        if let Some(overlay) = self.overlay.take() {
+            remove_overlay(overlay);
+        }
+    }
+}
+
+impl View for Dialog {
+    fn id(&self) -> ViewId {
+        self.id
+    }
+
+    fn debug_name(&self) -> std::borrow::Cow<'static, str> {
+        "Dialog".into()
+    }
+
+    fn view_style(&self) -> Option<Style> {
+        Some(
+            Style::new()
+                .absolute()
+                .size(self.window_size.width, self.window_size.height)
+                .items_center()
+                .justify_center(),
+        )
+    }
+
+    fn update(&mut self, cx: &mut UpdateCx, state: Box<dyn std::any::Any>) {
+        if let Ok(message) = state.downcast::<DialogMessage>() {
+            match *message {
+                DialogMessage::Close => self.close(cx),
+                DialogMessage::ExitAnimationComplete => {
+                    if let Some(waiting) = self.closing.as_mut() {
+                        *waiting = waiting.saturating_sub(1);
+                        if *waiting == 0 {
+                            self.remove();
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    fn compute_layout(&mut self, cx: &mut ComputeLayoutCx) -> Option<Rect> {
+        let window_size = cx.app_state().root_size / cx.app_state().scale;
+        if window_size != self.window_size {
+            self.window_size = window_size;
+            self.id.request_style();
+            self.id.request_layout();
+        }
+        if self.prev_focus.is_none() && self.closing.is_none() {
+            // The content is known by now, so the focus can be moved into the dialog.
+            let app_state = cx.app_state_mut();
+            self.prev_focus = Some(app_state.focus);
+            view_tab_navigation(self.id, app_state, false);
+        }
+        default_compute_layout(self.id, cx)
+    }
+
+    fn event_after_children(&mut self, _cx: &mut EventCx, event: &Event) -> EventPropagation {
+        if self.closing.is_some() {
+            return EventPropagation::Continue;
+        }
+        match event {
+            Event::PointerDown(pointer_event)
+                if self.close_on_scrim_click
+                    && !self.content.layout_rect().contains(pointer_event.pos) =>
+            {
+                DialogId(self.id).close();
+                EventPropagation::Stop
+            }
+            // The scrim covers the window, so nothing behind it receives pointer events.
+            Event::PointerDown(_)
+            | Event::PointerUp(_)
+            | Event::PointerMove(_)
+            | Event::PointerWheel(_)
+            | Event::Tap(_)
+            | Event::LongPress(_)
+            | Event::Pan(_)
+            | Event::Pinch(_)
+            | Event::Rotate(_) => EventPropagation::Stop,
+            _ => EventPropagation::Continue,
+        }
+    }
+}
+
+impl Drop for Dialog {
+    fn drop(&mut self) {
+        let id = self.id;
+        DIALOGS.with_borrow_mut(|dialogs| dialogs.retain(|entry| entry.id != id));
+    }
+}
@@ -0,0 +1,907 @@
+//! A docking workspace of tabbed panels.
+//!
+//! A [`dock`] arranges panels in tab groups, which are laid out in [splits](super::split) with
+//! draggable dividers between them. The arrangement is a [`DockNode`] tree in a signal, so it can
+//! be changed from code, and saved and restored with the `serde` feature.
+//!
+//! Each tab group has a tab strip, and clicking a tab shows its panel. A tab can be dragged:
+//!
+//! - along a tab strip, to reorder the tabs or to move the panel into another group,
+//! - onto the middle of a group, to add the panel to the group,
+//! - onto an edge of a group, to tear the panel out into a new group next to it.
+//!
+//! While a tab is dragged over a group, a preview shows where it will be docked.
+//!
+//! Panels are identified by strings. The view of a panel is created by a function when the panel
+//! is added to the dock, and kept while it's reordered, moved or docked elsewhere, until it's
+//! closed.
+//!
+//! ```rust
+//! # use floem::reactive::create_rw_signal;
+//! # use floem::views::{dock, label, DockNode, SplitAxis};
+//! let layout = create_rw_signal(DockNode::split(
+//!     SplitAxis::Horizontal,
+//!     [DockNode::tabs(["files"]), DockNode::tabs(["editor", "preview"])],
+//! ));
+//! dock(
+//!     layout,
+//!     |panel| panel.to_uppercase(),
+//!     |panel| {
+//!         let panel = panel.to_string();
+//!         label(move || panel.clone())
+//!     },
+//! );
+//! ```
+
+use std::{cell::Cell, collections::HashMap, rc::Rc};
+
+use floem_reactive::{
+    create_effect, create_memo, create_rw_signal, Memo, RwSignal, SignalGet, SignalUpdate,
+    SignalWith,
+};
+use peniko::kurbo::{Point, Rect, Size};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    event::{Event, EventListener, EventPropagation},
+    id::ViewId,
+    style::Style,
+    style_class,
+    view::{AnyView, IntoView, View},
+    views::{
+        container, dyn_container, dyn_stack, empty, h_stack_from_iter, label, split_from_iter,
+        stack, v_stack, Decorators, SplitAxis, Stack,
+    },
+};
+
+style_class!(pub DockGroupClass);
+style_class!(pub DockTabStripClass);
+style_class!(pub DockTabClass);
+style_class!(pub DockDropPreviewClass);
+/// The tab of the panel that's shown in its group.
+style_class!(pub DockActiveTabClass);
+/// A tab before which a dragged tab would be inserted.
+style_class!(pub DockInsertBeforeClass);
+/// The last tab of a strip, when a dragged tab would be inserted after it.
+style_class!(pub DockInsertAfterClass);
+
+/// The fraction of a group's width or height, from each edge, where a dropped tab is torn out
+/// into a new group.
+const EDGE_ZONE: f64 = 0.25;
+
+/// A node of the layout tree of a [`dock`].
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "kebab-case"))]
+pub enum DockNode {
+    /// A tab group with the panels in the order of their tabs, and the index of the panel that's
+    /// shown.
+    Tabs { panels: Vec<String>, active: usize },
+    /// Nodes laid out along the axis, with the fraction of the space that each one takes.
+    Split {
+        axis: SplitAxis,
+        children: Vec<DockNode>,
+        fractions: Vec<f64>,
+    },
+}
+
+impl Default for DockNode {
+    fn default() -> Self {
+        DockNode::Tabs {
+            panels: Vec::new(),
+            active: 0,
+        }
+    }
+}
+
+/// Where a tab that's dropped on a tab group is docked.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DropZone {
+    /// Into the group.
+    Center,
+    /// Into a new group on the left of the group.
+    Left,
+    /// Into a new group on the right of the group.
+    Right,
+    /// Into a new group above the group.
+    Top,
+    /// Into a new group below the group.
+    Bottom,
+}
+
+impl DropZone {
+    /// The zone of a group of the given size that a point in the group is in.
+    pub fn at(point: Point, size: Size) -> DropZone {
+        if size.width <= 0.0 || size.height <= 0.0 {
+            return DropZone::Center;
+        }
+        let x = point.x / size.width;
+        let y = point.y / size.height;
+        // The distance to each edge, as a fraction of the group's size.
+        let edges = [
+            (x, DropZone::Left),
+            (1.0 - x, DropZone::Right),
+            (y, DropZone::Top),
+            (1.0 - y, DropZone::Bottom),
+        ];
+        edges
+            .into_iter()
+            .filter(|(distance, _)| *distance < EDGE_ZONE)
+            .min_by(|a, b| a.0.total_cmp(&b.0))
+            .map_or(DropZone::Center, |(_, zone)| zone)
+    }
+
+    /// The part of the area of a group that a panel dropped in this zone takes.
+    pub fn preview(self, area: Rect) -> Rect {
+        let center = area.center();
+        match self {
+            DropZone::Center => area,
+            DropZone::Left => Rect::new(area.x0, area.y0, center.x, area.y1),
+            DropZone::Right => Rect::new(center.x, area.y0, area.x1, area.y1),
+            DropZone::Top => Rect::new(area.x0, area.y0, area.x1, center.y),
+            DropZone::Bottom => Rect::new(area.x0, center.y, area.x1, area.y1),
+        }
+    }
+
+    /// The axis of the split that a panel dropped in this zone makes, and whether the new group
+    /// goes after the group it's dropped on.
+    fn placement(self) -> Option<(SplitAxis, bool)> {
+        match self {
+            DropZone::Center => None,
+            DropZone::Left => Some((SplitAxis::Horizontal, false)),
+            DropZone::Right => Some((SplitAxis::Horizontal, true)),
+            DropZone::Top => Some((SplitAxis::Vertical, false)),
+            DropZone::Bottom => Some((SplitAxis::Vertical, true)),
+        }
+    }
+}
+
+impl DockNode {
+    /// A tab group with the panels, showing the first one.
+    pub fn tabs<S: Into<String>>(panels: impl IntoIterator<Item = S>) -> Self {
+        DockNode::Tabs {
+            panels: panels.into_iter().map(Into::into).collect(),
+            active: 0,
+        }
+    }
+
+    /// A split that shares its space equally between the nodes.
+    pub fn split(axis: SplitAxis, children: impl IntoIterator<Item = DockNode>) -> Self {
+        let children = children.into_iter().collect::<Vec<_>>();
+        let fractions = vec![1.0 / children.len().max(1) as f64; children.len()];
+        DockNode::Split {
+            axis,
+            children,
+            fractions,
+        }
+    }
+
+    /// The node at the path of child indices from this node.
+    pub fn node(&self, path: &[usize]) -> Option<&DockNode> {
+        match path.split_first() {
+            None => Some(self),
+            Some((&index, rest)) => match self {
+                DockNode::Split { children, .. } => children.get(index)?.node(rest),
+                DockNode::Tabs { .. } => None,
+            },
+        }
+    }
+
+    fn node_mut(&mut self, path: &[usize]) -> Option<&mut DockNode> {
+        match path.split_first() {
+            None => Some(self),
+            Some((&index, rest)) => match self {
+                DockNode::Split { children, .. } => children.get_mut(index)?.node_mut(rest),
+                DockNode::Tabs { .. } => None,
+            },
+        }
+    }
+
+    /// All the panels, group by group.
+    pub fn panels(&self) -> Vec<&str> {
+        match self {
+            DockNode::Tabs { panels, .. } => panels.iter().map(String::as_str).collect(),
+            DockNode::Split { children, .. } => {
+                children.iter().flat_map(|child| child.panels()).collect()
+            }
+        }
+    }
+
+    /// The path of the tab group with the panel, and the index of the panel's tab.
+    pub fn find(&self, panel: &str) -> Option<(Vec<usize>, usize)> {
+        match self {
+            DockNode::Tabs { panels, .. } => panels
+                .iter()
+                .position(|p| p == panel)
+                .map(|index| (Vec::new(), index)),
+            DockNode::Split { children, .. } => {
+                children.iter().enumerate().find_map(|(i, child)| {
+                    let (mut path, index) = child.find(panel)?;
+                    path.insert(0, i);
+                    Some((path, index))
+                })
+            }
+        }
+    }
+
+    /// The panel that's shown by the tab group at the path.
+    pub fn active_panel(&self, group: &[usize]) -> Option<&str> {
+        match self.node(group)? {
+            DockNode::Tabs { panels, active } => panels.get(*active).map(String::as_str),
+            DockNode::Split { .. } => None,
+        }
+    }
+
+    /// Shows the panel in its tab group. Returns `false` if there is no such panel.
+    pub fn activate(&mut self, panel: &str) -> bool {
+        let Some((group, index)) = self.find(panel) else {
+            return false;
+        };
+        if let Some(DockNode::Tabs { active, .. }) = self.node_mut(&group) {
+            *active = index;
+        }
+        true
+    }
+
+    /// Removes the panel, and the groups and splits that are left empty.
+    /// Returns `false` if there is no such panel.
+    pub fn close(&mut self, panel: &str) -> bool {
+        let Some((group, index)) = self.find(panel) else {
+            return false;
+        };
+        self.take_panel(&group, index);
+        self.normalize();
+        true
+    }
+
+    /// Adds a panel to the end of the tab group at the path and shows it, or moves it there if
+    /// it's already docked.
+    pub fn open(&mut self, panel: &str, group: &[usize]) {
+        if self.find(panel).is_some() {
+            self.move_panel(panel, group, usize::MAX);
+        } else if let Some(DockNode::Tabs { panels, active }) = self.node_mut(group) {
+            panels.push(panel.to_string());
+            *active = panels.len() - 1;
+        }
+    }
+
+    /// Moves the panel to `index` in the tab group at the path, and shows it there. Reorders the
+    /// tabs if the panel is already in the group.
+    pub fn move_panel(&mut self, panel: &str, group: &[usize], index: usize) {
+        let Some((from, from_index)) = self.find(panel) else {
+            return;
+        };
+        if !matches!(self.node(group), Some(DockNode::Tabs { .. })) {
+            return;
+        }
+        // Taking the panel out of its group doesn't change the paths, since the empty groups are
+        // only removed afterwards.
+        let index = if from == group && from_index < index {
+            index - 1
+        } else {
+            index
+        };
+        self.take_panel(&from, from_index);
+        if let Some(DockNode::Tabs { panels, active }) = self.node_mut(group) {
+            let index = index.min(panels.len());
+            panels.insert(index, panel.to_string());
+            *active = index;
+        }
+        self.normalize();
+    }
+
+    /// Docks the panel in the zone of the tab group at the path, either into the group or into a
+    /// new group next to it.
+    pub fn dock_panel(&mut self, panel: &str, group: &[usize], zone: DropZone) {
+        let Some((axis, after)) = zone.placement() else {
+            self.move_panel(panel, group, usize::MAX);
+            return;
+        };
+        let Some((from, from_index)) = self.find(panel) else {
+            return;
+        };
+        match self.node(group) {
+            Some(DockNode::Tabs { panels, .. }) => {
+                if from == group && panels.len() == 1 {
+                    // The group would only be replaced by a group with the same panel.
+                    return;
+                }
+            }
+            _ => return,
+        }
+        self.take_panel(&from, from_index);
+        let new = DockNode::tabs([panel]);
+
+        // A split along the same axis gets another child, instead of a nested split.
+        if let Some((&index, parent)) = group.split_last() {
+            if let Some(DockNode::Split {
+                axis: parent_axis,
+                children,
+                fractions,
+            }) = self.node_mut(parent)
+            {
+                if let Some(fraction) = fractions.get_mut(index).filter(|_| *parent_axis == axis) {
+                    let half = *fraction / 2.0;
+                    *fraction = half;
+                    let at = if after { index + 1 } else { index };
+                    children.insert(at, new);
+                    fractions.insert(at, half);
+                    self.normalize();
+                    return;
+                }
+            }
+        }
+
+        if let Some(target) = self.node_mut(group) {
+            let old = std::mem::take(target);
+            let children = if after { [old, new] } else { [new, old] };
+            *target = DockNode::split(axis, children);
+        }
+        self.normalize();
+    }
+
+    fn take_panel(&mut self, group: &[usize], index: usize) {
+        if let Some(DockNode::Tabs { panels, active }) = self.node_mut(group) {
+            panels.remove(index);
+            if *active > index || *active >= panels.len() {
+                *active = active.saturating_sub(1);
+            }
+        }
+    }
+
+    fn is_empty(&self) -> bool {
+        match self {
+            DockNode::Tabs { panels, .. } => panels.is_empty(),
+            DockNode::Split { children, .. } => children.iter().all(DockNode::is_empty),
+        }
+    }
+
+    /// Removes empty groups, replaces splits with a single child by the child, merges splits into
+    /// their parent split if it has the same axis, and makes the fractions of each split add up
+    /// to `1.0`.
+    pub fn normalize(&mut self) {
+        let DockNode::Split {
+            axis,
+            children,
+            fractions,
+        } = self
+        else {
+            if let DockNode::Tabs { panels, active } = self {
+                *active = (*active).min(panels.len().saturating_sub(1));
+            }
+            return;
+        };
+        if fractions.len() != children.len() {
+            *fractions = vec![1.0; children.len()];
+        }
+
+        let mut nodes = Vec::new();
+        for (mut child, fraction) in children.drain(..).zip(fractions.drain(..)) {
+            child.normalize();
+            if child.is_empty() {
+                continue;
+            }
+            match child {
+                DockNode::Split {
+                    axis: child_axis,
+                    children: grandchildren,
+                    fractions: child_fractions,
+                } if child_axis == *axis => {
+                    nodes.extend(
+                        grandchildren
+                            .into_iter()
+                            .zip(child_fractions.into_iter().map(|f| f * fraction)),
+                    );
+                }
+                child => nodes.push((child, fraction)),
+            }
+        }
+
+        let total: f64 = nodes.iter().map(|(_, fraction)| fraction.max(0.0)).sum();
+        let count = nodes.len();
+        for (child, fraction) in nodes {
+            children.push(child);
+            fractions.push(if total > 0.0 {
+                fraction.max(0.0) / total
+            } else {
+                1.0 / count as f64
+            });
+        }
+
+        if children.len() <= 1 {
+            *self = children.pop().unwrap_or_default();
+        }
+    }
+
+    /// The tree without the fractions and the active tabs, which change without rebuilding the
+    /// views.
+    fn shape(&self) -> DockNode {
+        match self {
+            DockNode::Tabs { panels, .. } => DockNode::Tabs {
+                panels: panels.clone(),
+                active: 0,
+            },
+            DockNode::Split { axis, children, .. } => DockNode::Split {
+                axis: *axis,
+                children: children.iter().map(DockNode::shape).collect(),
+                fractions: Vec::new(),
+            },
+        }
+    }
+}
+
+struct DockContext {
+    layout: RwSignal<DockNode>,
+    /// The panel whose tab is being dragged.
+    dragging: RwSignal<Option<String>>,
+    /// The tab group and the zone of it that the dragged tab is over.
+    drop_target: RwSignal<Option<(Vec<usize>, DropZone)>>,
+    /// The area of the content of each tab group, relative to the dock, where the views of its
+    /// panels are shown.
+    areas: RwSignal<HashMap<Vec<usize>, Rect>>,
+    root: Cell<Option<ViewId>>,
+    title: Box<dyn Fn(&str) -> String>,
+    view: Box<dyn Fn(&str) -> AnyView>,
+}
+
+/// Creates a docking workspace with the arrangement of panels in `layout`. See the
+/// [module documentation](self).
+///
+/// `title` returns the title of a panel's tab, and `view` creates the view of a panel.
+pub fn dock<V: IntoView + 'static>(
+    layout: RwSignal<DockNode>,
+    title: impl Fn(&str) -> String + 'static,
+    view: impl Fn(&str) -> V + 'static,
+) -> Stack {
+    // A layout that was saved or built by hand may have fractions that don't match its children.
+    layout.update(DockNode::normalize);
+
+    let cx = Rc::new(DockContext {
+        layout,
+        dragging: create_rw_signal(None),
+        drop_target: create_rw_signal(None),
+        areas: create_rw_signal(HashMap::new()),
+        root: Cell::new(None),
+        title: Box::new(title),
+        view: Box::new(move |panel| view(panel).into_any()),
+    });
+
+    // The splits and the tab strips are rebuilt when the shape of the layout changes, while the
+    // views of the panels are kept by name on top of them, in the areas of their groups.
+    let shape = create_memo(move |_| layout.with(DockNode::shape));
+    let tree = dyn_container(move || shape.get(), {
+        let cx = cx.clone();
+        move |shape| node_view(&shape, Vec::new(), &cx)
+    })
+    .style(|s| s.size_full());
+    let panels = dyn_stack(
+        move || {
+            layout.with(|layout| {
+                layout
+                    .panels()
+                    .into_iter()
+                    .map(str::to_string)
+                    .collect::<Vec<_>>()
+            })
+        },
+        |panel| panel.clone(),
+        {
+            let cx = cx.clone();
+            move |panel| panel_view(panel, &cx)
+        },
+    )
+    .style(|s| s.absolute().inset(0.0));
+    let preview = {
+        let (dragging, drop_target, areas) = (cx.dragging, cx.drop_target, cx.areas);
+        empty().class(DockDropPreviewClass).style(move |s| {
+            let target = dragging
+                .with(Option::is_some)
+                .then(|| drop_target.get())
+                .flatten();
+            let area = target.and_then(|(group, zone)| {
+                areas.with(|areas| areas.get(&group).map(|area| zone.preview(*area)))
+            });
+            match area {
+                Some(area) => place(s, area),
+                None => s.hide(),
+            }
+        })
+    };
+
+    let root = stack((tree, panels, preview)).style(|s| s.size_full());
+    cx.root.set(Some(root.id()));
+    root
+}
+
+/// Positions a view of the dock at the area.
+fn place(s: Style, area: Rect) -> Style {
+    s.absolute()
+        .inset_left(area.x0)
+        .inset_top(area.y0)
+        .width(area.width())
+        .height(area.height())
+}
+
+/// The view of a panel, shown in the content area of its group while it's the active panel.
+fn panel_view(panel: String, cx: &Rc<DockContext>) -> impl IntoView {
+    let layout = cx.layout;
+    let areas = cx.areas;
+    container((cx.view)(&panel)).style(move |s| {
+        let group = layout.with(|layout| {
+            let (group, _) = layout.find(&panel)?;
+            (layout.active_panel(&group) == Some(panel.as_str())).then_some(group)
+        });
+        match group.and_then(|group| areas.with(|areas| areas.get(&group).copied())) {
+            Some(area) => place(s, area),
+            None => s.hide(),
+        }
+    })
+}
+
+fn node_view(node: &DockNode, path: Vec<usize>, cx: &Rc<DockContext>) -> AnyView {
+    match node {
+        DockNode::Tabs { panels, .. } => group_view(panels, path, cx).into_any(),
+        DockNode::Split { axis, children, .. } => {
+            let layout = cx.layout;
+            let current = {
+                let path = path.clone();
+                move || {
+                    layout.with(|layout| match layout.node(&path) {
+                        Some(DockNode::Split { fractions, .. }) => fractions.clone(),
+                        _ => Vec::new(),
+                    })
+                }
+            };
+            let fractions = create_rw_signal(current());
+            create_effect(move |_| {
+                let current = current();
+                if fractions.get_untracked() != current {
+                    fractions.set(current);
+                }
+            });
+
+            let views = children.iter().enumerate().map(|(i, child)| {
+                let mut path = path.clone();
+                path.push(i);
+                node_view(child, path, cx)
+            });
+            let split = split_from_iter(views)
+                .fractions(fractions)
+                .on_resize(move |new| {
+                    layout.update(|layout| {
+                        if let Some(DockNode::Split { fractions, .. }) = layout.node_mut(&path) {
+                            *fractions = new.to_vec();
+                        }
+                    })
+                })
+                .style(|s| s.size_full());
+            match axis {
+                SplitAxis::Horizontal => split.into_any(),
+                SplitAxis::Vertical => split.vertical().into_any(),
+            }
+        }
+    }
+}
+
+fn group_view(panels: &[String], path: Vec<usize>, cx: &Rc<DockContext>) -> impl IntoView {
+    let layout = cx.layout;
+    let dragging = cx.dragging;
+    let path = Rc::new(path);
+    let active = {
+        let path = path.clone();
+        create_memo(move |_| layout.with(|layout| layout.active_panel(&path).map(str::to_string)))
+    };
+    let drop_target = cx.drop_target;
+    // Where a dragged tab would be inserted in the tab strip.
+    let insert_at = create_rw_signal(None::<usize>);
+
+    let tabs = panels
+        .iter()
+        .enumerate()
+        .map(|(index, panel)| tab_view(panel, index, panels.len(), active, insert_at, cx))
+        .collect::<Vec<_>>();
+    let tab_ids = tabs.iter().map(|tab| tab.id()).collect::<Vec<_>>();
+    let strip = h_stack_from_iter(tabs)
+        .class(DockTabStripClass)
+        .on_event_cont(EventListener::DragOver, move |event| {
+            if let Event::PointerMove(pointer_event) = event {
+                insert_at.set(Some(tab_index_at(&tab_ids, pointer_event.pos.x)));
+            }
+        })
+        .on_event_cont(EventListener::DragLeave, move |_| insert_at.set(None))
+        .on_event(EventListener::Drop, {
+            let path = path.clone();
+            move |_| {
+                let index = insert_at.get_untracked();
+                insert_at.set(None);
+                let (Some(panel), Some(index)) = (dragging.get_untracked(), index) else {
+                    return EventPropagation::Continue;
+                };
+                dragging.set(None);
+                layout.update(|layout| layout.move_panel(&panel, &path, index));
+                EventPropagation::Stop
+            }
+        });
+
+    // The views of the panels are placed over the content by `panel_view`.
+    let content = empty();
+    let content_id = content.id();
+    let root = cx.root.get();
+    let set_area = {
+        let (areas, path) = (cx.areas, path.clone());
+        move |size: Size| {
+            let Some(root) = root else {
+                return;
+            };
+            let origin = content_id.state().borrow().window_origin;
+            let root_origin = root.state().borrow().window_origin;
+            let area = Rect::from_origin_size(origin - root_origin.to_vec2(), size);
+            if areas.with_untracked(|areas| areas.get(&*path) != Some(&area)) {
+                areas.update(|areas| {
+                    areas.insert(path.to_vec(), area);
+                });
+            }
+        }
+    };
+    let content = content
+        .on_resize({
+            let set_area = set_area.clone();
+            move |rect| set_area(rect.size())
+        })
+        .on_move(move |_| set_area(content_id.get_size().unwrap_or_default()))
+        .on_event_cont(EventListener::DragOver, {
+            let path = path.clone();
+            move |event| {
+                if let Event::PointerMove(pointer_event) = event {
+                    let size = content_id.get_size().unwrap_or_default();
+                    let zone = DropZone::at(pointer_event.pos, size);
+                    drop_target.set(Some((path.to_vec(), zone)));
+                }
+            }
+        })
+        .on_event_cont(EventListener::DragLeave, {
+            let path = path.clone();
+            move |_| {
+                if drop_target.with_untracked(|target| {
+                    target.as_ref().is_some_and(|(group, _)| *group == *path)
+                }) {
+                    drop_target.set(None);
+                }
+            }
+        })
+        .on_event(EventListener::Drop, move |event| {
+            drop_target.set(None);
+            let (Event::PointerUp(pointer_event), Some(panel)) = (event, dragging.get_untracked())
+            else {
+                return EventPropagation::Continue;
+            };
+            dragging.set(None);
+            let size = content_id.get_size().unwrap_or_default();
+            let zone = DropZone::at(pointer_event.pos, size);
+            layout.update(|layout| layout.dock_panel(&panel, &path, zone));
+            EventPropagation::Stop
+        })
+        .style(|s| s.width_full().flex_grow(1.0).min_height(0.0));
+
+    v_stack((strip, content))
+        .class(DockGroupClass)
+        .style(|s| s.size_full())
+}
+
+fn tab_view(
+    panel: &str,
+    index: usize,
+    count: usize,
+    active: Memo<Option<String>>,
+    insert_at: RwSignal<Option<usize>>,
+    cx: &Rc<DockContext>,
+) -> impl View {
+    let layout = cx.layout;
+    let dragging = cx.dragging;
+    let title = (cx.title)(panel);
+    let panel = panel.to_string();
+    let is_active = {
+        let panel = panel.clone();
+        move || active.get().as_ref() == Some(&panel)
+    };
+    let insert_at = move || {
+        dragging
+            .with(Option::is_some)
+            .then(|| insert_at.get())
+            .flatten()
+    };
+    label(move || title.clone())
+        .class(DockTabClass)
+        .class_if(is_active, DockActiveTabClass)
+        .class_if(move || insert_at() == Some(index), DockInsertBeforeClass)
+        .class_if(
+            move || index + 1 == count && insert_at() == Some(count),
+            DockInsertAfterClass,
+        )
+        .draggable()
+        .on_event_cont(EventListener::PointerDown, {
+            let panel = panel.clone();
+            move |_| {
+                layout.update(|layout| {
+                    layout.activate(&panel);
+                })
+            }
+        })
+        .on_event_cont(EventListener::DragStart, {
+            let panel = panel.clone();
+            move |_| dragging.set(Some(panel.clone()))
+        })
+        .on_event_cont(EventListener::DragEnd, move |_| dragging.set(None))
+}
+
+/// The index where a tab dropped at `x` in a tab strip is inserted.
+fn tab_index_at(tabs: &[ViewId], x: f64) -> usize {
+    tabs.iter()
+        .position(|tab| {
+            tab.get_layout()
+                .is_some_and(|layout| x < layout.location.x as f64 + layout.size.width as f64 / 2.0)
+        })
+        .unwrap_or(tabs.len())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn workspace() -> DockNode {
+        DockNode::split(
+            SplitAxis::Horizontal,
+            [DockNode::tabs(["files"]), DockNode::tabs(["a", "b", "c"])],
+        )
+    }
+
+    #[test]
+    fn reorder_and_move_tabs() {
+        let mut layout = workspace();
+        layout.move_panel("a", &[1], 3);
+        assert_eq!(
+            layout.node(&[1]),
+            Some(&DockNode::Tabs {
+                panels: vec!["b".into(), "c".into(), "a".into()],
+                active: 2,
+            })
+        );
+
+        layout.move_panel("c", &[0], 0);
+        assert_eq!(layout.panels(), ["c", "files", "b", "a"]);
+        assert_eq!(layout.active_panel(&[0]), Some("c"));
+
+        // Moving the last panel out of a group removes the group and its split.
+        layout.move_panel("files", &[1], 0);
+        layout.move_panel("c", &[1], 0);
+        assert_eq!(
+            layout,
+            DockNode::Tabs {
+                panels: vec!["c".into(), "files".into(), "b".into(), "a".into()],
+                active: 0,
+            }
+        );
+        assert!(layout.close("files"));
+        assert!(!layout.close("files"));
+        assert_eq!(layout.panels(), ["c", "b", "a"]);
+    }
+
+    #[test]
+    fn dock_panels_into_new_groups() {
+        let mut layout = workspace();
+        // A split along the same axis gets another child.
+        layout.dock_panel("b", &[1], DropZone::Right);
+        assert_eq!(layout.find("b"), Some((vec![2], 0)));
+        assert_eq!(
+            layout.node(&[]).map(|node| match node {
+                DockNode::Split { fractions, .. } => fractions.clone(),
+                DockNode::Tabs { .. } => Vec::new(),
+            }),
+            Some(vec![0.5, 0.25, 0.25])
+        );
+
+        // Another axis makes a nested split.
+        layout.dock_panel("c", &[0], DropZone::Bottom);
+        assert_eq!(layout.find("files"), Some((vec![0, 0], 0)));
+        assert_eq!(layout.find("c"), Some((vec![0, 1], 0)));
+
+        // Docking the only panel of a group next to itself changes nothing.
+        let before = layout.clone();
+        layout.dock_panel("c", &[0, 1], DropZone::Left);
+        assert_eq!(layout, before);
+
+        // Emptied groups are removed, and nested splits with one child are replaced by it.
+        layout.dock_panel("c", &[1], DropZone::Center);
+        assert_eq!(layout.find("files"), Some((vec![0], 0)));
+        assert_eq!(layout.find("c"), Some((vec![1], 1)));
+    }
+
+    #[test]
+    fn normalize_merges_splits() {
+        let mut layout = DockNode::Split {
+            axis: SplitAxis::Horizontal,
+            children: vec![
+                DockNode::tabs(["a"]),
+                DockNode::split(
+                    SplitAxis::Horizontal,
+                    [DockNode::tabs(["b"]), DockNode::tabs(Vec::<String>::new())],
+                ),
+                DockNode::split(
+                    SplitAxis::Horizontal,
+                    [DockNode::tabs(["c"]), DockNode::tabs(["d"])],
+                ),
+            ],
+            fractions: vec![2.0, 1.0, 1.0],
+        };
+        layout.normalize();
+        assert_eq!(
+            layout,
+            DockNode::Split {
+                axis: SplitAxis::Horizontal,
+                children: vec![
+                    DockNode::tabs(["a"]),
+                    DockNode::tabs(["b"]),
+                    DockNode::tabs(["c"]),
+                    DockNode::tabs(["d"]),
+                ],
+                fractions: vec![0.5, 0.25, 0.125, 0.125],
+            }
+        );
+    }
+
+    #[test]
+    fn drop_zones() {
+        let size = Size::new(400.0, 200.0);
+        assert_eq!(
+            DropZone::at(Point::new(200.0, 100.0), size),
+            DropZone::Center
+        );
+        assert_eq!(DropZone::at(Point::new(10.0, 100.0), size), DropZone::Left);
+        assert_eq!(
+            DropZone::at(Point::new(390.0, 100.0), size),
+            DropZone::Right
+        );
+        assert_eq!(DropZone::at(Point::new(200.0, 10.0), size), DropZone::Top);
+        assert_eq!(
+            DropZone::at(Point::new(200.0, 190.0), size),
+            DropZone::Bottom
+        );
+        // The closest edge wins in a corner.
+        assert_eq!(DropZone::at(Point::new(5.0, 20.0), size), DropZone::Left);
+
+        let area = Rect::new(100.0, 50.0, 500.0, 250.0);
+        assert_eq!(DropZone::Center.preview(area), area);
+        assert_eq!(
+            DropZone::Right.preview(area),
+            Rect::new(300.0, 50.0, 500.0, 250.0)
+        );
+        assert_eq!(
+            DropZone::Top.preview(area),
+            Rect::new(100.0, 50.0, 500.0, 150.0)
+        );
+    }
+
+    #[test]
+    fn dock_in_split_without_fractions() {
+        // A layout built by hand, without calling `normalize`.
+        let mut layout = DockNode::Split {
+            axis: SplitAxis::Horizontal,
+            children: vec![DockNode::tabs(["a", "b"]), DockNode::tabs(["c"])],
+            fractions: Vec::new(),
+        };
+        layout.dock_panel("b", &[1], DropZone::Right);
+        assert_eq!(
+            layout,
+            DockNode::Split {
+                axis: SplitAxis::Horizontal,
+                children: vec![
+                    DockNode::tabs(["a"]),
+                    DockNode::tabs(["c"]),
+                    DockNode::tabs(["b"]),
+                ],
+                fractions: vec![0.5, 0.25, 0.25],
+            }
+        );
+    }
+}
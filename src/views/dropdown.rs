@@ -4,18 +4,17 @@ use floem_reactive::{
     as_child_of_current_scope, create_effect, create_updater, Scope, SignalGet, SignalUpdate,
 };
 use floem_winit::keyboard::{Key, NamedKey};
-use peniko::kurbo::{Point, Rect};
 
 use crate::{
-    action::{add_overlay, remove_overlay},
+    action::remove_overlay,
     event::{Event, EventListener, EventPropagation},
     id::ViewId,
     prop, prop_extractor,
     style::{CustomStylable, Style, StyleClass, Width},
     style_class,
     unit::PxPctAuto,
-    view::{default_compute_layout, IntoView, View},
-    views::{scroll, Decorators},
+    view::{IntoView, View},
+    views::{popover, scroll, Decorators, Placement},
 };
 
 use super::list;
@@ -74,7 +73,6 @@ pub struct Dropdown<T: 'static> {
     list_view: Rc<dyn Fn() -> Box<dyn View>>,
     list_style: Style,
     overlay_id: Option<ViewId>,
-    window_origin: Option<Point>,
     on_accept: Option<Box<dyn Fn(T)>>,
     on_open: Option<Box<dyn Fn(bool)>>,
     style: DropdownStyle,
@@ -84,6 +82,8 @@ enum Message {
     OpenState(bool),
     ActiveElement(Box<dyn Any>),
     ListFocusLost,
+    /// The list was closed with Escape or by clicking outside of it.
+    ListDismissed,
     ListSelect(Box<dyn Any>),
 }
 
@@ -110,18 +110,20 @@ impl<T: 'static> View for Dropdown<T> {
         }
     }
 
-    fn compute_layout(&mut self, cx: &mut crate::context::ComputeLayoutCx) -> Option<Rect> {
-        self.window_origin = Some(cx.window_origin);
-
-        default_compute_layout(self.id, cx)
-    }
-
     fn update(&mut self, cx: &mut crate::context::UpdateCx, state: Box<dyn std::any::Any>) {
         if let Ok(state) = state.downcast::<Message>() {
             match *state {
                 Message::OpenState(true) => self.open_dropdown(cx),
                 Message::OpenState(false) => self.close_dropdown(),
                 Message::ListFocusLost => self.close_dropdown(),
+                Message::ListDismissed => {
+                    // The popover has already removed itself.
+                    if self.overlay_id.take().is_some() {
+                        if let Some(on_open) = &self.on_open {
+                            on_open(false);
+                        }
+                    }
+                }
                 Message::ListSelect(val) => {
                     if let Ok(val) = val.downcast::<T>() {
                         if self.style.close_on_accept() {
@@ -236,7 +238,6 @@ impl<T> Dropdown<T> {
             list_view,
             list_style: Style::new(),
             overlay_id: None,
-            window_origin: None,
             on_accept: None,
             on_open: None,
             style: Default::default(),
@@ -294,9 +295,7 @@ impl<T> Dropdown<T> {
             cx.app_state.compute_layout();
             if let Some(layout) = self.id.get_layout() {
                 self.update_list_style(layout.size.width as f64);
-                let point =
-                    self.window_origin.unwrap_or_default() + (0., layout.size.height as f64);
-                self.create_overlay(point);
+                self.create_overlay();
 
                 if let Some(on_open) = &self.on_open {
                     on_open(true);
@@ -321,17 +320,23 @@ impl<T> Dropdown<T> {
         }
     }
 
-    fn create_overlay(&mut self, point: Point) {
+    fn create_overlay(&mut self) {
         let list = self.list_view.clone();
         let list_style = self.list_style.clone();
-        self.overlay_id = Some(add_overlay(point, move |_| {
-            let list = list()
-                .style(move |s| s.apply(list_style.clone()))
-                .into_view();
-            let list_id = list.id();
-            list_id.request_focus();
-            list
-        }));
+        let id = self.id;
+        self.overlay_id = Some(
+            popover(self.id, Placement::BOTTOM_START, move || {
+                let list = list()
+                    .style(move |s| s.apply(list_style.clone()))
+                    .into_view();
+                let list_id = list.id();
+                list_id.request_focus();
+                list
+            })
+            .light_dismiss(true)
+            .on_dismiss(move || id.update_state(Message::ListDismissed))
+            .show(),
+        );
     }
 
     /// Sets the custom style properties of the `DropDown`.
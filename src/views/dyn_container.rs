@@ -167,7 +167,9 @@ impl<T> DynamicContainer<T> {
         }
 
         self.num_started_animations =
-            animations_recursive_on_remove(id, old_child_id, old_child_scope);
+            animations_recursive_on_remove(old_child_id, old_child_scope, move || {
+                id.update_state(DynMessage::CompletedAnimation);
+            });
 
         let next_state = (val, old_child_id, old_child_scope);
         if self.num_started_animations == 0 {
@@ -191,7 +193,13 @@ impl<T> DynamicContainer<T> {
     }
 }
 
-fn animations_recursive_on_remove(id: ViewId, child_id: ViewId, child_scope: Scope) -> u16 {
+/// Starts the remove animations of `child_id` and its children, calling `on_complete` as each of
+/// them finishes. Returns the number of animations that were started.
+pub(crate) fn animations_recursive_on_remove(
+    child_id: ViewId,
+    child_scope: Scope,
+    on_complete: impl Fn() + Clone + 'static,
+) -> u16 {
     let mut wait_for = 0;
     let state = child_id.state();
     let mut state = state.borrow_mut();
@@ -204,12 +212,8 @@ fn animations_recursive_on_remove(id: ViewId, child_id: ViewId, child_scope: Sco
             request_style = true;
             wait_for += 1;
             let trigger = anim.on_visual_complete;
-            child_scope.create_updater(
-                move || trigger.track(),
-                move |_| {
-                    id.update_state(DynMessage::CompletedAnimation);
-                },
-            );
+            let on_complete = on_complete.clone();
+            child_scope.create_updater(move || trigger.track(), move |_| on_complete());
         }
     }
     drop(state);
@@ -221,10 +225,11 @@ fn animations_recursive_on_remove(id: ViewId, child_id: ViewId, child_scope: Sco
         .children()
         .into_iter()
         .fold(wait_for, |acc, child_id| {
-            acc + animations_recursive_on_remove(id, child_id, child_scope)
+            acc + animations_recursive_on_remove(child_id, child_scope, on_complete.clone())
         })
 }
-fn animations_recursive_on_create(child_id: ViewId) {
+/// Starts the create animations of `child_id` and its children.
+pub(crate) fn animations_recursive_on_create(child_id: ViewId) {
     let state = child_id.state();
     let mut state = state.borrow_mut();
     let animations = &mut state.animations.stack;
@@ -2,17 +2,25 @@ use std::ops::Range;
 
 use crate::keyboard::Modifiers;
 use floem_editor_core::{
+    buffer::rope_text::RopeText,
     command::{EditCommand, MotionModeCommand, MultiSelectionCommand, ScrollCommand},
     cursor::Cursor,
     mode::MotionMode,
     movement::Movement,
     register::Register,
+    text_object::TextObject,
 };
 use floem_reactive::{SignalGet, SignalUpdate, SignalWith};
 
 use super::{
     command::{Command, CommandExecuted},
-    movement, Editor,
+    gutter::toggle_breakpoint,
+    macros::DEFAULT_MACRO_REGISTER,
+    movement,
+    repeat::EditorInput,
+    symbols,
+    text::SystemClipboard,
+    Editor,
 };
 
 pub fn handle_command_default(
@@ -31,8 +39,133 @@ pub fn handle_command_default(
         Command::Scroll(cmd) => handle_scroll_command_default(ed, cmd, count, modifiers),
         Command::MotionMode(cmd) => handle_motion_mode_command_default(ed, action, cmd, count),
         Command::MultiSelection(cmd) => handle_multi_selection_command_default(ed, cmd),
+        Command::InlineFind(find) => {
+            ed.last_inline_find.set(Some(*find));
+            let movement = Movement::InlineFind {
+                find: *find,
+                repeat: false,
+            };
+            handle_move_command_default(ed, action, movement, count, modifiers)
+        }
+        Command::RepeatInlineFind { reverse } => {
+            let Some(find) = ed.last_inline_find.get_untracked() else {
+                return CommandExecuted::No;
+            };
+            let find = if *reverse { find.reversed() } else { find };
+            let movement = Movement::InlineFind { find, repeat: true };
+            handle_move_command_default(ed, action, movement, count, modifiers)
+        }
+        Command::TextObject(object) => handle_text_object_command_default(ed, action, object),
+        Command::SelectRegister(name) => {
+            ed.register.update(|register| register.select(*name));
+            CommandExecuted::Yes
+        }
+        Command::CreateMark(name) => {
+            let offset = ed.cursor.with_untracked(|cursor| cursor.offset());
+            ed.doc().set_mark(*name, offset);
+            CommandExecuted::Yes
+        }
+        Command::GoToMark { name, line } => {
+            let Some(offset) = ed.doc().mark(*name) else {
+                return CommandExecuted::No;
+            };
+            let text = ed.rope_text();
+            if text.is_empty() {
+                return CommandExecuted::No;
+            }
+            let offset = if *line {
+                text.first_non_blank_character_on_line(text.line_of_offset(offset))
+            } else {
+                offset
+            };
+            let movement = Movement::Offset(offset.min(text.len() - 1));
+            handle_move_command_default(ed, action, movement, None, modifiers)
+        }
+        Command::RepeatLastChange => {
+            let change = ed
+                .changes
+                .with_untracked(|changes| changes.last_change().to_vec());
+            if change.is_empty() {
+                return CommandExecuted::No;
+            }
+            replay_inputs(ed, &change);
+            CommandExecuted::Yes
+        }
+        Command::RecordMacro(name) => {
+            if ed.macros.try_update(|macros| macros.start_recording(*name)) == Some(true) {
+                CommandExecuted::Yes
+            } else {
+                CommandExecuted::No
+            }
+        }
+        Command::ToggleMacroRecording => {
+            let mut register = ed.register.get_untracked();
+            ed.macros.update(|macros| {
+                if macros.stop_recording(&mut register).is_none() {
+                    macros.start_recording(DEFAULT_MACRO_REGISTER);
+                }
+            });
+            ed.register.set(register);
+            CommandExecuted::Yes
+        }
+        Command::PlayMacro(name) => play_macro(ed, *name, count),
+        Command::PlayLastMacro => {
+            let Some(name) = ed.macros.with_untracked(|macros| macros.last()) else {
+                return CommandExecuted::No;
+            };
+            play_macro(ed, name, count)
+        }
+        Command::ToggleBreakpoint => {
+            let offset = ed.cursor.with_untracked(|cursor| cursor.offset());
+            toggle_breakpoint(ed.breakpoints, ed.line_of_offset(offset));
+            CommandExecuted::Yes
+        }
+        // Only documents backed by a file can be saved
+        Command::Save => CommandExecuted::No,
+        Command::SelectNextSyntaxItem | Command::SelectPreviousSyntaxItem => {
+            let next = *cmd == Command::SelectNextSyntaxItem;
+            let Some(name_range) = symbols::syntax_item_name(ed, next) else {
+                return CommandExecuted::No;
+            };
+            if ed.cursor.with_untracked(|cursor| cursor.is_insert()) {
+                symbols::go_to_symbol(ed, name_range);
+                CommandExecuted::Yes
+            } else {
+                let movement = Movement::Offset(name_range.start);
+                handle_move_command_default(ed, action, movement, None, modifiers)
+            }
+        }
+    }
+}
+
+/// Runs the recorded inputs on the editor's document, without recording them again.
+fn replay_inputs(ed: &Editor, inputs: &[EditorInput]) {
+    for input in inputs {
+        match input {
+            EditorInput::Command(cmd) => {
+                ed.doc().run_command(ed, cmd, None, Modifiers::empty());
+            }
+            EditorInput::Text(text) => ed.doc().receive_char(ed, text),
+        }
     }
 }
+
+fn play_macro(ed: &Editor, name: char, count: Option<usize>) -> CommandExecuted {
+    let register = ed.register.get_untracked();
+    let Some(recorded) = ed
+        .macros
+        .try_update(|macros| macros.start_playing(name, &register))
+        .flatten()
+    else {
+        return CommandExecuted::No;
+    };
+    for _ in 0..count.unwrap_or(1) {
+        replay_inputs(ed, &recorded.inputs);
+    }
+    ed.macros.update(|macros| macros.stop_playing());
+
+    CommandExecuted::Yes
+}
 fn handle_edit_command_default(
     ed: &Editor,
     action: &dyn CommonAction,
@@ -59,7 +192,7 @@ fn handle_edit_command_default(
 
     if had_edits {
         if let Some(data) = yank_data {
-            register.add_delete(data);
+            register.add_delete_with_clipboard(data, &mut SystemClipboard::new());
         }
     }
 
@@ -98,6 +231,20 @@ fn handle_move_command_default(
     CommandExecuted::Yes
 }
 
+fn handle_text_object_command_default(
+    ed: &Editor,
+    action: &dyn CommonAction,
+    object: &TextObject,
+) -> CommandExecuted {
+    let mut cursor = ed.cursor.get_untracked();
+    ed.register
+        .update(|register| movement::select_text_object(ed, action, &mut cursor, object, register));
+
+    ed.cursor.set(cursor);
+
+    CommandExecuted::Yes
+}
+
 fn handle_scroll_command_default(
     ed: &Editor,
     cmd: &ScrollCommand,
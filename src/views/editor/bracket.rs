@@ -0,0 +1,208 @@
+//! The highlight of the bracket at the caret and its partner, and the colorization of brackets by
+//! their depth.
+
+use floem_editor_core::{
+    buffer::rope_text::RopeText,
+    cursor::CursorAffinity,
+    util::{matching_char, matching_pair_direction},
+};
+use floem_reactive::{SignalGet, SignalWith};
+use peniko::kurbo::{Rect, Stroke};
+
+use crate::{
+    context::PaintCx,
+    text::{Attrs, AttrsList},
+};
+
+use super::{view::ScreenLines, Editor, CHAR_WIDTH};
+
+/// The depth of the brackets at the start of every line, computed as far as the lines that were
+/// laid out, so that coloring a line only needs the lines since the last edit before it.
+#[derive(Debug, Default, Clone)]
+pub struct BracketDepths {
+    cache_rev: u64,
+    /// The depth at the start of each line, the first of which is always `0`.
+    line_starts: Vec<usize>,
+}
+impl BracketDepths {
+    /// Forgets the depths after `line`, whose content was edited from the revision `old_rev` to
+    /// `new_rev`. All the depths are forgotten if they weren't up to date with `old_rev`.
+    ///
+    /// This has to be called before the revision of the document is bumped, since that lays out
+    /// the lines again.
+    pub fn invalidate_from(&mut self, line: usize, old_rev: u64, new_rev: u64) {
+        if self.cache_rev == old_rev {
+            self.line_starts.truncate(line + 1);
+        } else {
+            self.line_starts.clear();
+        }
+        self.cache_rev = new_rev;
+    }
+
+    /// The brackets of the line, as their column and depth. A closing bracket has the depth of
+    /// its opening bracket.
+    pub fn line_brackets(
+        &mut self,
+        text: &impl RopeText,
+        cache_rev: u64,
+        line: usize,
+    ) -> Vec<(usize, usize)> {
+        if self.cache_rev != cache_rev {
+            // The text changed without saying where, so everything is computed again.
+            self.line_starts.clear();
+            self.cache_rev = cache_rev;
+        }
+        if self.line_starts.is_empty() {
+            self.line_starts.push(0);
+        }
+        while self.line_starts.len() <= line {
+            let prev_line = self.line_starts.len() - 1;
+            let depth = self.line_starts[prev_line];
+            let (_, depth) = brackets_of_line(text, prev_line, depth);
+            self.line_starts.push(depth);
+        }
+
+        brackets_of_line(text, line, self.line_starts[line]).0
+    }
+}
+
+/// The brackets of the line, starting at `depth`, and the depth at the end of the line.
+fn brackets_of_line(
+    text: &impl RopeText,
+    line: usize,
+    mut depth: usize,
+) -> (Vec<(usize, usize)>, usize) {
+    let mut brackets = Vec::new();
+    for (col, c) in text.line_content(line).char_indices() {
+        match matching_pair_direction(c) {
+            Some(true) => {
+                brackets.push((col, depth));
+                depth += 1;
+            }
+            Some(false) => {
+                depth = depth.saturating_sub(1);
+                brackets.push((col, depth));
+            }
+            None => {}
+        }
+    }
+    (brackets, depth)
+}
+
+/// Colors the brackets of the line by their depth, cycling through the editor's bracket colors.
+pub(crate) fn apply_bracket_colors(
+    ed: &Editor,
+    line: usize,
+    default: Attrs,
+    attrs_list: &mut AttrsList,
+    col_at: impl Fn(usize) -> usize,
+) {
+    let colors = ed.es.with_untracked(|es| es.bracket_colors());
+    if colors.is_empty() {
+        return;
+    }
+
+    let cache_rev = ed.doc().cache_rev().get_untracked();
+    let brackets = ed
+        .bracket_depths
+        .borrow_mut()
+        .line_brackets(&ed.rope_text(), cache_rev, line);
+    for (col, depth) in brackets {
+        let col = col_at(col);
+        let color = colors[depth % colors.len()];
+        attrs_list.add_span(col..col + 1, default.color(color));
+    }
+}
+
+/// The offsets of the bracket at or just before the offset, and of its partner.
+pub fn matching_brackets(ed: &Editor, offset: usize) -> Option<(usize, usize)> {
+    let text = ed.rope_text();
+    let is_bracket = |offset: usize| {
+        text.slice_to_cow(offset..text.next_grapheme_offset(offset, 1, text.len()))
+            .chars()
+            .next()
+            .and_then(matching_char)
+            .is_some()
+    };
+
+    let bracket = if offset < text.len() && is_bracket(offset) {
+        offset
+    } else if offset > 0 && is_bracket(text.prev_grapheme_offset(offset, 1, 0)) {
+        text.prev_grapheme_offset(offset, 1, 0)
+    } else {
+        return None;
+    };
+
+    let partner = ed.doc().find_matching_pair(bracket);
+    (partner != bracket).then_some((bracket, partner))
+}
+
+/// Draws a box around the bracket at the caret and around its partner.
+pub(crate) fn paint_matching_brackets(cx: &mut PaintCx, ed: &Editor, screen_lines: &ScreenLines) {
+    let (show, color) = ed
+        .es
+        .with_untracked(|es| (es.show_matching_bracket(), es.matching_bracket_color()));
+    // Finding the partner of an unmatched bracket goes through the rest of the document.
+    if !show || ed.is_large_file() {
+        return;
+    }
+
+    let cursor = ed.cursor.get_untracked();
+    if !cursor.is_insert() && !cursor.is_normal() {
+        return;
+    }
+    let Some((bracket, partner)) = matching_brackets(ed, cursor.offset()) else {
+        return;
+    };
+
+    for offset in [bracket, partner] {
+        let Some(info) = screen_lines.iter_line_info().find(|info| {
+            info.vline_info.interval.start <= offset && offset < info.vline_info.interval.end
+        }) else {
+            continue;
+        };
+        let line_height = f64::from(ed.line_height(info.vline_info.rvline.line));
+        let x0 = ed.line_point_of_offset(offset, CursorAffinity::Forward).x;
+        let x1 = ed
+            .line_point_of_offset(offset + 1, CursorAffinity::Backward)
+            .x;
+        let width = if x1 > x0 { x1 - x0 } else { CHAR_WIDTH };
+
+        let rect = Rect::from_origin_size((x0, info.vline_y), (width, line_height));
+        cx.stroke(&rect.inset(-0.5), color, &Stroke::new(1.0));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use floem_editor_core::buffer::rope_text::RopeTextVal;
+    use lapce_xi_rope::Rope;
+
+    use super::BracketDepths;
+
+    #[test]
+    fn bracket_depths() {
+        let text = RopeTextVal::new(Rope::from("fn a() {\n    b[(1)];\n}\n)("));
+        let mut depths = BracketDepths::default();
+
+        assert_eq!(depths.line_brackets(&text, 0, 0), [(4, 0), (5, 0), (7, 0)]);
+        assert_eq!(
+            depths.line_brackets(&text, 0, 1),
+            [(5, 1), (6, 2), (8, 2), (9, 1)]
+        );
+        assert_eq!(depths.line_brackets(&text, 0, 2), [(0, 0)]);
+        // An unmatched closing bracket doesn't go below the top level.
+        assert_eq!(depths.line_brackets(&text, 0, 3), [(0, 0), (1, 0)]);
+
+        let text = RopeTextVal::new(Rope::from("fn a() {\n    b[(1)];\n{\n)("));
+        depths.invalidate_from(2, 0, 1);
+        assert_eq!(depths.line_starts, [0, 1, 1]);
+        assert_eq!(depths.line_brackets(&text, 1, 3), [(0, 1), (1, 1)]);
+
+        // Depths that missed an edit are all computed again.
+        let text = RopeTextVal::new(Rope::from("{\n{\n)("));
+        depths.invalidate_from(2, 2, 3);
+        assert!(depths.line_starts.is_empty());
+        assert_eq!(depths.line_brackets(&text, 3, 2), [(0, 1), (1, 1)]);
+    }
+}
@@ -1,15 +1,68 @@
-use floem_editor_core::command::{
-    EditCommand, MotionModeCommand, MoveCommand, MultiSelectionCommand, ScrollCommand,
+use floem_editor_core::{
+    command::{
+        EditCommand, FocusCommand, MotionModeCommand, MoveCommand, MultiSelectionCommand,
+        ScrollCommand,
+    },
+    movement::{InlineFind, InlineFindDirection},
+    text_object::TextObject,
 };
-use strum::EnumMessage;
+use floem_reactive::{RwSignal, SignalGet, SignalWith};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+use strum::{EnumMessage, IntoEnumIterator};
+
+use crate::keyboard::Modifiers;
+
+use super::Editor;
 
 #[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub enum Command {
     Edit(EditCommand),
     Move(MoveCommand),
     Scroll(ScrollCommand),
     MotionMode(MotionModeCommand),
     MultiSelection(MultiSelectionCommand),
+    /// Move to a character on the line, like `f`, `t`, `F` and `T` in vim.
+    InlineFind(InlineFind),
+    /// Repeat the last [`Command::InlineFind`], in the other direction when `reverse` is set,
+    /// like `;` and `,` in vim.
+    RepeatInlineFind {
+        reverse: bool,
+    },
+    /// Apply the pending motion mode to a text object, or select it in visual mode.
+    TextObject(TextObject),
+    /// Select the register for the next yank, delete or paste, like `"a` in vim.
+    SelectRegister(char),
+    /// Set a mark at the cursor, like `m` in vim.
+    CreateMark(char),
+    /// Move to a mark, or to the first non-blank character of its line when `line` is set,
+    /// like `` ` `` and `'` in vim.
+    GoToMark {
+        name: char,
+        line: bool,
+    },
+    /// Repeat the last change of the modal mode, like `.` in vim.
+    RepeatLastChange,
+    /// Start recording a macro into a register, like `q` in vim.
+    RecordMacro(char),
+    /// Stop recording the macro, or start recording one into the register `q`.
+    ToggleMacroRecording,
+    /// Play the macro of a register, like `@` in vim.
+    PlayMacro(char),
+    /// Play the macro that was last played or recorded, like `@@` in vim.
+    PlayLastMacro,
+    /// Add a breakpoint to the line of the cursor, or remove the one it has.
+    ToggleBreakpoint,
+    /// Save the document to its file, for documents backed by one like
+    /// [`FileDocument`](super::file_document::FileDocument).
+    Save,
+    /// Move to the name of the next symbol of the document's outline, selecting it in insert
+    /// mode. See [`symbols`](super::symbols).
+    SelectNextSyntaxItem,
+    /// Move to the name of the previous symbol of the document's outline, selecting it in insert
+    /// mode.
+    SelectPreviousSyntaxItem,
 }
 
 impl Command {
@@ -20,6 +73,23 @@ impl Command {
             Command::Scroll(cmd) => cmd.get_message(),
             Command::MotionMode(cmd) => cmd.get_message(),
             Command::MultiSelection(cmd) => cmd.get_message(),
+            Command::InlineFind(find) => inline_find_command(find).get_message(),
+            Command::RepeatInlineFind { .. } => FocusCommand::RepeatLastInlineFind.get_message(),
+            Command::TextObject(_) => Some("Text Object"),
+            Command::SelectRegister(_) => Some("Select Register"),
+            Command::CreateMark(_) => FocusCommand::CreateMark.get_message(),
+            Command::GoToMark { .. } => FocusCommand::GoToMark.get_message(),
+            Command::RepeatLastChange => Some("Repeat Last Change"),
+            Command::RecordMacro(_) => Some("Record Macro"),
+            Command::ToggleMacroRecording => Some("Toggle Macro Recording"),
+            Command::PlayMacro(_) => Some("Play Macro"),
+            Command::PlayLastMacro => Some("Play Last Macro"),
+            Command::ToggleBreakpoint => FocusCommand::ToggleBreakpoint.get_message(),
+            Command::Save => FocusCommand::Save.get_message(),
+            Command::SelectNextSyntaxItem => FocusCommand::SelectNextSyntaxItem.get_message(),
+            Command::SelectPreviousSyntaxItem => {
+                FocusCommand::SelectPreviousSyntaxItem.get_message()
+            }
         }
     }
 
@@ -30,8 +100,83 @@ impl Command {
             Command::Scroll(cmd) => cmd.into(),
             Command::MotionMode(cmd) => cmd.into(),
             Command::MultiSelection(cmd) => cmd.into(),
+            Command::InlineFind(find) => inline_find_command(find).into(),
+            Command::RepeatInlineFind { .. } => FocusCommand::RepeatLastInlineFind.into(),
+            Command::TextObject(_) => "text_object",
+            Command::SelectRegister(_) => "select_register",
+            Command::CreateMark(_) => FocusCommand::CreateMark.into(),
+            Command::GoToMark { .. } => FocusCommand::GoToMark.into(),
+            Command::RepeatLastChange => "repeat_last_change",
+            Command::RecordMacro(_) => "record_macro",
+            Command::ToggleMacroRecording => "toggle_macro_recording",
+            Command::PlayMacro(_) => "play_macro",
+            Command::PlayLastMacro => "play_last_macro",
+            Command::ToggleBreakpoint => FocusCommand::ToggleBreakpoint.into(),
+            Command::Save => FocusCommand::Save.into(),
+            Command::SelectNextSyntaxItem => FocusCommand::SelectNextSyntaxItem.into(),
+            Command::SelectPreviousSyntaxItem => FocusCommand::SelectPreviousSyntaxItem.into(),
         }
     }
+
+    /// The id of the command in the [command registry](crate::command), like
+    /// `editor.move_line_up`.
+    pub fn id(&self) -> String {
+        format!("editor.{}", self.str())
+    }
+
+    /// All the editor commands that don't take an argument.
+    pub fn all() -> impl Iterator<Item = Command> {
+        EditCommand::iter()
+            .map(Command::Edit)
+            .chain(MoveCommand::iter().map(Command::Move))
+            .chain(ScrollCommand::iter().map(Command::Scroll))
+            .chain(MotionModeCommand::iter().map(Command::MotionMode))
+            .chain(MultiSelectionCommand::iter().map(Command::MultiSelection))
+            .chain([
+                Command::RepeatLastChange,
+                Command::ToggleMacroRecording,
+                Command::PlayLastMacro,
+                Command::ToggleBreakpoint,
+                Command::Save,
+                Command::SelectNextSyntaxItem,
+                Command::SelectPreviousSyntaxItem,
+            ])
+    }
+
+    /// Registers the command in the [command registry](crate::command) so that it runs on
+    /// `editor`, replacing the command registered for any other editor.
+    ///
+    /// No key binding is registered, since the editor handles its own key bindings while it has
+    /// the focus.
+    pub fn register(self, editor: RwSignal<Editor>) {
+        let title = self.desc().unwrap_or(self.str());
+        let is_edit = matches!(self, Command::Edit(_));
+        crate::command::Command::new(self.id(), title, move || {
+            editor.with_untracked(|ed| {
+                ed.doc().run_command(ed, &self, Some(1), Modifiers::empty());
+            });
+        })
+        .category("Editor")
+        .enabled(move || !is_edit || !editor.with(|ed| ed.read_only.get()))
+        .register();
+    }
+}
+
+fn inline_find_command(find: &InlineFind) -> FocusCommand {
+    match find.direction {
+        InlineFindDirection::Left => FocusCommand::InlineFindLeft,
+        InlineFindDirection::Right => FocusCommand::InlineFindRight,
+    }
+}
+
+/// Registers all the editor [`Command`]s so that they run on `editor`.
+///
+/// As the commands of one editor replace those of another, this can be called when an editor
+/// gets the focus to make the commands follow it.
+pub fn register_editor_commands(editor: RwSignal<Editor>) {
+    for command in Command::all() {
+        command.register(editor);
+    }
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -0,0 +1,581 @@
+//! A document backed by a file, which is saved to the file and follows the changes that other
+//! programs make to it.
+
+use std::{
+    cell::Cell,
+    collections::BTreeSet,
+    ffi::OsString,
+    fs,
+    io::{self, Read, Write},
+    path::{Path, PathBuf},
+    rc::Rc,
+    time::{Duration, SystemTime},
+};
+
+use floem_editor_core::{
+    buffer::{rope_text::RopeTextVal, LARGE_FILE_THRESHOLD},
+    cursor::Cursor,
+    editor::EditType,
+    line_ending::LineEnding,
+    selection::Selection,
+};
+use floem_reactive::{RwSignal, Scope, SignalGet, SignalTrack, SignalUpdate, Trigger};
+use lapce_xi_rope::Rope;
+use peniko::Color;
+
+use crate::{
+    action::exec_after,
+    ext_event::{create_ext_action, spawn_background},
+    keyboard::Modifiers,
+};
+
+use super::{
+    command::{Command, CommandExecuted},
+    id::EditorId,
+    phantom_text::{PhantomText, PhantomTextLine},
+    text::{Document, DocumentPhantom, PreeditData},
+    text_document::{LoadState, TextDocument},
+    view::{ScreenLines, ScreenLinesBase},
+    Editor, EditorStyle,
+};
+
+/// How often the file is checked for changes made by other programs.
+const WATCH_INTERVAL: Duration = Duration::from_secs(1);
+
+/// The encoding of a file, detected from its byte order mark.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum Encoding {
+    #[default]
+    Utf8,
+    /// UTF-8 starting with a byte order mark, which is kept when saving.
+    Utf8Bom,
+    Utf16Le,
+    Utf16Be,
+}
+impl Encoding {
+    /// Detects the encoding of the bytes from their byte order mark, assuming UTF-8 when there
+    /// is none, and decodes them. Invalid sequences are replaced.
+    pub fn decode(bytes: &[u8]) -> (Encoding, String) {
+        if let Some(bytes) = bytes.strip_prefix(b"\xEF\xBB\xBF") {
+            (
+                Encoding::Utf8Bom,
+                String::from_utf8_lossy(bytes).into_owned(),
+            )
+        } else if let Some(bytes) = bytes.strip_prefix(b"\xFF\xFE") {
+            (Encoding::Utf16Le, decode_utf16(bytes, u16::from_le_bytes))
+        } else if let Some(bytes) = bytes.strip_prefix(b"\xFE\xFF") {
+            (Encoding::Utf16Be, decode_utf16(bytes, u16::from_be_bytes))
+        } else {
+            (Encoding::Utf8, String::from_utf8_lossy(bytes).into_owned())
+        }
+    }
+
+    /// Encodes the text, starting with the byte order mark of the encoding, if it has one.
+    pub fn encode(self, text: &str) -> Vec<u8> {
+        match self {
+            Encoding::Utf8 => text.as_bytes().to_vec(),
+            Encoding::Utf8Bom => [b"\xEF\xBB\xBF".as_slice(), text.as_bytes()].concat(),
+            Encoding::Utf16Le => encode_utf16(text, [0xFF, 0xFE], u16::to_le_bytes),
+            Encoding::Utf16Be => encode_utf16(text, [0xFE, 0xFF], u16::to_be_bytes),
+        }
+    }
+}
+
+fn decode_utf16(bytes: &[u8], from_bytes: fn([u8; 2]) -> u16) -> String {
+    let units = bytes
+        .chunks_exact(2)
+        .map(|pair| from_bytes([pair[0], pair[1]]));
+    char::decode_utf16(units)
+        .map(|c| c.unwrap_or(char::REPLACEMENT_CHARACTER))
+        .collect()
+}
+
+fn encode_utf16(text: &str, bom: [u8; 2], to_bytes: fn(u16) -> [u8; 2]) -> Vec<u8> {
+    let mut bytes = bom.to_vec();
+    bytes.extend(text.encode_utf16().flat_map(to_bytes));
+    bytes
+}
+
+/// A [`TextDocument`] backed by a file, which [`Command::Save`] saves it to.
+///
+/// The file is watched for changes made by other programs. While the buffer has no unsaved
+/// changes, it's reloaded from the file. Otherwise the change is a [`FileDocument::conflict`],
+/// which is resolved by saving over the file or by reloading it.
+#[derive(Clone)]
+pub struct FileDocument {
+    doc: TextDocument,
+    path: Rc<PathBuf>,
+    encoding: Rc<Cell<Encoding>>,
+    /// The modification time of the file when it was last loaded, saved or found changed.
+    modified: Rc<Cell<Option<SystemTime>>>,
+    /// Notified when the buffer is saved or reloaded, which makes it pristine.
+    saved: Trigger,
+
+    /// Whether the file was changed by another program while the buffer had unsaved changes.
+    pub conflict: RwSignal<bool>,
+    /// The error of the last save or reload, if it failed.
+    pub error: RwSignal<Option<String>>,
+}
+impl FileDocument {
+    /// Load the file at `path`, detecting its encoding and line ending, and start watching it
+    /// for changes until `cx` is disposed.
+    /// A UTF-8 file above [`LARGE_FILE_THRESHOLD`] is read in the background, see
+    /// [`TextDocument::load_file`]. It can't be saved or reloaded until it's fully loaded.
+    pub fn open(cx: Scope, path: impl Into<PathBuf>) -> io::Result<FileDocument> {
+        let path = path.into();
+        let modified = modified_time(&path);
+        let large_file = fs::metadata(&path)?.len() > LARGE_FILE_THRESHOLD as u64;
+
+        // Only UTF-8 without a byte order mark can be read in parts.
+        let (encoding, doc) = if large_file && start_encoding(&path)? == Encoding::Utf8 {
+            (Encoding::Utf8, TextDocument::load_file(cx, path.clone()))
+        } else {
+            let (encoding, text) = Encoding::decode(&fs::read(&path)?);
+            (encoding, TextDocument::new(cx, text))
+        };
+
+        let doc = FileDocument {
+            doc,
+            path: Rc::new(path),
+            encoding: Rc::new(Cell::new(encoding)),
+            modified: Rc::new(Cell::new(modified)),
+            saved: cx.create_trigger(),
+            conflict: cx.create_rw_signal(false),
+            error: cx.create_rw_signal(None),
+        };
+        doc.watch(cx);
+
+        Ok(doc)
+    }
+
+    /// The document holding the buffer, for its other options like
+    /// [`TextDocument::add_on_update`].
+    pub fn text_document(&self) -> &TextDocument {
+        &self.doc
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// The encoding the file was loaded with, which it's saved with.
+    pub fn encoding(&self) -> Encoding {
+        self.encoding.get()
+    }
+
+    /// Set the encoding that the file is saved with.
+    pub fn set_encoding(&self, encoding: Encoding) {
+        self.encoding.set(encoding);
+    }
+
+    /// The line ending that was detected when loading the file.
+    pub fn line_ending(&self) -> LineEnding {
+        self.doc.line_ending()
+    }
+
+    /// Whether the buffer has changes that aren't saved.
+    /// This is tracked, so that a view showing it updates.
+    pub fn is_dirty(&self) -> bool {
+        self.doc.cache_rev().track();
+        self.saved.track();
+        !self.doc.is_pristine()
+    }
+
+    /// Save the buffer to the file, resolving a conflict by overwriting the other program's
+    /// changes.
+    /// The buffer is written to a temporary file next to the file, which then replaces it, so
+    /// that the file is never left half written.
+    pub fn save(&self) -> io::Result<()> {
+        self.check_loaded()?;
+        let bytes = self
+            .encoding
+            .get()
+            .encode(&self.doc.text().slice_to_cow(..));
+        if let Err(err) = write_atomically(&self.path, &bytes) {
+            self.error.set(Some(err.to_string()));
+            return Err(err);
+        }
+
+        self.modified.set(modified_time(&self.path));
+        self.doc.set_pristine();
+        self.conflict.set(false);
+        self.error.set(None);
+        self.saved.notify();
+        Ok(())
+    }
+
+    /// Replace the buffer with the content of the file, resolving a conflict by dropping the
+    /// unsaved changes. Reloading can be undone, and the cursors keep their line and column.
+    pub fn reload(&self) -> io::Result<()> {
+        self.check_loaded()?;
+        let modified = modified_time(&self.path);
+        let bytes = match fs::read(&*self.path) {
+            Ok(bytes) => bytes,
+            Err(err) => {
+                self.error.set(Some(err.to_string()));
+                return Err(err);
+            }
+        };
+
+        let (encoding, text) = Encoding::decode(&bytes);
+        self.encoding.set(encoding);
+        self.modified.set(modified);
+        self.doc.reload(Rope::from(text), true);
+        self.doc.load_state.set(LoadState::Loaded);
+        self.conflict.set(false);
+        self.error.set(None);
+        self.saved.notify();
+        Ok(())
+    }
+
+    /// Check the file for changes every [`WATCH_INTERVAL`], until `cx`, the scope of the
+    /// document, is disposed. The modification time is read in the background.
+    fn watch(&self, cx: Scope) {
+        let doc = self.clone();
+        exec_after(WATCH_INTERVAL, move |_| {
+            // The signals of the document are disposed along with its scope
+            if doc.conflict.try_get_untracked().is_none() {
+                return;
+            }
+            let path = doc.path.to_path_buf();
+            let send = create_ext_action(cx, move |modified| {
+                if doc.conflict.try_get_untracked().is_none() {
+                    return;
+                }
+                doc.check_file(modified);
+                doc.watch(cx);
+            });
+            spawn_background(move || send(modified_time(&path)));
+        });
+    }
+
+    /// Reload the file if another program changed it, from `modified`, the modification time
+    /// the file has now, unless the buffer has unsaved changes, in which case the change is a
+    /// conflict.
+    /// A file that was removed is left alone, as saving creates it again.
+    fn check_file(&self, modified: Option<SystemTime>) {
+        if modified.is_none() || modified == self.modified.get() {
+            return;
+        }
+        // The loader would append the rest of the file to the reloaded buffer.
+        if self.doc.load_state.get_untracked() == LoadState::Loading {
+            return;
+        }
+
+        if self.doc.is_pristine() {
+            // The error is kept in `error`
+            let _ = self.reload();
+        } else {
+            self.modified.set(modified);
+            self.conflict.set(true);
+        }
+    }
+
+    /// Fails while the file is loading, as saving would cut it off, and reloading would be
+    /// followed by the rest of the file being appended.
+    fn check_loaded(&self) -> io::Result<()> {
+        if self.doc.load_state.get_untracked() == LoadState::Loading {
+            let err = io::Error::other("the file is still loading");
+            self.error.set(Some(err.to_string()));
+            return Err(err);
+        }
+        Ok(())
+    }
+}
+
+/// The encoding of the file from its first bytes.
+fn start_encoding(path: &Path) -> io::Result<Encoding> {
+    let mut start = Vec::with_capacity(3);
+    fs::File::open(path)?.take(3).read_to_end(&mut start)?;
+    Ok(Encoding::decode(&start).0)
+}
+
+fn modified_time(path: &Path) -> Option<SystemTime> {
+    fs::metadata(path)
+        .and_then(|metadata| metadata.modified())
+        .ok()
+}
+
+/// Write the bytes to a temporary file next to `path`, which then replaces the file, keeping
+/// its permissions. A symbolic link is followed, so that the file it points to is replaced
+/// rather than the link.
+fn write_atomically(path: &Path, bytes: &[u8]) -> io::Result<()> {
+    let path = match fs::canonicalize(path) {
+        Ok(path) => path,
+        // The file is created
+        Err(err) if err.kind() == io::ErrorKind::NotFound => path.to_path_buf(),
+        Err(err) => return Err(err),
+    };
+    let path = path.as_path();
+    let Some(file_name) = path.file_name() else {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            "the path has no file name",
+        ));
+    };
+    let mut tmp_name = OsString::from(".");
+    tmp_name.push(file_name);
+    tmp_name.push(".tmp");
+    let tmp_path = path.with_file_name(tmp_name);
+
+    let write = || -> io::Result<()> {
+        let mut file = fs::File::create(&tmp_path)?;
+        file.write_all(bytes)?;
+        file.sync_all()?;
+        if let Ok(metadata) = fs::metadata(path) {
+            fs::set_permissions(&tmp_path, metadata.permissions())?;
+        }
+        fs::rename(&tmp_path, path)?;
+        // The rename is only durable once the directory is synced.
+        #[cfg(unix)]
+        if let Some(dir) = path.parent().filter(|dir| !dir.as_os_str().is_empty()) {
+            fs::File::open(dir)?.sync_all()?;
+        }
+        Ok(())
+    };
+    let result = write();
+    if result.is_err() {
+        let _ = fs::remove_file(&tmp_path);
+    }
+    result
+}
+
+impl Document for FileDocument {
+    fn text(&self) -> Rope {
+        self.doc.text()
+    }
+
+    fn rope_text(&self) -> RopeTextVal {
+        self.doc.rope_text()
+    }
+
+    fn cache_rev(&self) -> RwSignal<u64> {
+        self.doc.cache_rev()
+    }
+
+    fn find_unmatched(&self, offset: usize, previous: bool, ch: char) -> usize {
+        self.doc.find_unmatched(offset, previous, ch)
+    }
+
+    fn find_matching_pair(&self, offset: usize) -> usize {
+        self.doc.find_matching_pair(offset)
+    }
+
+    fn preedit(&self) -> PreeditData {
+        self.doc.preedit()
+    }
+
+    fn preedit_phantom(&self, under_line: Option<Color>, line: usize) -> Option<PhantomText> {
+        self.doc.preedit_phantom(under_line, line)
+    }
+
+    fn compute_screen_lines(
+        &self,
+        editor: &Editor,
+        base: RwSignal<ScreenLinesBase>,
+    ) -> ScreenLines {
+        self.doc.compute_screen_lines(editor, base)
+    }
+
+    fn run_command(
+        &self,
+        ed: &Editor,
+        cmd: &Command,
+        count: Option<usize>,
+        modifiers: Modifiers,
+    ) -> CommandExecuted {
+        if *cmd == Command::Save {
+            // The error is kept in `error`
+            let _ = self.save();
+            return CommandExecuted::Yes;
+        }
+
+        self.doc.run_command(ed, cmd, count, modifiers)
+    }
+
+    fn receive_char(&self, ed: &Editor, c: &str) {
+        self.doc.receive_char(ed, c)
+    }
+
+    fn set_mark(&self, name: char, offset: usize) {
+        self.doc.set_mark(name, offset)
+    }
+
+    fn mark(&self, name: char) -> Option<usize> {
+        self.doc.mark(name)
+    }
+
+    fn add_editor_cursor(&self, editor: EditorId, cursor: RwSignal<Cursor>) {
+        self.doc.add_editor_cursor(editor, cursor)
+    }
+
+    fn add_editor_breakpoints(&self, editor: EditorId, breakpoints: RwSignal<BTreeSet<usize>>) {
+        self.doc.add_editor_breakpoints(editor, breakpoints)
+    }
+
+    fn is_large_file(&self) -> bool {
+        self.doc.is_large_file()
+    }
+
+    fn edit_single(&self, selection: Selection, content: &str, edit_type: EditType) {
+        self.doc.edit_single(selection, content, edit_type)
+    }
+
+    fn edit(&self, iter: &mut dyn Iterator<Item = (Selection, &str)>, edit_type: EditType) {
+        self.doc.edit(iter, edit_type)
+    }
+}
+impl DocumentPhantom for FileDocument {
+    fn phantom_text(&self, edid: EditorId, styling: &EditorStyle, line: usize) -> PhantomTextLine {
+        self.doc.phantom_text(edid, styling, line)
+    }
+
+    fn has_multiline_phantom(&self, edid: EditorId, styling: &EditorStyle) -> bool {
+        self.doc.has_multiline_phantom(edid, styling)
+    }
+
+    fn before_phantom_col(
+        &self,
+        edid: EditorId,
+        styling: &EditorStyle,
+        line: usize,
+        col: usize,
+    ) -> usize {
+        self.doc.before_phantom_col(edid, styling, line, col)
+    }
+}
+
+impl std::fmt::Debug for FileDocument {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let mut s = f.debug_struct("FileDocument");
+        s.field("path", &self.path);
+        s.field("encoding", &self.encoding.get());
+        s.finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{fs, path::PathBuf, time::SystemTime};
+
+    use floem_editor_core::{
+        cursor::{Cursor, CursorMode},
+        editor::EditType,
+        selection::Selection,
+    };
+    use floem_reactive::{Scope, SignalGet};
+
+    use crate::views::editor::{id::EditorId, text::Document};
+
+    use super::{modified_time, write_atomically, Encoding, FileDocument};
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir =
+            std::env::temp_dir().join(format!("floem-file-document-{name}-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[test]
+    fn encodings() {
+        for encoding in [
+            Encoding::Utf8,
+            Encoding::Utf8Bom,
+            Encoding::Utf16Le,
+            Encoding::Utf16Be,
+        ] {
+            let bytes = encoding.encode("a\r\nä€𝄞");
+            assert_eq!(Encoding::decode(&bytes), (encoding, "a\r\nä€𝄞".to_string()));
+        }
+
+        assert_eq!(Encoding::Utf16Le.encode("a"), [0xFF, 0xFE, b'a', 0]);
+        assert_eq!(
+            Encoding::decode(b"a\xFFb"),
+            (Encoding::Utf8, "a\u{FFFD}b".to_string())
+        );
+    }
+
+    #[test]
+    fn atomic_writes() {
+        let dir = temp_dir("write");
+        let path = dir.join("a.txt");
+        fs::write(&path, "old").unwrap();
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            fs::set_permissions(&path, fs::Permissions::from_mode(0o640)).unwrap();
+        }
+
+        write_atomically(&path, b"new").unwrap();
+        assert_eq!(fs::read_to_string(&path).unwrap(), "new");
+        assert!(!dir.join(".a.txt.tmp").exists());
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let mode = fs::metadata(&path).unwrap().permissions().mode();
+            assert_eq!(mode & 0o777, 0o640);
+        }
+
+        // A directory can't be replaced, and the temporary file is removed.
+        let not_file = dir.join("b");
+        fs::create_dir(&not_file).unwrap();
+        assert!(write_atomically(&not_file, b"new").is_err());
+        assert!(!dir.join(".b.tmp").exists());
+
+        // A link is kept, and the file it points to is replaced.
+        #[cfg(unix)]
+        {
+            let link = dir.join("link.txt");
+            std::os::unix::fs::symlink(&path, &link).unwrap();
+            write_atomically(&link, b"linked").unwrap();
+            assert!(fs::symlink_metadata(&link)
+                .unwrap()
+                .file_type()
+                .is_symlink());
+            assert_eq!(fs::read_to_string(&path).unwrap(), "linked");
+        }
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn external_changes() {
+        let dir = temp_dir("watch");
+        let path = dir.join("a.txt");
+        fs::write(&path, "one\ntwo\n").unwrap();
+
+        let cx = Scope::new();
+        let doc = FileDocument::open(cx, &path).unwrap();
+        let cursor = cx.create_rw_signal(Cursor::new(CursorMode::Normal(5), None, None));
+        doc.add_editor_cursor(EditorId::next(), cursor);
+
+        // A clean buffer is reloaded, and the cursor stays on its line and column. The
+        // modification time is reset, as the file may be written within its resolution.
+        fs::write(&path, "one\ntwo\nthree\n").unwrap();
+        doc.modified.set(Some(SystemTime::UNIX_EPOCH));
+        doc.check_file(modified_time(&path));
+        assert_eq!(doc.text().slice_to_cow(..), "one\ntwo\nthree\n");
+        assert_eq!(cursor.get_untracked().offset(), 5);
+        assert!(!doc.conflict.get_untracked());
+
+        // A dirty buffer is kept, and the change is a conflict, which saving resolves.
+        doc.edit_single(Selection::caret(0), "zero\n", EditType::InsertChars);
+        fs::write(&path, "other\n").unwrap();
+        doc.modified.set(Some(SystemTime::UNIX_EPOCH));
+        doc.check_file(modified_time(&path));
+        assert!(doc.conflict.get_untracked());
+        assert_eq!(doc.text().slice_to_cow(..), "zero\none\ntwo\nthree\n");
+
+        doc.save().unwrap();
+        assert!(!doc.conflict.get_untracked());
+        assert!(!doc.is_dirty());
+        assert_eq!(
+            fs::read_to_string(&path).unwrap(),
+            "zero\none\ntwo\nthree\n"
+        );
+
+        cx.dispose();
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
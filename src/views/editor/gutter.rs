@@ -10,9 +10,11 @@ use crate::{
     views::Decorators,
     Renderer,
 };
+use std::{collections::BTreeSet, rc::Rc};
+
 use floem_editor_core::{cursor::CursorMode, mode::Mode};
-use floem_reactive::{RwSignal, SignalGet, SignalWith};
-use peniko::kurbo::Rect;
+use floem_reactive::{create_effect, RwSignal, SignalGet, SignalTrack, SignalUpdate, SignalWith};
+use peniko::kurbo::{Circle, Rect};
 use peniko::Color;
 
 use super::{CurrentLineColor, Editor};
@@ -40,6 +42,84 @@ impl GutterStyle {
     }
 }
 
+/// How the line numbers of the gutter are shown.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum LineNumbers {
+    /// The number of every line.
+    #[default]
+    Absolute,
+    /// The distance of every line to the line of the cursor, which shows `0`.
+    Relative,
+    /// The distance of every line to the line of the cursor, which shows its own number.
+    Hybrid,
+}
+impl LineNumbers {
+    /// The number shown for `line` when the cursor is on `current_line`, both zero-based.
+    pub fn number(&self, line: usize, current_line: usize) -> usize {
+        match self {
+            LineNumbers::Absolute => line + 1,
+            LineNumbers::Hybrid if line == current_line => line + 1,
+            LineNumbers::Relative | LineNumbers::Hybrid => line.abs_diff(current_line),
+        }
+    }
+}
+impl std::fmt::Display for LineNumbers {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_fmt(format_args!("{self:?}"))
+    }
+}
+
+/// An extra column of the gutter, drawn to the left of the line numbers, which shows markers for
+/// the lines, such as breakpoints, changes or folds.
+#[derive(Clone)]
+pub struct GutterColumn {
+    width: f64,
+    paint: Rc<dyn Fn(&mut PaintCx, usize, Rect)>,
+    on_click: Option<Rc<dyn Fn(usize)>>,
+}
+impl GutterColumn {
+    /// A column of the width, where `paint` draws the marker of a buffer line, if it has one,
+    /// within the rect of the line in the column.
+    pub fn new(width: f64, paint: impl Fn(&mut PaintCx, usize, Rect) + 'static) -> Self {
+        Self {
+            width,
+            paint: Rc::new(paint),
+            on_click: None,
+        }
+    }
+
+    /// Sets the function called with the buffer line when the column is clicked.
+    pub fn on_click(mut self, on_click: impl Fn(usize) + 'static) -> Self {
+        self.on_click = Some(Rc::new(on_click));
+        self
+    }
+
+    /// A column that draws a dot on the lines with a breakpoint, and toggles the breakpoint of a
+    /// line when it's clicked.
+    pub fn breakpoints(breakpoints: RwSignal<BTreeSet<usize>>, color: Color) -> Self {
+        GutterColumn::new(16.0, move |cx, line, rect| {
+            if breakpoints.with_untracked(|breakpoints| breakpoints.contains(&line)) {
+                let radius = (rect.width().min(rect.height()) / 2.0 - 2.0).max(1.0);
+                cx.fill(&Circle::new(rect.center(), radius), color, 0.0);
+            }
+        })
+        .on_click(move |line| toggle_breakpoint(breakpoints, line))
+    }
+
+    pub fn width(&self) -> f64 {
+        self.width
+    }
+}
+
+/// Adds a breakpoint to the line, or removes the one it has.
+pub fn toggle_breakpoint(breakpoints: RwSignal<BTreeSet<usize>>, line: usize) {
+    breakpoints.update(|breakpoints| {
+        if !breakpoints.remove(&line) {
+            breakpoints.insert(line);
+        }
+    });
+}
+
 pub struct EditorGutterView {
     id: ViewId,
     editor: RwSignal<Editor>,
@@ -53,6 +133,15 @@ style_class!(pub GutterClass);
 pub fn editor_gutter_view(editor: RwSignal<Editor>) -> EditorGutterView {
     let id = ViewId::new();
 
+    // The columns and the breakpoints change what the gutter shows, or its width.
+    let gutter_columns = editor.with_untracked(|ed| ed.gutter_columns);
+    let breakpoints = editor.with_untracked(|ed| ed.breakpoints);
+    create_effect(move |_| {
+        gutter_columns.track();
+        breakpoints.track();
+        id.request_layout();
+    });
+
     EditorGutterView {
         id,
         editor,
@@ -88,8 +177,14 @@ impl View for EditorGutterView {
                 .new_leaf(taffy::style::Style::DEFAULT)
                 .unwrap();
 
+            let columns_width = self.columns_width();
             let style = Style::new()
-                .width(self.gutter_style.left_padding() + width + self.gutter_style.right_padding())
+                .width(
+                    columns_width
+                        + self.gutter_style.left_padding()
+                        + width
+                        + self.gutter_style.right_padding(),
+                )
                 .height(height)
                 .to_taffy_style();
             let _ = self.id.taffy().borrow_mut().set_style(layout_node, style);
@@ -115,6 +210,7 @@ impl View for EditorGutterView {
 
         let widest_text_width = self.compute_widest_text_width(&attrs_list);
         if (self.full_width
+            - self.columns_width()
             - widest_text_width
             - self.gutter_style.left_padding()
             - self.gutter_style.right_padding())
@@ -149,9 +245,8 @@ impl View for EditorGutterView {
             .font_size(style.font_size(edid, 0) as f32);
         let attrs_list = AttrsList::new(attrs);
         let current_line_attrs_list = AttrsList::new(attrs.color(accent_color));
-        let show_relative = editor.es.with_untracked(|es| es.modal())
-            && editor.es.with_untracked(|es| es.modal_relative_line())
-            && mode != Mode::Insert;
+        let line_numbers = line_numbers(&editor, mode);
+        let columns = editor.gutter_columns.get_untracked();
 
         self.text_width = self.compute_widest_text_width(&attrs_list);
 
@@ -164,16 +259,7 @@ impl View for EditorGutterView {
 
                 let line_height = f64::from(style.line_height(edid, line));
 
-                let text = if show_relative {
-                    if line == current_line {
-                        line + 1
-                    } else {
-                        line.abs_diff(current_line)
-                    }
-                } else {
-                    line + 1
-                }
-                .to_string();
+                let text = line_numbers.number(line, current_line).to_string();
 
                 let mut text_layout = TextLayout::new();
                 if line == current_line {
@@ -220,12 +306,78 @@ impl View for EditorGutterView {
                 );
 
                 cx.draw_text(&text_layout, pos);
+
+                let mut x = 0.0;
+                for column in &columns {
+                    let rect =
+                        Rect::from_origin_size((x, y - viewport.y0), (column.width, line_height));
+                    (column.paint)(cx, line, rect);
+                    x += column.width;
+                }
             }
         });
     }
 }
 
+/// How the line numbers are shown in the editor's current mode.
+fn line_numbers(editor: &Editor, mode: Mode) -> LineNumbers {
+    let (modal, modal_line_numbers, modal_relative_line) = editor.es.with_untracked(|es| {
+        (
+            es.modal(),
+            es.modal_line_numbers(),
+            es.modal_relative_line(),
+        )
+    });
+    if !modal || mode == Mode::Insert {
+        return LineNumbers::Absolute;
+    }
+    match modal_line_numbers {
+        LineNumbers::Absolute if modal_relative_line => LineNumbers::Hybrid,
+        line_numbers => line_numbers,
+    }
+}
+
+/// Calls the click handler of the gutter column at `x` with the buffer line at `y`, both relative
+/// to the gutter. Returns whether there was a handler.
+pub(crate) fn click_gutter_column(editor: &Editor, x: f64, y: f64) -> bool {
+    let columns = editor.gutter_columns.get_untracked();
+    let mut left = 0.0;
+    let Some(column) = columns.iter().find(|column| {
+        let found = x >= left && x < left + column.width;
+        left += column.width;
+        found
+    }) else {
+        return false;
+    };
+    let Some(on_click) = &column.on_click else {
+        return false;
+    };
+
+    let viewport = editor.viewport.get_untracked();
+    let last_line = editor.last_line();
+    let line = editor.screen_lines.with_untracked(|screen_lines| {
+        screen_lines.iter_lines_y().find_map(|(line, line_y)| {
+            let line_height = f64::from(editor.line_height(line));
+            let top = line_y - viewport.y0;
+            (line <= last_line && y >= top && y < top + line_height).then_some(line)
+        })
+    });
+    let Some(line) = line else {
+        return false;
+    };
+    on_click(line);
+
+    true
+}
+
 impl EditorGutterView {
+    fn columns_width(&self) -> f64 {
+        self.editor.with_untracked(|ed| {
+            ed.gutter_columns
+                .with_untracked(|columns| columns.iter().map(GutterColumn::width).sum())
+        })
+    }
+
     fn compute_widest_text_width(&mut self, attrs_list: &AttrsList) -> f64 {
         let last_line = self.editor.get_untracked().last_line() + 1;
         let mut text = TextLayout::new();
@@ -233,3 +385,17 @@ impl EditorGutterView {
         text.size().width
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::LineNumbers;
+
+    #[test]
+    fn line_number_modes() {
+        let numbers =
+            |mode: LineNumbers| (0..5).map(|line| mode.number(line, 2)).collect::<Vec<_>>();
+        assert_eq!(numbers(LineNumbers::Absolute), [1, 2, 3, 4, 5]);
+        assert_eq!(numbers(LineNumbers::Relative), [2, 1, 0, 1, 2]);
+        assert_eq!(numbers(LineNumbers::Hybrid), [2, 1, 3, 1, 2]);
+    }
+}
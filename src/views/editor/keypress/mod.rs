@@ -1,4 +1,5 @@
 pub mod key;
+pub mod modal;
 pub mod press;
 
 use std::{collections::HashMap, str::FromStr};
@@ -8,17 +9,21 @@ use floem_editor_core::{
     command::{EditCommand, MoveCommand, MultiSelectionCommand, ScrollCommand},
     mode::Mode,
 };
-use floem_reactive::{SignalGet, SignalWith};
+use floem_reactive::{SignalGet, SignalUpdate, SignalWith};
 
 use super::{
     command::{Command, CommandExecuted},
     Editor,
 };
 
-use self::{key::KeyInput, press::KeyPress};
+use self::{
+    key::KeyInput,
+    modal::{ModalKey, ModalKeys},
+    press::KeyPress,
+};
 
 /// The default keymap handler does not have modal-mode specific
-/// keybindings.
+/// keybindings. Those are handled by [`ModalKeys`].
 #[derive(Clone)]
 pub struct KeypressMap {
     pub keymaps: HashMap<KeyPress, Command>,
@@ -142,6 +147,7 @@ fn add_default_macos(c: &mut HashMap<KeyPress, Command>) {
     // `defaults/keymaps-macos.toml`
 
     // --- Basic editing ---
+    c.insert(key("s", Modifiers::META), Command::Save);
     c.insert(key("z", Modifiers::META), Command::Edit(EditCommand::Undo));
     c.insert(
         key("z", Modifiers::META | Modifiers::SHIFT),
@@ -270,6 +276,7 @@ fn add_default_nonmacos(c: &mut HashMap<KeyPress, Command>) {
     // `defaults/keymaps-nonmacos.toml`
 
     // --- Basic editing ---
+    c.insert(key("s", Modifiers::CONTROL), Command::Save);
     c.insert(
         key("z", Modifiers::CONTROL),
         Command::Edit(EditCommand::Undo),
@@ -378,7 +385,33 @@ pub fn default_key_handler(
     editor: RwSignal<Editor>,
 ) -> impl Fn(&KeyPress, Modifiers) -> CommandExecuted + 'static {
     let keypress_map = KeypressMap::default();
+    let modal_keys = ModalKeys::default();
     move |keypress, modifiers| {
+        let modal_key = editor.with_untracked(|editor| {
+            if !editor.es.with_untracked(|es| es.modal()) {
+                return None;
+            }
+            let cursor = editor.cursor.get_untracked();
+            let recording_macro = editor
+                .macros
+                .with_untracked(|macros| macros.recording().is_some());
+            modal_keys.key(
+                keypress,
+                cursor.get_mode(),
+                cursor.motion_mode.is_some(),
+                recording_macro,
+            )
+        });
+        match modal_key {
+            Some(ModalKey::Pending) => return CommandExecuted::Yes,
+            Some(ModalKey::Command(command)) => {
+                let count = modal_keys.take_count();
+                return editor
+                    .with_untracked(|editor| run_key_command(editor, &command, count, modifiers));
+            }
+            None => {}
+        }
+
         let command = keypress_map.keymaps.get(keypress).or_else(|| {
             let mode = editor.get_untracked().cursor.get_untracked().get_mode();
             if mode == Mode::Insert {
@@ -390,14 +423,43 @@ pub fn default_key_handler(
             }
         });
 
+        let count = modal_keys.take_count();
         let Some(command) = command else {
             return CommandExecuted::No;
         };
 
-        editor.with_untracked(|editor| {
-            editor
-                .doc()
-                .run_command(editor, command, Some(1), modifiers)
-        })
+        editor.with_untracked(|editor| run_key_command(editor, command, count, modifiers))
     }
 }
+
+/// Runs the command of a key with the count typed before it, recording it into the macro being
+/// recorded, and as part of a change in the modal mode so that it can be repeated.
+fn run_key_command(
+    editor: &Editor,
+    command: &Command,
+    count: Option<usize>,
+    modifiers: Modifiers,
+) -> CommandExecuted {
+    let modal = editor.es.with_untracked(|es| es.modal());
+    let before = editor.cursor.with_untracked(|cursor| cursor.get_mode());
+
+    let executed = editor
+        .doc()
+        .run_command(editor, command, Some(count.unwrap_or(1)), modifiers);
+
+    if executed == CommandExecuted::Yes {
+        editor
+            .macros
+            .update(|macros| macros.record_command(command));
+    }
+    if modal && executed == CommandExecuted::Yes {
+        let (after, motion_pending) = editor
+            .cursor
+            .with_untracked(|cursor| (cursor.get_mode(), cursor.motion_mode.is_some()));
+        editor
+            .changes
+            .update(|changes| changes.record_command(command, before, after, motion_pending));
+    }
+
+    executed
+}
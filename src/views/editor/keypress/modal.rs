@@ -0,0 +1,492 @@
+use std::cell::Cell;
+
+use floem_editor_core::{
+    command::{EditCommand, MotionModeCommand, MoveCommand},
+    mode::Mode,
+    movement::{InlineFind, InlineFindDirection},
+    register::Register,
+    text_object::TextObject,
+};
+
+use crate::{
+    keyboard::{Key, NamedKey},
+    views::editor::{command::Command, macros::Macros},
+};
+
+use super::{key::KeyInput, press::KeyPress};
+
+/// A key sequence of the modal mode that's waiting for the character typed after it.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Pending {
+    /// `i` or `a` after an operator or in visual mode, waiting for the kind of text object.
+    TextObject { around: bool },
+    /// `f`, `t`, `F` or `T`, waiting for the character to find.
+    InlineFind {
+        direction: InlineFindDirection,
+        till: bool,
+    },
+    /// `"`, waiting for the name of the register.
+    Register,
+    /// `m`, waiting for the name of the mark to set.
+    CreateMark,
+    /// `` ` `` or `'`, waiting for the name of the mark to go to.
+    GoToMark { line: bool },
+    /// `q`, waiting for the name of the register to record a macro into.
+    RecordMacro,
+    /// `@`, waiting for the name of the register of the macro to play.
+    PlayMacro,
+    /// `g`, waiting for the rest of a command like `gg`.
+    Go,
+}
+
+/// What a key does in the modal mode.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ModalKey {
+    /// The key starts a sequence that needs another key.
+    Pending,
+    Command(Command),
+}
+
+/// The keys of the modal mode in normal and visual mode: the motions, like `w` and `$`, which
+/// also complete an operator like the `d` of `dw`, the operators and the sequences that take the
+/// character typed after them as an argument, like the text object of `diw`, the inline find
+/// of `f,` and the register of `"ay`, which a [`KeypressMap`](super::KeypressMap) can't express.
+///
+/// The digits typed before a command are its count, like the `3` of `3@q`, which is read with
+/// [`ModalKeys::take_count`].
+#[derive(Debug, Default)]
+pub struct ModalKeys {
+    pending: Cell<Option<Pending>>,
+    count: Cell<Option<usize>>,
+}
+
+impl ModalKeys {
+    /// What the key does in `mode`, or `None` when it isn't a modal key.
+    /// `motion_pending` is set when an operator, like `d`, is waiting for its motion, and
+    /// `recording_macro` when a macro is being recorded, which `q` stops.
+    pub fn key(
+        &self,
+        keypress: &KeyPress,
+        mode: Mode,
+        motion_pending: bool,
+        recording_macro: bool,
+    ) -> Option<ModalKey> {
+        let pending = self.pending.take();
+
+        let c = match &keypress.key {
+            KeyInput::Keyboard(Key::Named(NamedKey::Escape), _) => {
+                self.count.set(None);
+                return Some(ModalKey::Command(Command::Edit(EditCommand::NormalMode)));
+            }
+            KeyInput::Keyboard(Key::Character(c), _) if keypress.is_char() => c,
+            _ => return None,
+        };
+        let mut chars = c.chars();
+        let (Some(c), None) = (chars.next(), chars.next()) else {
+            return None;
+        };
+        if mode == Mode::Insert {
+            return None;
+        }
+
+        if let Some(pending) = pending {
+            let command = match pending {
+                Pending::TextObject { around } => {
+                    Command::TextObject(TextObject::from_char(c, around)?)
+                }
+                Pending::InlineFind { direction, till } => {
+                    Command::InlineFind(InlineFind { direction, c, till })
+                }
+                Pending::Register if Register::is_valid_name(c) => Command::SelectRegister(c),
+                Pending::CreateMark | Pending::GoToMark { .. } if !c.is_ascii_alphabetic() => {
+                    return None;
+                }
+                Pending::CreateMark => Command::CreateMark(c),
+                Pending::GoToMark { line } => Command::GoToMark { name: c, line },
+                Pending::Register => return None,
+                Pending::RecordMacro if Macros::is_valid_name(c) => Command::RecordMacro(c),
+                Pending::PlayMacro if c == '@' => Command::PlayLastMacro,
+                Pending::PlayMacro if Macros::is_valid_name(c) => Command::PlayMacro(c),
+                Pending::RecordMacro | Pending::PlayMacro => return None,
+                Pending::Go if c == 'g' => Command::Move(MoveCommand::GotoLineDefaultFirst),
+                Pending::Go => return None,
+            };
+            return Some(ModalKey::Command(command));
+        }
+
+        // `0` only continues a count, since on its own it moves to the start of the line.
+        if let Some(digit) = c
+            .to_digit(10)
+            .filter(|digit| *digit != 0 || self.count.get().is_some())
+        {
+            let count = self.count.get().unwrap_or(0);
+            let count = count.saturating_mul(10).saturating_add(digit as usize);
+            self.count.set(Some(count));
+            return Some(ModalKey::Pending);
+        }
+
+        let visual = matches!(mode, Mode::Visual(_));
+        let command = match c {
+            'f' | 't' | 'F' | 'T' => {
+                let direction = if c.is_lowercase() {
+                    InlineFindDirection::Right
+                } else {
+                    InlineFindDirection::Left
+                };
+                let till = c.eq_ignore_ascii_case(&'t');
+                self.pending
+                    .set(Some(Pending::InlineFind { direction, till }));
+                return Some(ModalKey::Pending);
+            }
+            '"' | 'm' | '`' | '\'' => {
+                let pending = match c {
+                    '"' => Pending::Register,
+                    'm' => Pending::CreateMark,
+                    _ => Pending::GoToMark { line: c == '\'' },
+                };
+                self.pending.set(Some(pending));
+                return Some(ModalKey::Pending);
+            }
+            'h' => Command::Move(MoveCommand::Left),
+            'j' => Command::Move(MoveCommand::Down),
+            'k' => Command::Move(MoveCommand::Up),
+            'l' => Command::Move(MoveCommand::Right),
+            'w' => Command::Move(MoveCommand::WordForward),
+            'b' => Command::Move(MoveCommand::WordBackward),
+            'e' => Command::Move(MoveCommand::WordEndForward),
+            '0' => Command::Move(MoveCommand::LineStart),
+            '^' => Command::Move(MoveCommand::LineStartNonBlank),
+            '$' => Command::Move(MoveCommand::LineEnd),
+            '{' => Command::Move(MoveCommand::ParagraphBackward),
+            '}' => Command::Move(MoveCommand::ParagraphForward),
+            '%' => Command::Move(MoveCommand::MatchPairs),
+            // The count of a key is 1 when none was typed, which would make `G` go to the first
+            // line.
+            'G' if self.count.get().is_some() => Command::Move(MoveCommand::GotoLineDefaultLast),
+            'G' => Command::Move(MoveCommand::DocumentEnd),
+            'g' => {
+                self.pending.set(Some(Pending::Go));
+                return Some(ModalKey::Pending);
+            }
+            '.' => Command::RepeatLastChange,
+            'q' if recording_macro => Command::ToggleMacroRecording,
+            'q' | '@' => {
+                let pending = if c == 'q' {
+                    Pending::RecordMacro
+                } else {
+                    Pending::PlayMacro
+                };
+                self.pending.set(Some(pending));
+                return Some(ModalKey::Pending);
+            }
+            ';' => Command::RepeatInlineFind { reverse: false },
+            ',' => Command::RepeatInlineFind { reverse: true },
+            'i' | 'a' if motion_pending || visual => {
+                self.pending
+                    .set(Some(Pending::TextObject { around: c == 'a' }));
+                return Some(ModalKey::Pending);
+            }
+            'i' => Command::Edit(EditCommand::InsertMode),
+            'a' => Command::Edit(EditCommand::Append),
+            'v' => Command::Edit(EditCommand::ToggleVisualMode),
+            'V' => Command::Edit(EditCommand::ToggleLinewiseVisualMode),
+            'd' if visual => Command::Edit(EditCommand::DeleteForward),
+            'y' if visual => Command::Edit(EditCommand::Yank),
+            '>' if visual => Command::Edit(EditCommand::IndentLine),
+            '<' if visual => Command::Edit(EditCommand::OutdentLine),
+            'x' => Command::Edit(EditCommand::DeleteForward),
+            'p' => Command::Edit(EditCommand::Paste),
+            'P' => Command::Edit(EditCommand::PasteBefore),
+            'd' => Command::MotionMode(MotionModeCommand::MotionModeDelete),
+            'y' => Command::MotionMode(MotionModeCommand::MotionModeYank),
+            '>' => Command::MotionMode(MotionModeCommand::MotionModeIndent),
+            '<' => Command::MotionMode(MotionModeCommand::MotionModeOutdent),
+            _ => return None,
+        };
+        Some(ModalKey::Command(command))
+    }
+
+    /// Whether a key sequence is waiting for another key.
+    pub fn is_pending(&self) -> bool {
+        self.pending.get().is_some()
+    }
+
+    /// The count typed before the command of the last key, which is reset.
+    pub fn take_count(&self) -> Option<usize> {
+        self.count.take()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use floem_editor_core::{
+        command::{EditCommand, MotionModeCommand, MoveCommand},
+        mode::{Mode, VisualMode},
+        movement::{InlineFind, InlineFindDirection},
+        text_object::TextObject,
+    };
+
+    use crate::{
+        keyboard::{Key, Modifiers},
+        views::editor::{
+            command::Command,
+            keypress::{key::KeyInput, press::KeyPress},
+        },
+    };
+
+    use super::{ModalKey, ModalKeys};
+
+    fn key(keys: &ModalKeys, s: &str, mode: Mode, motion_pending: bool) -> Option<ModalKey> {
+        key_recording(keys, s, mode, motion_pending, false)
+    }
+
+    fn key_recording(
+        keys: &ModalKeys,
+        s: &str,
+        mode: Mode,
+        motion_pending: bool,
+        recording_macro: bool,
+    ) -> Option<ModalKey> {
+        let mods = if s.chars().any(char::is_uppercase) {
+            Modifiers::SHIFT
+        } else {
+            Modifiers::empty()
+        };
+        // Parsing lowercases the characters, like for the keys of a keymap.
+        let key = match KeyInput::from_str(s).unwrap() {
+            KeyInput::Keyboard(Key::Character(_), code) if s.chars().count() == 1 => {
+                KeyInput::Keyboard(Key::Character(s.into()), code)
+            }
+            key => key,
+        };
+        keys.key(
+            &KeyPress::new(key, mods),
+            mode,
+            motion_pending,
+            recording_macro,
+        )
+    }
+
+    #[test]
+    fn text_objects_follow_operators() {
+        let keys = ModalKeys::default();
+        assert_eq!(
+            key(&keys, "d", Mode::Normal, false),
+            Some(ModalKey::Command(Command::MotionMode(
+                MotionModeCommand::MotionModeDelete
+            )))
+        );
+        assert_eq!(key(&keys, "i", Mode::Normal, true), Some(ModalKey::Pending));
+        assert!(keys.is_pending());
+        assert_eq!(
+            key(&keys, "w", Mode::Normal, true),
+            Some(ModalKey::Command(Command::TextObject(TextObject::Word {
+                around: false
+            })))
+        );
+        assert!(!keys.is_pending());
+
+        let visual = Mode::Visual(VisualMode::Normal);
+        assert_eq!(key(&keys, "a", visual, false), Some(ModalKey::Pending));
+        assert_eq!(
+            key(&keys, "p", visual, false),
+            Some(ModalKey::Command(Command::TextObject(
+                TextObject::Paragraph { around: true }
+            )))
+        );
+
+        // Without an operator, `i` enters insert mode, where the keys aren't modal.
+        assert!(matches!(
+            key(&keys, "i", Mode::Normal, false),
+            Some(ModalKey::Command(Command::Edit(_)))
+        ));
+        assert_eq!(key(&keys, "f", Mode::Insert, false), None);
+    }
+
+    #[test]
+    fn motions_complete_operators() {
+        let keys = ModalKeys::default();
+        assert_eq!(
+            key(&keys, "j", Mode::Normal, false),
+            Some(ModalKey::Command(Command::Move(MoveCommand::Down)))
+        );
+        assert_eq!(
+            key(&keys, "d", Mode::Normal, false),
+            Some(ModalKey::Command(Command::MotionMode(
+                MotionModeCommand::MotionModeDelete
+            )))
+        );
+        assert_eq!(
+            key(&keys, "w", Mode::Normal, true),
+            Some(ModalKey::Command(Command::Move(MoveCommand::WordForward)))
+        );
+        assert_eq!(
+            key(&keys, "$", Mode::Visual(VisualMode::Normal), false),
+            Some(ModalKey::Command(Command::Move(MoveCommand::LineEnd)))
+        );
+
+        assert_eq!(
+            key(&keys, "g", Mode::Normal, false),
+            Some(ModalKey::Pending)
+        );
+        assert_eq!(
+            key(&keys, "g", Mode::Normal, false),
+            Some(ModalKey::Command(Command::Move(
+                MoveCommand::GotoLineDefaultFirst
+            )))
+        );
+        assert_eq!(
+            key(&keys, "G", Mode::Normal, false),
+            Some(ModalKey::Command(Command::Move(MoveCommand::DocumentEnd)))
+        );
+        assert_eq!(
+            key(&keys, "5", Mode::Normal, false),
+            Some(ModalKey::Pending)
+        );
+        assert_eq!(
+            key(&keys, "G", Mode::Normal, false),
+            Some(ModalKey::Command(Command::Move(
+                MoveCommand::GotoLineDefaultLast
+            )))
+        );
+        assert_eq!(keys.take_count(), Some(5));
+    }
+
+    #[test]
+    fn inline_find_takes_the_next_character() {
+        let keys = ModalKeys::default();
+        assert_eq!(
+            key(&keys, "T", Mode::Normal, false),
+            Some(ModalKey::Pending)
+        );
+        assert_eq!(
+            key(&keys, "x", Mode::Normal, false),
+            Some(ModalKey::Command(Command::InlineFind(InlineFind {
+                direction: InlineFindDirection::Left,
+                c: 'x',
+                till: true,
+            })))
+        );
+        assert_eq!(
+            key(&keys, ",", Mode::Normal, false),
+            Some(ModalKey::Command(Command::RepeatInlineFind {
+                reverse: true
+            }))
+        );
+
+        // Another key cancels the pending find.
+        assert_eq!(
+            key(&keys, "f", Mode::Normal, false),
+            Some(ModalKey::Pending)
+        );
+        assert_eq!(
+            key(&keys, "escape", Mode::Normal, false),
+            Some(ModalKey::Command(Command::Edit(EditCommand::NormalMode)))
+        );
+        assert!(!keys.is_pending());
+    }
+
+    #[test]
+    fn registers_and_marks_take_a_name() {
+        let keys = ModalKeys::default();
+        assert_eq!(
+            key(&keys, "\"", Mode::Normal, false),
+            Some(ModalKey::Pending)
+        );
+        assert_eq!(
+            key(&keys, "A", Mode::Normal, false),
+            Some(ModalKey::Command(Command::SelectRegister('A')))
+        );
+        assert_eq!(
+            key(&keys, "'", Mode::Normal, false),
+            Some(ModalKey::Pending)
+        );
+        assert_eq!(
+            key(&keys, "b", Mode::Normal, false),
+            Some(ModalKey::Command(Command::GoToMark {
+                name: 'b',
+                line: true
+            }))
+        );
+
+        // Marks are named by letters.
+        assert_eq!(
+            key(&keys, "m", Mode::Normal, false),
+            Some(ModalKey::Pending)
+        );
+        assert_eq!(key(&keys, "1", Mode::Normal, false), None);
+        assert!(!keys.is_pending());
+
+        assert_eq!(
+            key(&keys, ".", Mode::Normal, false),
+            Some(ModalKey::Command(Command::RepeatLastChange))
+        );
+    }
+
+    #[test]
+    fn macros_are_recorded_and_played_by_register() {
+        let keys = ModalKeys::default();
+        assert_eq!(
+            key(&keys, "q", Mode::Normal, false),
+            Some(ModalKey::Pending)
+        );
+        assert_eq!(
+            key(&keys, "a", Mode::Normal, false),
+            Some(ModalKey::Command(Command::RecordMacro('a')))
+        );
+        assert_eq!(
+            key_recording(&keys, "q", Mode::Normal, false, true),
+            Some(ModalKey::Command(Command::ToggleMacroRecording))
+        );
+
+        assert_eq!(
+            key(&keys, "@", Mode::Normal, false),
+            Some(ModalKey::Pending)
+        );
+        assert_eq!(
+            key(&keys, "a", Mode::Normal, false),
+            Some(ModalKey::Command(Command::PlayMacro('a')))
+        );
+        assert_eq!(
+            key(&keys, "@", Mode::Normal, false),
+            Some(ModalKey::Pending)
+        );
+        assert_eq!(
+            key(&keys, "@", Mode::Normal, false),
+            Some(ModalKey::Command(Command::PlayLastMacro))
+        );
+    }
+
+    #[test]
+    fn counts_come_before_commands() {
+        let keys = ModalKeys::default();
+        assert_eq!(
+            key(&keys, "1", Mode::Normal, false),
+            Some(ModalKey::Pending)
+        );
+        assert_eq!(
+            key(&keys, "0", Mode::Normal, false),
+            Some(ModalKey::Pending)
+        );
+        assert_eq!(
+            key(&keys, "@", Mode::Normal, false),
+            Some(ModalKey::Pending)
+        );
+        assert_eq!(
+            key(&keys, "q", Mode::Normal, false),
+            Some(ModalKey::Command(Command::PlayMacro('q')))
+        );
+        assert_eq!(keys.take_count(), Some(10));
+        assert_eq!(keys.take_count(), None);
+
+        // On its own, `0` isn't a count but moves to the start of the line.
+        assert_eq!(
+            key(&keys, "0", Mode::Normal, false),
+            Some(ModalKey::Command(Command::Move(MoveCommand::LineStart)))
+        );
+        assert_eq!(key(&keys, "3", Mode::Insert, false), None);
+        assert_eq!(keys.take_count(), None);
+    }
+}
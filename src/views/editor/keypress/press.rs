@@ -1,6 +1,8 @@
 use std::fmt::Display;
 
-use crate::keyboard::{Key, KeyCode, KeyEvent, Modifiers, NamedKey, PhysicalKey};
+use crate::keyboard::{
+    split_key_press_lenient, Key, KeyCode, KeyEvent, Modifiers, NamedKey, PhysicalKey,
+};
 
 use super::key::KeyInput;
 
@@ -81,37 +83,15 @@ impl KeyPress {
         keys.trim().to_string()
     }
 
+    /// Parses key presses separated by spaces, such as `Ctrl+K Ctrl+S`, skipping those whose key
+    /// isn't valid. The modifiers are those of [`HotKey::parse`](crate::keyboard::HotKey::parse),
+    /// but unlike it, a modifier that isn't known is ignored rather than making the key press
+    /// invalid.
     pub fn parse(key: &str) -> Vec<Self> {
-        key.split(' ')
+        key.split_whitespace()
             .filter_map(|k| {
-                let (modifiers, key) = match k.rsplit_once('+') {
-                    Some(pair) => pair,
-                    None => ("", k),
-                };
-
-                let key = match key.parse().ok() {
-                    Some(key) => key,
-                    None => {
-                        // Skip past unrecognized key definitions
-                        // warn!("Unrecognized key: {key}");
-                        return None;
-                    }
-                };
-
-                let mut mods = Modifiers::empty();
-                for part in modifiers.to_lowercase().split('+') {
-                    match part {
-                        "ctrl" => mods.set(Modifiers::CONTROL, true),
-                        "meta" => mods.set(Modifiers::META, true),
-                        "shift" => mods.set(Modifiers::SHIFT, true),
-                        "alt" => mods.set(Modifiers::ALT, true),
-                        "altgr" => mods.set(Modifiers::ALT, true),
-                        "" => (),
-                        // other => warn!("Invalid key modifier: {}", other),
-                        _ => {}
-                    }
-                }
-
+                let (mods, key) = split_key_press_lenient(k)?;
+                let key = key.parse().ok()?;
                 Some(KeyPress { key, mods })
             })
             .collect()
@@ -0,0 +1,388 @@
+//! Keyboard macros, like those recorded with `q` and played with `@` in vim.
+//!
+//! Like in vim, a macro is kept as the text of a register, so that it can be pasted, edited and
+//! yanked back. Typed text is kept as it is, with `<` written as `<lt>`, and each command is
+//! written as its name between angle brackets, followed by its argument if it has one, like
+//! `<down>`, `<text_object iw>` or `<inline_find_right x till>`.
+
+use floem_editor_core::{
+    mode::VisualMode,
+    movement::{InlineFind, InlineFindDirection},
+    register::{Register, RegisterData},
+    text_object::TextObject,
+};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use super::{command::Command, repeat::EditorInput};
+
+/// The register that [`Command::ToggleMacroRecording`] records into. It's a named register, so
+/// yanks and deletes don't overwrite the macro.
+pub const DEFAULT_MACRO_REGISTER: char = 'q';
+
+/// A recorded sequence of commands and typed text, which can be played back on an editor.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Macro {
+    pub inputs: Vec<EditorInput>,
+}
+
+impl Macro {
+    pub fn new(inputs: Vec<EditorInput>) -> Self {
+        Self { inputs }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.inputs.is_empty()
+    }
+
+    /// The macro in the register `name`, if it holds any text.
+    pub fn from_register(register: &Register, name: char) -> Option<Self> {
+        register
+            .get(name)
+            .map(|data| Self::from_text(&data.content))
+            .filter(|recorded| !recorded.is_empty())
+    }
+
+    /// The macro as the text of a register. See the [module documentation](self).
+    pub fn to_text(&self) -> String {
+        let mut text = String::new();
+        for input in &self.inputs {
+            match input {
+                EditorInput::Command(command) => text.push_str(&command_text(command)),
+                EditorInput::Text(typed) => text.push_str(&typed.replace('<', "<lt>")),
+            }
+        }
+        text
+    }
+
+    /// Reads the text of a register as a macro. The text that isn't a command is typed.
+    pub fn from_text(text: &str) -> Self {
+        let mut recorded = Macro::default();
+        let mut rest = text;
+        while let Some(c) = rest.chars().next() {
+            if let Some(after) = rest.strip_prefix("<lt>") {
+                recorded.push_text("<");
+                rest = after;
+            } else if let Some((command, after)) = rest.strip_prefix('<').and_then(parse_command) {
+                recorded.push_command(&command);
+                rest = after;
+            } else {
+                recorded.push_text(&rest[..c.len_utf8()]);
+                rest = &rest[c.len_utf8()..];
+            }
+        }
+        recorded
+    }
+
+    fn push_command(&mut self, command: &Command) {
+        self.inputs.push(EditorInput::Command(command.clone()));
+    }
+
+    fn push_text(&mut self, text: &str) {
+        match self.inputs.last_mut() {
+            Some(EditorInput::Text(typed)) => typed.push_str(text),
+            _ => self.inputs.push(EditorInput::Text(text.to_string())),
+        }
+    }
+}
+
+fn command_text(command: &Command) -> String {
+    let name = command.str();
+    match command {
+        Command::InlineFind(find) if find.till => format!("<{name} {} till>", find.c),
+        Command::InlineFind(find) => format!("<{name} {}>", find.c),
+        Command::RepeatInlineFind { reverse: true } => format!("<{name} reverse>"),
+        Command::TextObject(object) => {
+            let (c, around) = object.to_char();
+            format!("<{name} {}{c}>", if around { 'a' } else { 'i' })
+        }
+        Command::GoToMark { name: mark, line } => {
+            format!("<{name} {mark}{}>", if *line { " line" } else { "" })
+        }
+        Command::SelectRegister(c)
+        | Command::CreateMark(c)
+        | Command::RecordMacro(c)
+        | Command::PlayMacro(c) => format!("<{name} {c}>"),
+        _ => format!("<{name}>"),
+    }
+}
+
+/// Parses a command written by [`command_text`], after its `<`, returning the text after it.
+fn parse_command(text: &str) -> Option<(Command, &str)> {
+    let (name, rest) = text.split_at(text.find([' ', '>'])?);
+    let (command, rest) = if name == "text_object" {
+        let (kind, rest) = split_char(rest.strip_prefix(' ')?)?;
+        let (c, rest) = split_char(rest)?;
+        let around = match kind {
+            'i' => false,
+            'a' => true,
+            _ => return None,
+        };
+        (Command::TextObject(TextObject::from_char(c, around)?), rest)
+    } else if let Some(command) = Command::all()
+        .chain([Command::RepeatInlineFind { reverse: false }])
+        .find(|command| command.str() == name)
+    {
+        (command, rest)
+    } else {
+        let (c, rest) = split_char(rest.strip_prefix(' ')?)?;
+        let find = |direction| InlineFind {
+            direction,
+            c,
+            till: false,
+        };
+        let command = [
+            Command::InlineFind(find(InlineFindDirection::Left)),
+            Command::InlineFind(find(InlineFindDirection::Right)),
+            Command::SelectRegister(c),
+            Command::CreateMark(c),
+            Command::GoToMark {
+                name: c,
+                line: false,
+            },
+            Command::RecordMacro(c),
+            Command::PlayMacro(c),
+        ]
+        .into_iter()
+        .find(|command| command.str() == name)?;
+        (command, rest)
+    };
+
+    let flag = |flag: &str| {
+        rest.strip_prefix(' ')
+            .and_then(|rest| rest.strip_prefix(flag))
+    };
+    let (command, rest) = match command {
+        Command::InlineFind(find) => match flag("till") {
+            Some(rest) => (Command::InlineFind(InlineFind { till: true, ..find }), rest),
+            None => (command, rest),
+        },
+        Command::RepeatInlineFind { .. } => match flag("reverse") {
+            Some(rest) => (Command::RepeatInlineFind { reverse: true }, rest),
+            None => (command, rest),
+        },
+        Command::GoToMark { name, .. } => match flag("line") {
+            Some(rest) => (Command::GoToMark { name, line: true }, rest),
+            None => (command, rest),
+        },
+        command => (command, rest),
+    };
+    Some((command, rest.strip_prefix('>')?))
+}
+
+fn split_char(text: &str) -> Option<(char, &str)> {
+    let c = text.chars().next()?;
+    Some((c, &text[c.len_utf8()..]))
+}
+
+/// The recording and playing of the macros of an editor, which are kept in its [`Register`].
+///
+/// Like the registers of text, `a` to `z` name macros, and recording into `A` to `Z` appends to
+/// the macro of the lowercase register. [`Command::ToggleMacroRecording`] records into
+/// [`DEFAULT_MACRO_REGISTER`]. The digits and the unnamed register `"` can hold macros too.
+#[derive(Clone, Debug, Default)]
+pub struct Macros {
+    recording: Option<(char, Macro)>,
+    last: Option<char>,
+    playing: Vec<char>,
+}
+
+impl Macros {
+    /// Whether `name` is the name of a register that can hold a macro.
+    pub fn is_valid_name(name: char) -> bool {
+        name.is_ascii_alphanumeric() || name == '"'
+    }
+
+    /// Starts recording a macro into the register `name`, dropping the macro being recorded, if
+    /// any. Returns `false` when the name isn't valid.
+    pub fn start_recording(&mut self, name: char) -> bool {
+        if !Self::is_valid_name(name) {
+            return false;
+        }
+        self.recording = Some((name, Macro::default()));
+        true
+    }
+
+    /// Stops recording, and writes the macro to its register. Returns the name of the register.
+    pub fn stop_recording(&mut self, register: &mut Register) -> Option<char> {
+        let (name, recorded) = self.recording.take()?;
+        let data = RegisterData {
+            content: recorded.to_text(),
+            mode: VisualMode::Normal,
+        };
+        register.set(name, data);
+        let lowercase = name.to_ascii_lowercase();
+        self.last = Some(lowercase);
+        Some(lowercase)
+    }
+
+    /// The register that a macro is being recorded into, if any.
+    pub fn recording(&self) -> Option<char> {
+        self.recording.as_ref().map(|(name, _)| *name)
+    }
+
+    /// Records a command that was run, when a macro is being recorded.
+    /// The commands that start a recording aren't recorded.
+    pub fn record_command(&mut self, command: &Command) {
+        if matches!(
+            command,
+            Command::RecordMacro(_) | Command::ToggleMacroRecording
+        ) {
+            return;
+        }
+        if let Some((_, recording)) = self.recording.as_mut() {
+            recording.push_command(command);
+        }
+    }
+
+    /// Records text that was typed, when a macro is being recorded.
+    pub fn record_text(&mut self, text: &str) {
+        if let Some((_, recording)) = self.recording.as_mut() {
+            recording.push_text(text);
+        }
+    }
+
+    /// The register of the macro that was last played or recorded, which
+    /// [`Command::PlayLastMacro`] plays.
+    pub fn last(&self) -> Option<char> {
+        self.last
+    }
+
+    /// Starts playing the macro of the register `name`, returning it.
+    /// Returns `None` when the register is empty, or when its macro is already being played, so
+    /// that a macro that plays itself doesn't recurse forever. Every macro that's returned must be
+    /// followed by a call to [`Macros::stop_playing`].
+    pub fn start_playing(&mut self, name: char, register: &Register) -> Option<Macro> {
+        let name = name.to_ascii_lowercase();
+        if self.playing.contains(&name) {
+            return None;
+        }
+        let recorded = Macro::from_register(register, name)?;
+        self.playing.push(name);
+        self.last = Some(name);
+        Some(recorded)
+    }
+
+    /// Stops playing the macro that was last started.
+    pub fn stop_playing(&mut self) {
+        self.playing.pop();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use floem_editor_core::{
+        command::{EditCommand, MoveCommand},
+        movement::{InlineFind, InlineFindDirection},
+        register::{Register, RegisterData},
+        text_object::TextObject,
+    };
+
+    use crate::views::editor::{command::Command, repeat::EditorInput};
+
+    use super::{Macro, Macros};
+
+    #[test]
+    fn record_and_append() {
+        let mut macros = Macros::default();
+        let mut register = Register::default();
+        let down = Command::Move(MoveCommand::Down);
+        let new_line = Command::Edit(EditCommand::InsertNewLine);
+
+        // Nothing is recorded outside of a recording.
+        macros.record_command(&down);
+        assert_eq!(macros.stop_recording(&mut register), None);
+
+        assert!(macros.start_recording('a'));
+        macros.record_command(&Command::RecordMacro('a'));
+        macros.record_command(&down);
+        macros.record_text("x");
+        macros.record_text("y");
+        assert_eq!(macros.recording(), Some('a'));
+        assert_eq!(macros.stop_recording(&mut register), Some('a'));
+        assert_eq!(macros.recording(), None);
+        // The macro is the text of its register, which can be pasted.
+        assert_eq!(register.get('a').unwrap().content, "<down>xy");
+        assert_eq!(
+            Macro::from_register(&register, 'a'),
+            Some(Macro::new(vec![
+                EditorInput::Command(down.clone()),
+                EditorInput::Text("xy".to_string()),
+            ]))
+        );
+
+        assert!(macros.start_recording('A'));
+        macros.record_command(&new_line);
+        macros.stop_recording(&mut register);
+        assert_eq!(
+            Macro::from_register(&register, 'a'),
+            Some(Macro::new(vec![
+                EditorInput::Command(down),
+                EditorInput::Text("xy".to_string()),
+                EditorInput::Command(new_line),
+            ]))
+        );
+        assert_eq!(macros.last(), Some('a'));
+
+        assert!(!macros.start_recording('%'));
+    }
+
+    #[test]
+    fn macros_as_text() {
+        let recorded = Macro::new(vec![
+            EditorInput::Command(Command::TextObject(TextObject::Pair {
+                open: '(',
+                close: ')',
+                around: true,
+            })),
+            EditorInput::Text("a <b>".to_string()),
+            EditorInput::Command(Command::InlineFind(InlineFind {
+                direction: InlineFindDirection::Left,
+                c: '>',
+                till: true,
+            })),
+            EditorInput::Command(Command::GoToMark {
+                name: 'm',
+                line: true,
+            }),
+            EditorInput::Command(Command::RepeatInlineFind { reverse: true }),
+            EditorInput::Command(Command::SelectRegister(' ')),
+        ]);
+        let text = recorded.to_text();
+        assert_eq!(
+            text,
+            "<text_object a(>a <lt>b><inline_find_left > till><go_to_mark m line>\
+             <repeat_last_inline_find reverse><select_register  >"
+        );
+        assert_eq!(Macro::from_text(&text), recorded);
+
+        // Text that isn't a command is typed, like yanked text played as a macro.
+        assert_eq!(
+            Macro::from_text("<up><nope>"),
+            Macro::new(vec![
+                EditorInput::Command(Command::Move(MoveCommand::Up)),
+                EditorInput::Text("<nope>".to_string()),
+            ])
+        );
+    }
+
+    #[test]
+    fn macros_dont_play_themselves() {
+        let mut macros = Macros::default();
+        let mut register = Register::default();
+        let data = RegisterData {
+            content: "<play_macro q>".to_string(),
+            ..Default::default()
+        };
+        register.set('q', data);
+
+        assert!(macros.start_playing('q', &register).is_some());
+        assert!(macros.start_playing('q', &register).is_none());
+        macros.stop_playing();
+        assert!(macros.start_playing('Q', &register).is_some());
+        macros.stop_playing();
+
+        assert!(macros.start_playing('b', &register).is_none());
+    }
+}
@@ -2,7 +2,7 @@ use core::indent::IndentStyle;
 use std::{
     cell::{Cell, RefCell},
     cmp::Ordering,
-    collections::{hash_map::DefaultHasher, HashMap},
+    collections::{hash_map::DefaultHasher, BTreeSet, HashMap},
     hash::{Hash, Hasher},
     rc::Rc,
     sync::Arc,
@@ -27,7 +27,7 @@ use floem_editor_core::{
     command::MoveCommand,
     cursor::{ColPosition, Cursor, CursorAffinity, CursorMode},
     mode::Mode,
-    movement::Movement,
+    movement::{InlineFind, Movement},
     register::Register,
     selection::Selection,
     soft_tab::{snap_to_soft_tab_line_col, SnapDirection},
@@ -36,15 +36,21 @@ use floem_reactive::{SignalGet, SignalTrack, SignalUpdate, SignalWith, Trigger};
 use lapce_xi_rope::Rope;
 
 pub mod actions;
+pub mod bracket;
 pub mod color;
 pub mod command;
+pub mod file_document;
 pub mod gutter;
 pub mod id;
 pub mod keypress;
 pub mod layout;
 pub mod listener;
+pub mod macros;
 pub mod movement;
 pub mod phantom_text;
+pub mod repeat;
+pub mod sticky_header;
+pub mod symbols;
 pub mod text;
 pub mod text_document;
 pub mod view;
@@ -54,10 +60,16 @@ pub use floem_editor_core as core;
 use peniko::Brush;
 
 use self::{
+    bracket::BracketDepths,
     command::Command,
+    gutter::{GutterColumn, LineNumbers},
     id::EditorId,
     layout::TextLayoutLine,
+    macros::Macros,
     phantom_text::PhantomTextLine,
+    repeat::ChangeRecorder,
+    sticky_header::{IndentScopeProvider, ScopeProvider, StickyHeaderCache},
+    symbols::{DocumentSymbols, KeywordSymbols, SymbolCache},
     text::{Document, Preedit, PreeditData, RenderWhitespace, Styling, WrapMethod},
     view::{LineInfo, ScreenLines, ScreenLinesBase},
     visual_line::{
@@ -75,8 +87,19 @@ impl StylePropValue for WrapMethod {
 prop!(pub CursorSurroundingLines: usize {} = 1);
 prop!(pub ScrollBeyondLastLine: bool {} = false);
 prop!(pub ShowIndentGuide: bool {} = false);
+prop!(pub ShowStickyHeaders: bool {} = false);
+prop!(pub ShowMatchingBracket: bool {} = false);
+prop!(pub MatchingBracketColor: Color {} = Color::DIM_GRAY);
+prop!(pub BracketColorization: bool {} = false);
+prop!(pub BracketColors: Vec<Color> {} = vec![Color::GOLD, Color::ORCHID, Color::DEEP_SKY_BLUE]);
 prop!(pub Modal: bool {} = false);
 prop!(pub ModalRelativeLine: bool {} = false);
+prop!(pub ModalLineNumbers: LineNumbers {} = LineNumbers::Absolute);
+impl StylePropValue for LineNumbers {
+    fn debug_view(&self) -> Option<Box<dyn View>> {
+        Some(text(self).into_any())
+    }
+}
 prop!(pub SmartTab: bool {} = false);
 prop!(pub PhantomColor: Color {} = Color::DIM_GRAY);
 prop!(pub PlaceholderColor: Color {} = Color::DIM_GRAY);
@@ -110,9 +133,18 @@ prop_extractor! {
         pub placeholder_color: PlaceholderColor,
         pub preedit_underline_color: PreeditUnderlineColor,
         pub show_indent_guide: ShowIndentGuide,
+        pub show_sticky_headers: ShowStickyHeaders,
+        pub sticky_header_background: StickyHeaderBackground,
+        pub show_matching_bracket: ShowMatchingBracket,
+        pub matching_bracket_color: MatchingBracketColor,
+        // Whether brackets are colored by their depth
+        pub bracket_colorization: BracketColorization,
+        pub bracket_colors: BracketColors,
         pub modal: Modal,
         // Whether line numbers are relative in modal mode
         pub modal_relative_line: ModalRelativeLine,
+        // How line numbers are shown outside of insert mode in modal mode
+        pub modal_line_numbers: ModalLineNumbers,
         // Whether to insert the indent that is detected for the file when a tab character
         // is inputted.
         pub smart_tab: SmartTab,
@@ -183,6 +215,33 @@ pub struct Editor {
     pub cursor_info: CursorInfo,
 
     pub last_movement: RwSignal<Movement>,
+    /// The last inline find, which is repeated by [`Command::RepeatInlineFind`].
+    pub last_inline_find: RwSignal<Option<InlineFind>>,
+    /// The changes of the modal mode, the last of which is repeated by
+    /// [`Command::RepeatLastChange`].
+    pub changes: RwSignal<ChangeRecorder>,
+    /// The recording and playing of keyboard macros, with [`Command::RecordMacro`] and
+    /// [`Command::PlayMacro`]. The macros themselves are kept in the [`register`](Self::register).
+    pub macros: RwSignal<Macros>,
+
+    /// The extra columns of the gutter, see [`Editor::add_gutter_column`].
+    pub gutter_columns: RwSignal<Vec<GutterColumn>>,
+    /// The buffer lines with a breakpoint, toggled by [`Command::ToggleBreakpoint`] and shown by
+    /// a [`GutterColumn::breakpoints`] column.
+    pub breakpoints: RwSignal<BTreeSet<usize>>,
+    /// Provides the scopes whose opening lines the sticky headers pin, from the indentation by
+    /// default.
+    pub scope_provider: RwSignal<Rc<dyn ScopeProvider>>,
+    /// The lines pinned by the sticky headers, found again when the document or the top line
+    /// changes.
+    pub(crate) sticky_headers: Rc<RefCell<StickyHeaderCache>>,
+    /// The depths of the brackets, for coloring them.
+    pub(crate) bracket_depths: Rc<RefCell<BracketDepths>>,
+    /// Provides the symbols of the document for its outline, from keywords and the indentation
+    /// by default.
+    pub symbol_provider: RwSignal<Rc<dyn DocumentSymbols>>,
+    /// The symbols of the document, found again when it changes.
+    pub(crate) symbols: Rc<RefCell<SymbolCache>>,
 
     /// Whether ime input is allowed.  
     /// Should not be set manually outside of the specific handling for ime.
@@ -253,6 +312,7 @@ impl Editor {
         let cursor = Cursor::new(cursor_mode, None, None);
         let cursor = cx.create_rw_signal(cursor);
 
+        doc.add_editor_cursor(id, cursor);
         let doc = cx.create_rw_signal(doc);
         let style = cx.create_rw_signal(style);
 
@@ -288,11 +348,22 @@ impl Editor {
             register: cx.create_rw_signal(Register::default()),
             cursor_info: CursorInfo::new(cx),
             last_movement: cx.create_rw_signal(Movement::Left),
+            last_inline_find: cx.create_rw_signal(None),
+            changes: cx.create_rw_signal(ChangeRecorder::default()),
+            macros: cx.create_rw_signal(Macros::default()),
+            gutter_columns: cx.create_rw_signal(Vec::new()),
+            breakpoints: cx.create_rw_signal(BTreeSet::new()),
+            scope_provider: cx.create_rw_signal(Rc::new(IndentScopeProvider)),
+            sticky_headers: Rc::new(RefCell::new(StickyHeaderCache::default())),
+            bracket_depths: Rc::new(RefCell::new(BracketDepths::default())),
+            symbol_provider: cx.create_rw_signal(Rc::new(KeywordSymbols::default())),
+            symbols: Rc::new(RefCell::new(SymbolCache::default())),
             ime_allowed: cx.create_rw_signal(false),
             es: editor_style,
             floem_style_id: cx.create_rw_signal(0),
         };
 
+        ed.doc().add_editor_breakpoints(id, ed.breakpoints);
         create_view_effects(ed.effects_cx.get(), &ed);
 
         ed
@@ -342,6 +413,8 @@ impl Editor {
                 doc: self.doc.read_only(),
             });
             self.lines.clear(0, None);
+            doc.add_editor_cursor(self.id(), self.cursor);
+            doc.add_editor_breakpoints(self.id(), self.breakpoints);
             self.doc.set(doc);
             if let Some(styling) = styling {
                 self.style.set(styling);
@@ -406,6 +479,21 @@ impl Editor {
             editor.register.set(self.register.get_untracked());
             editor.cursor_info = self.cursor_info.clone();
             editor.last_movement.set(self.last_movement.get_untracked());
+            editor
+                .last_inline_find
+                .set(self.last_inline_find.get_untracked());
+            editor.changes.set(self.changes.get_untracked());
+            editor.macros.set(self.macros.get_untracked());
+            editor
+                .gutter_columns
+                .set(self.gutter_columns.get_untracked());
+            editor.breakpoints.set(self.breakpoints.get_untracked());
+            editor
+                .scope_provider
+                .set(self.scope_provider.get_untracked());
+            editor
+                .symbol_provider
+                .set(self.symbol_provider.get_untracked());
             // ?
             // editor.ime_allowed.set(self.ime_allowed.get_untracked());
         });
@@ -415,6 +503,11 @@ impl Editor {
         editor
     }
 
+    /// Adds a column to the gutter, to the right of the columns that were added before it.
+    pub fn add_gutter_column(&self, column: GutterColumn) {
+        self.gutter_columns.update(|columns| columns.push(column));
+    }
+
     /// Get the styling untracked
     pub fn style(&self) -> Rc<dyn Styling> {
         self.style.get_untracked()
@@ -472,7 +565,13 @@ impl Editor {
     }
 
     pub fn receive_char(&self, c: &str) {
-        self.doc().receive_char(self, c)
+        let is_modal_insert = self.es.with_untracked(|es| es.modal())
+            && self.cursor.with_untracked(|cursor| cursor.is_insert());
+        self.doc().receive_char(self, c);
+        if is_modal_insert {
+            self.changes.update(|changes| changes.record_text(c));
+        }
+        self.macros.update(|macros| macros.record_text(c));
     }
 
     fn compute_screen_lines(&self, base: RwSignal<ScreenLinesBase>) -> ScreenLines {
@@ -687,7 +786,25 @@ impl Editor {
 
     // === Information ===
 
+    /// Whether the document is a large file, see [`Document::is_large_file`].
+    pub fn is_large_file(&self) -> bool {
+        self.doc().is_large_file()
+    }
+
+    /// How the lines are wrapped, which is never for a large file.
+    pub fn wrap_method(&self) -> WrapMethod {
+        if self.is_large_file() {
+            WrapMethod::None
+        } else {
+            self.es.with(|s| s.wrap_method())
+        }
+    }
+
+    /// The phantom text of the line, of which a large file has none.
     pub fn phantom_text(&self, line: usize) -> PhantomTextLine {
+        if self.is_large_file() {
+            return PhantomTextLine::default();
+        }
         self.doc()
             .phantom_text(self.id(), &self.es.get_untracked(), line)
     }
@@ -1254,7 +1371,7 @@ impl TextLayoutProvider for Editor {
             line_content_original.to_string()
         };
         // Combine the phantom text with the line content
-        let phantom_text = doc.phantom_text(edid, &self.es.get_untracked(), line);
+        let phantom_text = self.phantom_text(line);
         let line_content = phantom_text.combine_with_text(&line_content);
 
         let family = style.font_family(edid, line);
@@ -1268,6 +1385,11 @@ impl TextLayoutProvider for Editor {
         self.es.with_untracked(|es| {
             style.apply_attr_styles(edid, es, line, attrs, &mut attrs_list);
         });
+        if !doc.is_large_file() && self.es.with_untracked(|es| es.bracket_colorization()) {
+            bracket::apply_bracket_colors(self, line, attrs, &mut attrs_list, |col| {
+                phantom_text.col_at(col)
+            });
+        }
 
         // Apply phantom text specific styling
         for (offset, size, col, phantom) in phantom_text.offset_size_iter() {
@@ -1298,7 +1420,7 @@ impl TextLayoutProvider for Editor {
         text_layout.set_text(&line_content, attrs_list);
 
         // dbg!(self.editor_style.with(|s| s.wrap_method()));
-        match self.es.with(|s| s.wrap_method()) {
+        match self.wrap_method() {
             WrapMethod::None => {}
             WrapMethod::EditorWidth => {
                 let width = self.viewport.get_untracked().width();
@@ -1354,11 +1476,17 @@ impl TextLayoutProvider for Editor {
     }
 
     fn before_phantom_col(&self, line: usize, col: usize) -> usize {
+        if self.is_large_file() {
+            return col;
+        }
         self.doc()
             .before_phantom_col(self.id(), &self.es.get_untracked(), line, col)
     }
 
     fn has_multiline_phantom(&self) -> bool {
+        if self.is_large_file() {
+            return false;
+        }
         self.doc()
             .has_multiline_phantom(self.id(), &self.es.get_untracked())
     }
@@ -1464,7 +1592,7 @@ fn create_view_effects(cx: Scope, ed: &Editor) {
 
         let viewport = ed.viewport.get();
 
-        let wrap = match ed.es.with(|s| s.wrap_method()) {
+        let wrap = match ed.wrap_method() {
             WrapMethod::None => ResolvedWrap::None,
             WrapMethod::EditorWidth => {
                 ResolvedWrap::Width((viewport.width() as f32).max(MIN_WRAPPED_WIDTH))
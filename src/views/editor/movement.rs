@@ -5,10 +5,11 @@ use floem_editor_core::{
     command::MultiSelectionCommand,
     cursor::{ColPosition, Cursor, CursorAffinity, CursorMode},
     mode::{Mode, MotionMode, VisualMode},
-    movement::{LinePosition, Movement},
+    movement::{InlineFindDirection, LinePosition, Movement},
     register::Register,
     selection::{SelRegion, Selection},
     soft_tab::{snap_to_soft_tab, SnapDirection},
+    text_object::TextObject,
 };
 
 use super::{
@@ -190,6 +191,13 @@ pub fn move_offset(
         Movement::ParagraphBackward => {
             let new_offset = view.rope_text().move_n_paragraphs_backward(offset, count);
 
+            (new_offset, None)
+        }
+        Movement::InlineFind { find, repeat } => {
+            let new_offset = find
+                .offset(&view.rope_text(), offset, count, *repeat)
+                .unwrap_or(offset);
+
             (new_offset, None)
         }
     };
@@ -601,6 +609,16 @@ pub fn move_cursor(
                     Mode::Insert,
                 );
                 let range = match movement {
+                    // Like in vim, an operator does nothing when the character isn't found.
+                    Movement::InlineFind { .. } if new_offset == offset => {
+                        cursor.motion_mode = None;
+                        return;
+                    }
+                    Movement::InlineFind { find, .. }
+                        if find.direction == InlineFindDirection::Right =>
+                    {
+                        offset..moved_new_offset
+                    }
                     Movement::EndOfLine | Movement::WordEndForward => offset..moved_new_offset,
                     Movement::MatchPairs => {
                         if new_offset > offset {
@@ -657,6 +675,64 @@ pub fn move_cursor(
     }
 }
 
+/// Apply the pending motion mode to the text object at the cursor, or select the text object in
+/// visual mode.
+/// Nothing happens when there's no such text object at the cursor.
+pub fn select_text_object(
+    ed: &Editor,
+    action: &dyn CommonAction,
+    cursor: &mut Cursor,
+    object: &TextObject,
+    register: &mut Register,
+) {
+    let rope_text = ed.rope_text();
+    let Some(range) = object.range(&rope_text, cursor.offset()) else {
+        cursor.motion_mode = None;
+        return;
+    };
+
+    match cursor.mode {
+        CursorMode::Normal(_) => {
+            let Some(motion_mode) = cursor.motion_mode.take() else {
+                return;
+            };
+            // Linewise motions are given an offset on their last line, rather than the start of
+            // the line after it.
+            let range = if object.is_linewise() {
+                range.start..range.end.saturating_sub(1).max(range.start)
+            } else {
+                range
+            };
+            action.exec_motion_mode(
+                ed,
+                cursor,
+                motion_mode,
+                range,
+                object.is_linewise(),
+                register,
+            );
+        }
+        CursorMode::Visual { mode, .. } => {
+            // The end of a visual selection is the offset of its last character.
+            let end = rope_text.prev_grapheme_offset(range.end, 1, range.start);
+            let mode = if object.is_linewise() {
+                VisualMode::Linewise
+            } else {
+                mode
+            };
+            cursor.mode = CursorMode::Visual {
+                start: range.start,
+                end,
+                mode,
+            };
+            cursor.horiz = None;
+        }
+        CursorMode::Insert(_) => {
+            cursor.set_insert(Selection::region(range.start, range.end));
+        }
+    }
+}
+
 pub fn do_multi_selection(view: &Editor, cursor: &mut Cursor, cmd: &MultiSelectionCommand) {
     use MultiSelectionCommand::*;
     let rope_text = view.rope_text();
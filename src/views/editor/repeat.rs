@@ -0,0 +1,165 @@
+//! Repeating the last change of the modal mode, like `.` in vim.
+
+use floem_editor_core::{
+    command::{EditCommand, MotionModeCommand},
+    mode::Mode,
+};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use super::command::Command;
+
+/// An input to the editor that's part of a recorded change or [macro](super::macros::Macro).
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum EditorInput {
+    Command(Command),
+    /// Text typed in insert mode.
+    Text(String),
+}
+
+/// Records the changes made in the modal mode, so that the last one can be repeated with
+/// [`Command::RepeatLastChange`].
+///
+/// A change starts with a command that edits the text, or with an operator or a command that
+/// enters insert mode. It goes on while the operator waits for its motion, and while text is
+/// typed in insert mode, up to the return to normal mode.
+#[derive(Clone, Debug, Default)]
+pub struct ChangeRecorder {
+    current: Option<Vec<EditorInput>>,
+    last: Vec<EditorInput>,
+}
+
+impl ChangeRecorder {
+    /// Records a command that was run, which went from the mode `before` to the mode `after`.
+    /// `motion_pending` is set when an operator is waiting for its motion after the command.
+    pub fn record_command(
+        &mut self,
+        command: &Command,
+        before: Mode,
+        after: Mode,
+        motion_pending: bool,
+    ) {
+        if self.current.is_none() {
+            if before == Mode::Insert || !starts_change(command, after) {
+                return;
+            }
+            self.current = Some(Vec::new());
+        }
+
+        if let Some(current) = self.current.as_mut() {
+            current.push(EditorInput::Command(command.clone()));
+        }
+        if !motion_pending && after != Mode::Insert {
+            self.last = self.current.take().unwrap_or_default();
+        }
+    }
+
+    /// Records text typed in insert mode, when it's part of a change.
+    pub fn record_text(&mut self, text: &str) {
+        if let Some(current) = self.current.as_mut() {
+            match current.last_mut() {
+                Some(EditorInput::Text(typed)) => typed.push_str(text),
+                _ => current.push(EditorInput::Text(text.to_string())),
+            }
+        }
+    }
+
+    /// The inputs of the last change, which is empty if there's none yet.
+    pub fn last_change(&self) -> &[EditorInput] {
+        &self.last
+    }
+
+    /// Whether a change is being recorded, like while in insert mode.
+    pub fn is_recording(&self) -> bool {
+        self.current.is_some()
+    }
+}
+
+fn starts_change(command: &Command, after: Mode) -> bool {
+    match command {
+        Command::MotionMode(MotionModeCommand::MotionModeYank) => false,
+        Command::MotionMode(_) => true,
+        Command::Edit(EditCommand::Undo | EditCommand::Redo) => false,
+        Command::Edit(cmd) => !cmd.not_changing_buffer() || after == Mode::Insert,
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use floem_editor_core::{
+        command::{EditCommand, MotionModeCommand, MoveCommand},
+        mode::Mode,
+        text_object::TextObject,
+    };
+
+    use crate::views::editor::command::Command;
+
+    use super::{ChangeRecorder, EditorInput};
+
+    #[test]
+    fn records_operators_and_insertions() {
+        let mut changes = ChangeRecorder::default();
+        let delete = Command::MotionMode(MotionModeCommand::MotionModeDelete);
+        let word = Command::TextObject(TextObject::Word { around: true });
+
+        // Motions aren't changes.
+        changes.record_command(
+            &Command::Move(MoveCommand::Right),
+            Mode::Normal,
+            Mode::Normal,
+            false,
+        );
+        assert!(changes.last_change().is_empty());
+
+        changes.record_command(&delete, Mode::Normal, Mode::Normal, true);
+        assert!(changes.is_recording());
+        changes.record_command(&word, Mode::Normal, Mode::Normal, false);
+        assert_eq!(
+            changes.last_change(),
+            [
+                EditorInput::Command(delete.clone()),
+                EditorInput::Command(word.clone())
+            ]
+        );
+
+        // Yanking doesn't change the text, so it doesn't replace the last change.
+        let yank = Command::MotionMode(MotionModeCommand::MotionModeYank);
+        changes.record_command(&yank, Mode::Normal, Mode::Normal, true);
+        assert!(!changes.is_recording());
+        changes.record_command(&word, Mode::Normal, Mode::Normal, false);
+        assert_eq!(
+            changes.last_change(),
+            [
+                EditorInput::Command(delete),
+                EditorInput::Command(word.clone())
+            ]
+        );
+
+        let insert = Command::Edit(EditCommand::InsertMode);
+        let new_line = Command::Edit(EditCommand::InsertNewLine);
+        let normal = Command::Edit(EditCommand::NormalMode);
+        changes.record_command(&insert, Mode::Normal, Mode::Insert, false);
+        changes.record_text("a");
+        changes.record_text("b");
+        changes.record_command(&new_line, Mode::Insert, Mode::Insert, false);
+        changes.record_text("c");
+        changes.record_command(&normal, Mode::Insert, Mode::Normal, false);
+        assert!(!changes.is_recording());
+        assert_eq!(
+            changes.last_change(),
+            [
+                EditorInput::Command(insert),
+                EditorInput::Text("ab".to_string()),
+                EditorInput::Command(new_line),
+                EditorInput::Text("c".to_string()),
+                EditorInput::Command(normal),
+            ]
+        );
+
+        // Text typed outside of a change isn't recorded.
+        changes.record_text("d");
+        assert!(!changes.is_recording());
+    }
+}
@@ -0,0 +1,206 @@
+//! Sticky headers, which pin the lines that open the scopes containing the first visible line to
+//! the top of the editor, so that the context isn't lost when scrolling into a long scope.
+
+use std::rc::Rc;
+
+use floem_editor_core::buffer::rope_text::RopeText;
+use floem_reactive::{SignalGet, SignalWith};
+use peniko::kurbo::{Point, Rect};
+
+use crate::context::PaintCx;
+
+use super::{view::ScreenLines, Editor};
+
+/// The most lines that are pinned at once.
+pub const MAX_STICKY_HEADER_LINES: usize = 5;
+
+/// The number of columns that a tab counts as when comparing indentation.
+const TAB_WIDTH: usize = 4;
+
+/// Provides the scopes of a document for the sticky headers.
+pub trait ScopeProvider {
+    /// The buffer lines that open the scopes containing `line`, from the outermost scope in.
+    /// They all come before `line`.
+    fn scope_lines(&self, ed: &Editor, line: usize) -> Vec<usize>;
+}
+
+/// Provides scopes from the indentation: a line opens a scope containing the lines after it that
+/// are indented more than it, like the body of a function.
+///
+/// A large file has no scopes, since finding them goes back through the lines before.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct IndentScopeProvider;
+impl ScopeProvider for IndentScopeProvider {
+    fn scope_lines(&self, ed: &Editor, line: usize) -> Vec<usize> {
+        if ed.is_large_file() {
+            return Vec::new();
+        }
+        indent_scope_lines(&ed.rope_text(), line)
+    }
+}
+
+/// The pinned lines found last, which are kept until the document, its revision, the scope
+/// provider or the top line changes.
+#[derive(Default)]
+pub(crate) struct StickyHeaderCache {
+    /// The address and revision of the document, the address of the scope provider and the top
+    /// line.
+    key: Option<(usize, u64, usize, usize)>,
+    lines: Vec<usize>,
+}
+
+fn indent_width(text: &impl RopeText, line: usize) -> usize {
+    text.indent_on_line(line)
+        .chars()
+        .map(|c| if c == '\t' { TAB_WIDTH } else { 1 })
+        .sum()
+}
+
+/// The lines that open the indentation scopes containing `line`, from the outermost in.
+fn indent_scope_lines(text: &impl RopeText, line: usize) -> Vec<usize> {
+    let last_line = text.last_line();
+    if line > last_line {
+        return Vec::new();
+    }
+
+    // A blank line is in the scope of the next line that isn't blank.
+    let Some(inner_line) = (line..=last_line).find(|line| !text.is_line_whitespace(*line)) else {
+        return Vec::new();
+    };
+    let mut indent = indent_width(text, inner_line);
+
+    let mut scopes = Vec::new();
+    for line in (0..line).rev() {
+        if indent == 0 {
+            break;
+        }
+        if text.is_line_whitespace(line) {
+            continue;
+        }
+        let line_indent = indent_width(text, line);
+        if line_indent < indent {
+            scopes.push(line);
+            indent = line_indent;
+        }
+    }
+    scopes.reverse();
+    scopes
+}
+
+/// The buffer lines pinned at the top of the editor, from the top down.
+///
+/// The pinned lines cover the lines at the top of the viewport, so they are the scopes of the
+/// first line below them.
+pub fn sticky_header_lines(ed: &Editor, screen_lines: &ScreenLines) -> Vec<usize> {
+    if !ed.es.with_untracked(|es| es.show_sticky_headers()) {
+        return Vec::new();
+    }
+
+    let viewport = ed.viewport.get_untracked();
+    let Some(top) = screen_lines
+        .iter_lines_y()
+        .find(|(line, y)| y + f64::from(ed.line_height(*line)) > viewport.y0)
+        .map(|(line, _)| line)
+    else {
+        return Vec::new();
+    };
+
+    let doc = ed.doc();
+    let provider = ed.scope_provider.get_untracked();
+    let key = (
+        Rc::as_ptr(&doc) as *const () as usize,
+        doc.cache_rev().get_untracked(),
+        Rc::as_ptr(&provider) as *const () as usize,
+        top,
+    );
+    if ed.sticky_headers.borrow().key == Some(key) {
+        return ed.sticky_headers.borrow().lines.clone();
+    }
+
+    let mut lines = Vec::new();
+    // Pinning lines covers more lines, whose scopes may differ, so this settles on the scopes of
+    // the line below the pinned ones.
+    for _ in 0..MAX_STICKY_HEADER_LINES {
+        let mut scopes = provider.scope_lines(ed, top + lines.len());
+        scopes.truncate(MAX_STICKY_HEADER_LINES);
+        if scopes == lines {
+            break;
+        }
+        lines = scopes;
+    }
+    *ed.sticky_headers.borrow_mut() = StickyHeaderCache {
+        key: Some(key),
+        lines: lines.clone(),
+    };
+    lines
+}
+
+/// The pinned line at the point of the editor's content, if any.
+pub fn sticky_header_line_at(ed: &Editor, point: Point) -> Option<usize> {
+    let viewport = ed.viewport.get_untracked();
+    let lines = ed
+        .screen_lines
+        .with_untracked(|screen_lines| sticky_header_lines(ed, screen_lines));
+
+    let mut y = viewport.y0;
+    lines.into_iter().find(|line| {
+        let line_height = f64::from(ed.line_height(*line));
+        let found = point.y >= y && point.y < y + line_height;
+        y += line_height;
+        found
+    })
+}
+
+/// Paints the pinned lines over the top of the viewport, reusing the text layouts of the lines.
+pub(crate) fn paint_sticky_headers(
+    cx: &mut PaintCx,
+    ed: &Editor,
+    viewport: Rect,
+    screen_lines: &ScreenLines,
+) {
+    let lines = sticky_header_lines(ed, screen_lines);
+    if lines.is_empty() {
+        return;
+    }
+
+    let background = ed.es.with_untracked(|es| es.sticky_header_background());
+    let mut y = viewport.y0;
+    for line in lines {
+        let line_height = f64::from(ed.line_height(line));
+        let rect = Rect::new(viewport.x0, y, viewport.x1, y + line_height);
+        if let Some(background) = background {
+            cx.fill(&rect, background, 0.0);
+        }
+
+        // Only the first visual line of a wrapped line fits in the header.
+        let text_layout = ed.text_layout(line);
+        cx.save();
+        cx.clip(&rect);
+        cx.draw_text(&text_layout.text, Point::new(0.0, y));
+        cx.restore();
+        y += line_height;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use floem_editor_core::buffer::rope_text::RopeTextVal;
+    use lapce_xi_rope::Rope;
+
+    use super::indent_scope_lines;
+
+    #[test]
+    fn indent_scopes() {
+        let text = RopeTextVal::new(Rope::from(
+            "fn a() {\n    if b {\n        c();\n\n        d();\n    }\n}\nfn e() {}\n",
+        ));
+
+        assert_eq!(indent_scope_lines(&text, 0), Vec::<usize>::new());
+        assert_eq!(indent_scope_lines(&text, 1), [0]);
+        assert_eq!(indent_scope_lines(&text, 2), [0, 1]);
+        // The blank line is in the scope of the line after it.
+        assert_eq!(indent_scope_lines(&text, 3), [0, 1]);
+        assert_eq!(indent_scope_lines(&text, 5), [0]);
+        assert_eq!(indent_scope_lines(&text, 7), Vec::<usize>::new());
+    }
+}
@@ -0,0 +1,447 @@
+//! The symbols of a document, like its functions and types, which make up its outline and which
+//! [`Command::SelectNextSyntaxItem`](super::command::Command::SelectNextSyntaxItem) and
+//! [`Command::SelectPreviousSyntaxItem`](super::command::Command::SelectPreviousSyntaxItem) move
+//! between.
+
+use std::{ops::Range, rc::Rc};
+
+use floem_editor_core::{buffer::rope_text::RopeText, cursor::CursorMode, selection::Selection};
+use floem_reactive::{
+    create_effect, create_memo, SignalGet, SignalTrack, SignalUpdate, SignalWith,
+};
+
+use crate::{
+    event::EventListener,
+    style_class,
+    view::IntoView,
+    views::{dyn_stack, label, scroll, Decorators},
+};
+
+use super::Editor;
+
+style_class!(pub OutlineClass);
+style_class!(pub OutlineItemClass);
+style_class!(pub OutlineCurrentItemClass);
+
+/// The number of columns that a tab counts as when comparing indentation.
+const TAB_WIDTH: usize = 4;
+
+/// The padding that each level of nesting adds before an item of the outline.
+const OUTLINE_INDENT: f64 = 12.0;
+
+/// The kind of a [`DocumentSymbol`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SymbolKind {
+    Module,
+    Function,
+    Struct,
+    Enum,
+    Trait,
+    Impl,
+    Class,
+    Interface,
+    Other,
+}
+
+/// A symbol of a document, like a function, with the symbols declared inside of it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DocumentSymbol {
+    pub name: String,
+    pub kind: SymbolKind,
+    /// The offsets of the whole symbol, like a function with its body.
+    pub range: Range<usize>,
+    /// The offsets of the name of the symbol, which is selected when going to the symbol.
+    pub name_range: Range<usize>,
+    pub children: Vec<DocumentSymbol>,
+}
+
+/// The symbols and the symbols inside of them, each with its depth, in the order of the text.
+pub fn flatten_symbols(symbols: &[DocumentSymbol]) -> Vec<(usize, &DocumentSymbol)> {
+    fn flatten<'a>(
+        symbols: &'a [DocumentSymbol],
+        depth: usize,
+        flat: &mut Vec<(usize, &'a DocumentSymbol)>,
+    ) {
+        for symbol in symbols {
+            flat.push((depth, symbol));
+            flatten(&symbol.children, depth + 1, flat);
+        }
+    }
+
+    let mut flat = Vec::new();
+    flatten(symbols, 0, &mut flat);
+    flat
+}
+
+/// Provides the symbols of a document for its outline.
+///
+/// The symbols aren't moved along with edits, they are found again whenever the document
+/// changes.
+pub trait DocumentSymbols {
+    /// The outermost symbols of the document, in the order of the text.
+    fn symbols(&self, ed: &Editor) -> Vec<DocumentSymbol>;
+}
+
+/// Provides symbols from the lines that declare them with a keyword like `fn` or `class`, after
+/// modifiers like `pub`. A symbol contains the lines after it that are indented more than it,
+/// and the line that closes it at its own indentation, like a `}`.
+#[derive(Debug, Clone)]
+pub struct KeywordSymbols {
+    pub keywords: Vec<(String, SymbolKind)>,
+    pub modifiers: Vec<String>,
+}
+
+impl Default for KeywordSymbols {
+    fn default() -> Self {
+        let keywords = [
+            ("mod", SymbolKind::Module),
+            ("fn", SymbolKind::Function),
+            ("struct", SymbolKind::Struct),
+            ("enum", SymbolKind::Enum),
+            ("trait", SymbolKind::Trait),
+            ("impl", SymbolKind::Impl),
+            ("class", SymbolKind::Class),
+            ("interface", SymbolKind::Interface),
+            ("def", SymbolKind::Function),
+            ("function", SymbolKind::Function),
+        ];
+        let modifiers = [
+            "pub",
+            "pub(crate)",
+            "pub(super)",
+            "async",
+            "unsafe",
+            "export",
+            "default",
+            "public",
+            "private",
+            "protected",
+            "static",
+            "abstract",
+        ];
+        Self {
+            keywords: keywords
+                .into_iter()
+                .map(|(keyword, kind)| (keyword.to_string(), kind))
+                .collect(),
+            modifiers: modifiers.into_iter().map(String::from).collect(),
+        }
+    }
+}
+
+impl DocumentSymbols for KeywordSymbols {
+    fn symbols(&self, ed: &Editor) -> Vec<DocumentSymbol> {
+        self.text_symbols(&ed.rope_text())
+    }
+}
+
+impl KeywordSymbols {
+    /// The outermost symbols of the text.
+    pub fn text_symbols(&self, text: &impl RopeText) -> Vec<DocumentSymbol> {
+        let mut symbols = Vec::new();
+        // The symbols whose scopes contain the line, with their indentation and their last line
+        // so far, from the outermost in.
+        let mut open: Vec<(usize, usize, DocumentSymbol)> = Vec::new();
+        let close = |open: &mut Vec<(usize, usize, DocumentSymbol)>,
+                     symbols: &mut Vec<DocumentSymbol>| {
+            let (_, end_line, mut symbol) = open.pop().unwrap();
+            symbol.range.end = line_end(text, end_line);
+            match open.last_mut() {
+                Some((_, parent_end_line, parent)) => {
+                    *parent_end_line = (*parent_end_line).max(end_line);
+                    parent.children.push(symbol);
+                }
+                None => symbols.push(symbol),
+            }
+        };
+
+        for line in 0..=text.last_line() {
+            if text.is_line_whitespace(line) {
+                continue;
+            }
+            let content = text.line_content(line);
+            let indent = indent_width(&content);
+
+            // A line that isn't indented more than a symbol ends its scope. The line that closes
+            // the symbol at its indentation, like a `}`, is a part of it.
+            while let Some((open_indent, end_line, _)) = open.last_mut() {
+                if indent > *open_indent {
+                    *end_line = line;
+                    break;
+                }
+                if indent == *open_indent && content.trim_start().starts_with(['}', ')', ']']) {
+                    *end_line = line;
+                    close(&mut open, &mut symbols);
+                    break;
+                }
+                close(&mut open, &mut symbols);
+            }
+
+            let Some((kind, name_col, name)) = self.declaration(&content) else {
+                continue;
+            };
+            let line_start = text.offset_of_line(line);
+            let indent_len = content.len() - content.trim_start().len();
+            let symbol = DocumentSymbol {
+                kind,
+                // The end is set when the scope of the symbol ends.
+                range: line_start + indent_len..line_start + indent_len,
+                name_range: line_start + name_col..line_start + name_col + name.len(),
+                name,
+                children: Vec::new(),
+            };
+            open.push((indent, line, symbol));
+        }
+        while !open.is_empty() {
+            close(&mut open, &mut symbols);
+        }
+
+        symbols
+    }
+
+    /// The kind, the column and the name of the symbol that the line declares, if any.
+    fn declaration(&self, content: &str) -> Option<(SymbolKind, usize, String)> {
+        let mut rest = content.trim_start();
+        while let Some(modifier) = self
+            .modifiers
+            .iter()
+            .find(|modifier| starts_with_word(rest, modifier))
+        {
+            rest = rest[modifier.len()..].trim_start();
+        }
+
+        let (keyword, kind) = self
+            .keywords
+            .iter()
+            .find(|(keyword, _)| starts_with_word(rest, keyword))?;
+        let mut rest = rest[keyword.len()..].trim_start();
+
+        let name = if *kind == SymbolKind::Impl {
+            // The generic parameters of the impl are skipped, so that it's named by its type.
+            if rest.starts_with('<') {
+                let mut depth = 0;
+                let end = rest.find(|c: char| {
+                    match c {
+                        '<' => depth += 1,
+                        '>' => depth -= 1,
+                        _ => {}
+                    }
+                    depth == 0
+                })?;
+                rest = rest[end + 1..].trim_start();
+            }
+            let end = rest
+                .find(" where")
+                .or_else(|| rest.find('{'))
+                .unwrap_or(rest.len());
+            rest[..end].trim_end()
+        } else {
+            let end = rest
+                .find(|c: char| !(c.is_alphanumeric() || c == '_'))
+                .unwrap_or(rest.len());
+            &rest[..end]
+        };
+        if name.is_empty() {
+            return None;
+        }
+
+        Some((*kind, content.len() - rest.len(), name.to_string()))
+    }
+}
+
+/// Whether `text` starts with `word`, followed by something that can't continue it.
+fn starts_with_word(text: &str, word: &str) -> bool {
+    text.strip_prefix(word).is_some_and(|rest| {
+        !rest.starts_with(|c: char| c.is_alphanumeric() || c == '_' || c == '(')
+    })
+}
+
+fn indent_width(content: &str) -> usize {
+    content
+        .chars()
+        .take_while(|c| *c == ' ' || *c == '\t')
+        .map(|c| if c == '\t' { TAB_WIDTH } else { 1 })
+        .sum()
+}
+
+/// The offset of the end of the line, before its line ending.
+fn line_end(text: &impl RopeText, line: usize) -> usize {
+    let content = text.line_content(line);
+    text.offset_of_line(line) + content.trim_end_matches(['\n', '\r']).len()
+}
+
+/// The symbols of an editor's document, found again when the document or the provider changes.
+#[derive(Debug, Default)]
+pub(crate) struct SymbolCache {
+    /// The address and revision of the document, and the address of the provider that found the
+    /// symbols.
+    key: Option<(usize, u64, usize)>,
+    symbols: Rc<Vec<DocumentSymbol>>,
+}
+
+/// The outermost symbols of the editor's document, from the editor's
+/// [`symbol_provider`](Editor::symbol_provider). A large file has none, since finding them goes
+/// through the whole document.
+pub fn document_symbols(ed: &Editor) -> Rc<Vec<DocumentSymbol>> {
+    if ed.is_large_file() {
+        return Rc::default();
+    }
+
+    let doc = ed.doc();
+    let provider = ed.symbol_provider.get_untracked();
+    let key = (
+        Rc::as_ptr(&doc) as *const () as usize,
+        doc.cache_rev().get_untracked(),
+        Rc::as_ptr(&provider) as *const () as usize,
+    );
+    if ed.symbols.borrow().key != Some(key) {
+        let symbols = Rc::new(provider.symbols(ed));
+        *ed.symbols.borrow_mut() = SymbolCache {
+            key: Some(key),
+            symbols,
+        };
+    }
+    ed.symbols.borrow().symbols.clone()
+}
+
+/// The innermost symbol containing the offset, if any.
+pub fn symbol_at(ed: &Editor, offset: usize) -> Option<DocumentSymbol> {
+    let symbols = document_symbols(ed);
+    let mut found = None;
+    let mut level = symbols.as_slice();
+    while let Some(symbol) = level
+        .iter()
+        .find(|symbol| symbol.range.start <= offset && offset <= symbol.range.end)
+    {
+        found = Some(symbol);
+        level = symbol.children.as_slice();
+    }
+    found.cloned()
+}
+
+/// The name offsets of the symbol after the cursor, or before it when `next` isn't set.
+pub(crate) fn syntax_item_name(ed: &Editor, next: bool) -> Option<Range<usize>> {
+    let start = ed.cursor.with_untracked(|cursor| match &cursor.mode {
+        CursorMode::Insert(selection) => selection.min_offset(),
+        _ => cursor.offset(),
+    });
+    let symbols = document_symbols(ed);
+    let flat = flatten_symbols(&symbols);
+    let symbol = if next {
+        // A name selected by moving to it starts at the cursor, so moving again goes past it.
+        flat.iter()
+            .find(|(_, symbol)| symbol.name_range.start > start)
+    } else {
+        flat.iter()
+            .rev()
+            .find(|(_, symbol)| symbol.name_range.start < start)
+    };
+    symbol.map(|(_, symbol)| symbol.name_range.clone())
+}
+
+/// Moves the cursor to the name of the symbol, selecting it in insert mode.
+pub fn go_to_symbol(ed: &Editor, name_range: Range<usize>) {
+    ed.cursor.update(|cursor| match cursor.mode {
+        CursorMode::Insert(_) => {
+            cursor.set_insert(Selection::region(name_range.start, name_range.end));
+        }
+        _ => cursor.set_offset(name_range.start, false, false),
+    });
+}
+
+/// A list of the symbols of the editor's document, indented by their nesting. The symbol
+/// containing the cursor has the [`OutlineCurrentItemClass`] and is scrolled to, and clicking a
+/// symbol moves the cursor to it.
+pub fn outline_view(editor: &Editor) -> impl IntoView {
+    let ed = editor.clone();
+    let items = create_memo(move |_| {
+        ed.doc_track().cache_rev().track();
+        ed.symbol_provider.track();
+        let symbols = document_symbols(&ed);
+        flatten_symbols(&symbols)
+            .into_iter()
+            .map(|(depth, symbol)| (depth, symbol.name.clone(), symbol.name_range.clone()))
+            .collect::<Vec<_>>()
+    });
+
+    let ed = editor.clone();
+    let current = create_memo(move |_| {
+        items.track();
+        let offset = ed.cursor.with(|cursor| cursor.offset());
+        symbol_at(&ed, offset).map(|symbol| symbol.name_range.start)
+    });
+
+    let ed = editor.clone();
+    scroll(
+        dyn_stack(
+            move || items.get(),
+            |(_, _, name_range)| name_range.start,
+            move |(depth, name, name_range)| {
+                let ed = ed.clone();
+                let start = name_range.start;
+                let item = label(move || name.clone())
+                    .class(OutlineItemClass)
+                    .class_if(
+                        move || current.get() == Some(start),
+                        OutlineCurrentItemClass,
+                    )
+                    .on_event_stop(EventListener::PointerDown, move |_| {
+                        go_to_symbol(&ed, name_range.clone())
+                    })
+                    .style(move |s| s.padding_left(depth as f64 * OUTLINE_INDENT));
+                let item_id = item.id();
+                create_effect(move |_| {
+                    if current.get() == Some(start) {
+                        item_id.scroll_to(None);
+                    }
+                });
+                item
+            },
+        )
+        .style(|s| s.flex_col().width_full()),
+    )
+    .class(OutlineClass)
+}
+
+#[cfg(test)]
+mod tests {
+    use floem_editor_core::buffer::rope_text::RopeTextVal;
+    use lapce_xi_rope::Rope;
+
+    use super::{flatten_symbols, KeywordSymbols, SymbolKind};
+
+    #[test]
+    fn keyword_symbols() {
+        let source = "pub struct A {\n    b: u8,\n}\n\nimpl<T> Foo<T> for A {\n    pub(crate) fn c(&self) {\n        let d = 1;\n    }\n\n    async fn e() {}\n}\nfn f() {}\n";
+        let text = RopeTextVal::new(Rope::from(source));
+        let symbols = KeywordSymbols::default().text_symbols(&text);
+
+        let flat = flatten_symbols(&symbols)
+            .into_iter()
+            .map(|(depth, symbol)| (depth, symbol.name.as_str(), symbol.kind))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            flat,
+            [
+                (0, "A", SymbolKind::Struct),
+                (0, "Foo<T> for A", SymbolKind::Impl),
+                (1, "c", SymbolKind::Function),
+                (1, "e", SymbolKind::Function),
+                (0, "f", SymbolKind::Function),
+            ]
+        );
+
+        // The struct ends with its closing brace, and the impl contains its methods.
+        assert_eq!(
+            &source[symbols[0].range.clone()],
+            "pub struct A {\n    b: u8,\n}"
+        );
+        assert_eq!(&source[symbols[1].children[0].name_range.clone()], "c");
+        assert!(symbols[1].range.end > symbols[1].children[1].range.end);
+        assert_eq!(
+            &source[symbols[1].children[0].range.clone()],
+            "pub(crate) fn c(&self) {\n        let d = 1;\n    }"
+        );
+        assert_eq!(&source[symbols[2].range.clone()], "fn f() {}");
+    }
+}
@@ -1,4 +1,4 @@
-use std::{borrow::Cow, fmt::Debug, ops::Range, rc::Rc};
+use std::{borrow::Cow, collections::BTreeSet, fmt::Debug, ops::Range, rc::Rc};
 
 use crate::{
     keyboard::Modifiers,
@@ -175,6 +175,33 @@ pub trait Document: DocumentPhantom + Downcast {
 
     fn receive_char(&self, ed: &Editor, c: &str);
 
+    /// Set the mark `name` at the offset, like `m` in vim.  
+    /// The mark should move with the text around it as the document is edited. Documents that
+    /// don't keep marks ignore this.
+    fn set_mark(&self, _name: char, _offset: usize) {}
+
+    /// The offset of the mark `name`, if it's set.
+    fn mark(&self, _name: char) -> Option<usize> {
+        None
+    }
+
+    /// Called when an editor starts showing the document, so that the document can move the
+    /// editor's cursor with the text on edits that aren't made through that editor, like those
+    /// of other editors or of external tools. Documents that don't do this ignore it.
+    fn add_editor_cursor(&self, _editor: EditorId, _cursor: RwSignal<Cursor>) {}
+
+    /// Called when an editor starts showing the document, so that the document can move the
+    /// editor's breakpoints with their lines on every edit. Documents that don't do this ignore
+    /// it.
+    fn add_editor_breakpoints(&self, _editor: EditorId, _breakpoints: RwSignal<BTreeSet<usize>>) {}
+
+    /// Whether the document is a large file, for which the editor skips the work over the whole
+    /// document: it doesn't wrap lines, show phantom text or highlight and color brackets, so
+    /// that only the visible lines are laid out.
+    fn is_large_file(&self) -> bool {
+        false
+    }
+
     /// Perform a single edit.  
     fn edit_single(&self, selection: Selection, content: &str, edit_type: EditType) {
         let mut iter = std::iter::once((selection, content));
@@ -376,7 +403,7 @@ pub fn default_light_theme(mut style: EditorCustomStyle) -> EditorCustomStyle {
     let current_line = Color::rgb8(0xF2, 0xF2, 0xF2);
     let _dropdown_shadow = Color::rgb8(0xB4, 0xB4, 0xB4);
     let _link = blue;
-    let _sticky_header_background = bg;
+    let sticky_header_background = bg;
 
     style.0 = style
         .0
@@ -393,6 +420,7 @@ pub fn default_light_theme(mut style: EditorCustomStyle) -> EditorCustomStyle {
         .preedit_underline_color(fg)
         .indent_guide_color(grey)
         .gutter_current_color(current_line)
+        .sticky_header_background(sticky_header_background)
 }
 
 pub fn default_dark_color(mut style: EditorCustomStyle) -> EditorCustomStyle {
@@ -406,7 +434,7 @@ pub fn default_dark_color(mut style: EditorCustomStyle) -> EditorCustomStyle {
     let current_line = Color::rgb8(0x2C, 0x31, 0x3c);
     let _dropdown_shadow = Color::BLACK;
     let _link = blue;
-    let _sticky_header_background = bg;
+    let sticky_header_background = bg;
 
     style.0 = style
         .0
@@ -423,6 +451,7 @@ pub fn default_dark_color(mut style: EditorCustomStyle) -> EditorCustomStyle {
         .preedit_underline_color(fg)
         .indent_guide_color(grey)
         .gutter_current_color(current_line)
+        .sticky_header_background(sticky_header_background)
 }
 
 pub type DocumentRef = Rc<dyn Document>;
@@ -505,6 +534,26 @@ where
         self.doc.receive_char(ed, c)
     }
 
+    fn set_mark(&self, name: char, offset: usize) {
+        self.doc.set_mark(name, offset)
+    }
+
+    fn mark(&self, name: char) -> Option<usize> {
+        self.doc.mark(name)
+    }
+
+    fn add_editor_cursor(&self, editor: EditorId, cursor: RwSignal<Cursor>) {
+        self.doc.add_editor_cursor(editor, cursor)
+    }
+
+    fn add_editor_breakpoints(&self, editor: EditorId, breakpoints: RwSignal<BTreeSet<usize>>) {
+        self.doc.add_editor_breakpoints(editor, breakpoints)
+    }
+
+    fn is_large_file(&self) -> bool {
+        self.doc.is_large_file()
+    }
+
     fn edit_single(&self, selection: Selection, content: &str, edit_type: EditType) {
         self.doc.edit_single(selection, content, edit_type)
     }
@@ -1,27 +1,40 @@
 use std::{
+    borrow::Cow,
     cell::{Cell, RefCell},
-    collections::HashMap,
+    collections::{BTreeSet, HashMap, VecDeque},
+    fs::File,
+    io::Read,
     ops::Range,
+    path::{Path, PathBuf},
     rc::Rc,
+    sync::Arc,
 };
 
 use floem_editor_core::{
-    buffer::{rope_text::RopeText, Buffer, InvalLines},
+    buffer::{
+        rope_text::{RopeText, RopeTextRef, RopeTextVal},
+        Buffer, InvalLines, LARGE_FILE_THRESHOLD,
+    },
     command::EditCommand,
-    cursor::Cursor,
+    cursor::{Cursor, CursorMode},
     editor::{Action, EditConf, EditType},
+    line_ending::LineEnding,
     mode::{Mode, MotionMode},
     register::Register,
-    selection::Selection,
+    selection::{SelRegion, Selection},
     word::WordCursor,
 };
 use floem_reactive::{
     create_effect, RwSignal, Scope, SignalGet, SignalTrack, SignalUpdate, SignalWith,
 };
-use lapce_xi_rope::{Rope, RopeDelta};
+use lapce_xi_rope::{Rope, RopeDelta, Transformer};
+use parking_lot::Mutex;
 use smallvec::{smallvec, SmallVec};
 
-use crate::keyboard::Modifiers;
+use crate::{
+    ext_event::{create_ext_action, register_ext_trigger, spawn_background},
+    keyboard::Modifiers,
+};
 
 use super::{
     actions::{handle_command_default, CommonAction},
@@ -54,6 +67,27 @@ impl<'a> OnUpdate<'a> {
     }
 }
 
+/// The size of the parts that [`TextDocument::load_file`] reads a file in.
+const LOAD_CHUNK_SIZE: usize = 4 * 1024 * 1024;
+
+/// How far a [`TextDocument`] is through loading its file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LoadState {
+    /// The file is still being read, and the document holds the part read so far.
+    Loading,
+    Loaded,
+    /// Reading the file failed, with the error. The document holds the part read before it.
+    Failed(String),
+}
+
+/// A part of a file read by [`TextDocument::load_file`].
+#[derive(Clone)]
+enum FileChunk {
+    Text(Rope),
+    Done,
+    Failed(String),
+}
+
 /// A simple text document that holds content in a rope.  
 /// This can be used as a base structure for common operations.
 #[derive(Clone)]
@@ -69,6 +103,16 @@ pub struct TextDocument {
 
     pub placeholders: RwSignal<HashMap<EditorId, String>>,
 
+    /// The cursors of the editors into the document, which are moved with the text on edits that
+    /// aren't made through their editor.
+    editor_cursors: Rc<RefCell<HashMap<EditorId, RwSignal<Cursor>>>>,
+    /// The breakpoints of the editors into the document, which are moved with their lines on
+    /// every edit.
+    editor_breakpoints: Rc<RefCell<HashMap<EditorId, RwSignal<BTreeSet<usize>>>>>,
+
+    /// How far the document is through loading its file, see [`TextDocument::load_file`].
+    pub load_state: RwSignal<LoadState>,
+
     // (cmd: &Command, count: Option<usize>, modifiers: ModifierState)
     /// Ran before a command is executed. If it says that it executed the command, then handlers
     /// after it will not be called.
@@ -102,11 +146,160 @@ impl TextDocument {
             keep_indent: Cell::new(true),
             auto_indent: Cell::new(false),
             placeholders,
+            editor_cursors: Rc::new(RefCell::new(HashMap::new())),
+            editor_breakpoints: Rc::new(RefCell::new(HashMap::new())),
+            load_state: cx.create_rw_signal(LoadState::Loaded),
             pre_command: Rc::new(RefCell::new(HashMap::new())),
             on_updates: Rc::new(RefCell::new(SmallVec::new())),
         }
     }
 
+    /// Create a document that reads the file at `path` in the background, appending each part
+    /// as it's read, so that it can be shown and scrolled before the whole file is read.  
+    /// A file above [`LARGE_FILE_THRESHOLD`] is a large file from the start, see
+    /// [`Document::is_large_file`].
+    pub fn load_file(cx: Scope, path: impl Into<PathBuf>) -> TextDocument {
+        let path = path.into();
+        let doc = TextDocument::new(cx, "");
+        let large_file = std::fs::metadata(&path)
+            .map(|metadata| metadata.len() > LARGE_FILE_THRESHOLD as u64)
+            .unwrap_or(false);
+        doc.buffer
+            .update(|buffer| buffer.set_large_file(large_file));
+        doc.load_state.set(LoadState::Loading);
+
+        // The parts that were read, waiting to be appended on the UI thread.
+        let chunks = Arc::new(Mutex::new(VecDeque::new()));
+        let trigger = cx.create_trigger();
+        {
+            let chunks = chunks.clone();
+            let doc = doc.clone();
+            cx.create_effect(move |_| {
+                trigger.track();
+                let ready = std::mem::take(&mut *chunks.lock());
+                for chunk in ready {
+                    match chunk {
+                        FileChunk::Text(text) => doc.append_loaded(text),
+                        FileChunk::Done => doc.load_state.set(LoadState::Loaded),
+                        FileChunk::Failed(err) => doc.load_state.set(LoadState::Failed(err)),
+                    }
+                }
+            });
+        }
+
+        spawn_background(move || {
+            // Reading stops once the document is gone, along with the effect that appends.
+            let send = |chunk| {
+                chunks.lock().push_back(chunk);
+                register_ext_trigger(trigger);
+                Arc::strong_count(&chunks) > 1
+            };
+            let read = read_file_chunks(&path, |text| send(FileChunk::Text(text)));
+            send(match read {
+                Ok(()) => FileChunk::Done,
+                Err(err) => FileChunk::Failed(err.to_string()),
+            });
+        });
+
+        doc
+    }
+
+    /// Appends the next part of the file being loaded.
+    fn append_loaded(&self, text: Rope) {
+        let delta = self.buffer.try_update(|buffer| buffer.append_content(text));
+
+        self.update_cache_rev();
+        if let Some(delta) = delta {
+            // The text goes after every cursor, so the cursors stay where they are.
+            self.notify_update(None, &[delta]);
+        }
+    }
+
+    /// Whether the buffer is unchanged since it was last marked pristine, like when it was saved.
+    pub fn is_pristine(&self) -> bool {
+        self.buffer.with_untracked(Buffer::is_pristine)
+    }
+
+    /// Mark the buffer as pristine, like after saving it.
+    pub fn set_pristine(&self) {
+        self.buffer.update(Buffer::set_pristine);
+    }
+
+    /// The line ending of the buffer, which was detected from its text.
+    pub fn line_ending(&self) -> LineEnding {
+        self.buffer.with_untracked(Buffer::line_ending)
+    }
+
+    /// Replace the content of the buffer, like when the file it was loaded from changed.  
+    /// Reloading can be undone. The cursors of the editors keep their line and column.
+    pub fn reload(&self, content: Rope, set_pristine: bool) {
+        let delta = self
+            .buffer
+            .try_update(|buffer| buffer.reload(content, set_pristine));
+
+        self.update_cache_rev();
+        if let Some(delta) = delta {
+            self.on_update(None, &[delta]);
+        }
+    }
+
+    /// The current revision of the buffer, which edits made elsewhere, like by a background
+    /// formatter, are made against. See [`TextDocument::apply_delta_at_rev`].
+    pub fn rev(&self) -> u64 {
+        self.buffer.with_untracked(Buffer::rev)
+    }
+
+    /// Applies a delta made against the text of the revision `base_rev`, rebasing it over the
+    /// edits made since then, see [`Buffer::apply_delta_at_rev`]. The cursors of the editors,
+    /// the marks and the preedit move with the text, and the edit is undone on its own.  
+    /// Returns `false` when the revision isn't known or the delta doesn't apply to its text.
+    pub fn apply_delta_at_rev(&self, base_rev: u64, delta: RopeDelta) -> bool {
+        let delta = self
+            .buffer
+            .try_update(|buffer| buffer.apply_delta_at_rev(base_rev, delta, EditType::Other))
+            .flatten();
+        let Some(delta) = delta else {
+            return false;
+        };
+
+        self.preedit.preedit.update(|preedit| {
+            if let Some(preedit) = preedit {
+                preedit.offset = Transformer::new(&delta.1).transform(preedit.offset, true);
+            }
+        });
+
+        self.update_cache_rev();
+        self.on_update(None, &[delta]);
+        true
+    }
+
+    /// Finds the ranges of the text that match `pattern` on a background thread, or right away
+    /// on wasm, which has no threads, so that searching a large file doesn't block the editor.
+    /// A match doesn't span lines, and matching without case sensitivity only ignores the case
+    /// of ASCII letters.
+    ///
+    /// `on_found` is called with the matches on the UI thread, unless the text was edited in the
+    /// meantime, since the matches would be out of date.
+    pub fn find_all(
+        &self,
+        pattern: impl Into<String>,
+        case_sensitive: bool,
+        on_found: impl FnOnce(Vec<Range<usize>>) + 'static,
+    ) {
+        let pattern = pattern.into();
+        let text = self.text();
+        let rev = self.rev();
+        let buffer = self.buffer;
+        let send = create_ext_action(Scope::current(), move |found| {
+            if buffer.try_with_untracked(|buffer| buffer.map(Buffer::rev)) == Some(rev) {
+                on_found(found);
+            }
+        });
+        spawn_background(move || {
+            send(find_in_text(text, &pattern, case_sensitive));
+        });
+    }
+
     fn update_cache_rev(&self) {
         self.cache_rev.try_update(|cache_rev| {
             *cache_rev += 1;
@@ -114,6 +307,78 @@ impl TextDocument {
     }
 
     fn on_update(&self, ed: Option<&Editor>, deltas: &[(Rope, RopeDelta, InvalLines)]) {
+        self.move_editor_cursors(ed.map(Editor::id), deltas);
+        self.move_editor_breakpoints(deltas);
+        self.notify_update(ed, deltas);
+    }
+
+    /// Moves the breakpoints of all the editors with their lines, forgetting those of the
+    /// editors that are gone.
+    fn move_editor_breakpoints(&self, deltas: &[(Rope, RopeDelta, InvalLines)]) {
+        let breakpoints: Vec<_> = self
+            .editor_breakpoints
+            .borrow()
+            .iter()
+            .map(|(id, breakpoints)| (*id, *breakpoints))
+            .collect();
+        for (id, breakpoints) in breakpoints {
+            let Some(empty) =
+                breakpoints.try_with_untracked(|breakpoints| breakpoints.map(BTreeSet::is_empty))
+            else {
+                self.editor_breakpoints.borrow_mut().remove(&id);
+                continue;
+            };
+            if !empty {
+                breakpoints.update(|breakpoints| {
+                    for (old_text, delta, _) in deltas {
+                        move_breakpoints(breakpoints, old_text, delta);
+                    }
+                });
+            }
+        }
+    }
+
+    /// Moves the cursors of the editors other than `edid` with the text, forgetting those of the
+    /// editors that are gone.
+    fn move_editor_cursors(
+        &self,
+        edid: Option<EditorId>,
+        deltas: &[(Rope, RopeDelta, InvalLines)],
+    ) {
+        let cursors: Vec<_> = self
+            .editor_cursors
+            .borrow()
+            .iter()
+            .map(|(id, cursor)| (*id, *cursor))
+            .collect();
+        for (id, cursor) in cursors {
+            if Some(id) == edid {
+                continue;
+            }
+            let moved = cursor.try_update(|cursor| {
+                for (old_text, delta, _) in deltas {
+                    move_cursor(cursor, old_text, delta);
+                }
+            });
+            if moved.is_none() {
+                self.editor_cursors.borrow_mut().remove(&id);
+            }
+        }
+    }
+
+    /// Keeps the bracket depths of the editor before the first edited line. Called before the
+    /// cache revision is bumped for the edits.
+    fn invalidate_bracket_depths(&self, ed: &Editor, deltas: &[(Rope, RopeDelta, InvalLines)]) {
+        let start_line = deltas.iter().map(|(_, _, inval)| inval.start_line).min();
+        if let Some(start_line) = start_line {
+            let cache_rev = self.cache_rev.get_untracked();
+            ed.bracket_depths
+                .borrow_mut()
+                .invalidate_from(start_line, cache_rev, cache_rev + 1);
+        }
+    }
+
+    fn notify_update(&self, ed: Option<&Editor>, deltas: &[(Rope, RopeDelta, InvalLines)]) {
         let on_updates = self.on_updates.borrow();
         let data = OnUpdate { editor: ed, deltas };
         for on_update in on_updates.iter() {
@@ -225,6 +490,7 @@ impl Document for TextDocument {
                     buffer.set_cursor_after(cursor.mode.clone());
                 });
                 // TODO: line specific invalidation
+                self.invalidate_bracket_depths(ed, &deltas);
                 self.update_cache_rev();
                 self.on_update(Some(ed), &deltas);
             }
@@ -232,6 +498,30 @@ impl Document for TextDocument {
         }
     }
 
+    fn set_mark(&self, name: char, offset: usize) {
+        self.buffer
+            .update(|buffer| buffer.marks_mut().set(name, offset));
+    }
+
+    fn mark(&self, name: char) -> Option<usize> {
+        self.buffer
+            .with_untracked(|buffer| buffer.marks().get(name))
+    }
+
+    fn add_editor_cursor(&self, editor: EditorId, cursor: RwSignal<Cursor>) {
+        self.editor_cursors.borrow_mut().insert(editor, cursor);
+    }
+
+    fn add_editor_breakpoints(&self, editor: EditorId, breakpoints: RwSignal<BTreeSet<usize>>) {
+        self.editor_breakpoints
+            .borrow_mut()
+            .insert(editor, breakpoints);
+    }
+
+    fn is_large_file(&self) -> bool {
+        self.buffer.with_untracked(Buffer::is_large_file)
+    }
+
     fn edit(&self, iter: &mut dyn Iterator<Item = (Selection, &str)>, edit_type: EditType) {
         let deltas = self
             .buffer
@@ -305,8 +595,17 @@ impl CommonAction for TextDocument {
         is_vertical: bool,
         register: &mut Register,
     ) {
+        let mut clipboard = SystemClipboard::new();
         self.buffer.try_update(move |buffer| {
-            Action::execute_motion_mode(cursor, buffer, motion_mode, range, is_vertical, register)
+            Action::execute_motion_mode_with_clipboard(
+                cursor,
+                buffer,
+                motion_mode,
+                range,
+                is_vertical,
+                register,
+                &mut clipboard,
+            )
         });
     }
 
@@ -352,6 +651,7 @@ impl CommonAction for TextDocument {
                 buffer.set_cursor_after(cursor.mode.clone());
             });
 
+            self.invalidate_bracket_depths(ed, &deltas);
             self.update_cache_rev();
             self.on_update(Some(ed), &deltas);
         }
@@ -367,3 +667,238 @@ impl std::fmt::Debug for TextDocument {
         s.finish()
     }
 }
+
+/// Moves the cursor of another editor with an edit. Text inserted at the cursor goes after it,
+/// and when the whole text is replaced, like on a reload, the cursor keeps its line and column.
+fn move_cursor(cursor: &mut Cursor, old_text: &Rope, delta: &RopeDelta) {
+    let (iv, _) = delta.summary();
+    if !old_text.is_empty() && iv.start == 0 && iv.end == old_text.len() {
+        let new_text = delta.apply(old_text);
+        let old = RopeTextRef::new(old_text);
+        let new = RopeTextRef::new(&new_text);
+        map_cursor_offsets(cursor, |offset| {
+            let (line, col) = old.offset_to_line_col(offset);
+            new.offset_of_line_col(line, col)
+        });
+    } else {
+        map_cursor_offsets(cursor, |offset| {
+            Transformer::new(delta).transform(offset, false)
+        });
+    }
+}
+
+/// Moves the breakpoint lines through the delta, like [`move_cursor`] does for offsets. A line
+/// moves with the newlines inserted or deleted before it, and a reload keeps the line numbers.
+fn move_breakpoints(breakpoints: &mut BTreeSet<usize>, old_text: &Rope, delta: &RopeDelta) {
+    let (iv, _) = delta.summary();
+    let new_text = delta.apply(old_text);
+    let old = RopeTextRef::new(old_text);
+    let new = RopeTextRef::new(&new_text);
+    let reload = !old_text.is_empty() && iv.start == 0 && iv.end == old_text.len();
+    let last_line = new.last_line();
+    *breakpoints = breakpoints
+        .iter()
+        .map(|&line| {
+            if reload {
+                line.min(last_line)
+            } else {
+                // Text inserted at the start of the line goes before it, so the breakpoint
+                // stays with the line's text.
+                let offset = Transformer::new(delta).transform(old.offset_of_line(line), true);
+                new.line_of_offset(offset)
+            }
+        })
+        .collect();
+}
+
+fn map_cursor_offsets(cursor: &mut Cursor, f: impl Fn(usize) -> usize) {
+    cursor.mode = match &cursor.mode {
+        CursorMode::Normal(offset) => CursorMode::Normal(f(*offset)),
+        CursorMode::Visual { start, end, mode } => CursorMode::Visual {
+            start: f(*start),
+            end: f(*end),
+            mode: *mode,
+        },
+        CursorMode::Insert(selection) => {
+            let mut moved = Selection::new();
+            for region in selection.regions() {
+                moved.add_region(SelRegion::new(f(region.start), f(region.end), None));
+            }
+            CursorMode::Insert(moved)
+        }
+    };
+    cursor.horiz = None;
+}
+
+/// The ranges of the lines of `text` that match `pattern`, see [`TextDocument::find_all`].
+fn find_in_text(text: Rope, pattern: &str, case_sensitive: bool) -> Vec<Range<usize>> {
+    if pattern.is_empty() {
+        return Vec::new();
+    }
+    let pattern = if case_sensitive {
+        pattern.to_string()
+    } else {
+        pattern.to_ascii_lowercase()
+    };
+    let text = RopeTextVal::new(text);
+    let mut found = Vec::new();
+    for line in 0..text.num_lines() {
+        let content = text.line_content(line);
+        let content = if case_sensitive {
+            content
+        } else {
+            Cow::Owned(content.to_ascii_lowercase())
+        };
+        let line_start = text.offset_of_line(line);
+        found.extend(
+            content
+                .match_indices(&pattern)
+                .map(|(start, matched)| line_start + start..line_start + start + matched.len()),
+        );
+    }
+    found
+}
+
+/// Reads the file in parts of about [`LOAD_CHUNK_SIZE`], passing each to `send`, until the end of
+/// the file or until `send` returns `false`. Invalid UTF-8 is replaced.
+fn read_file_chunks(path: &Path, mut send: impl FnMut(Rope) -> bool) -> std::io::Result<()> {
+    let mut file = File::open(path)?;
+    let mut buf = vec![0; LOAD_CHUNK_SIZE];
+    // The bytes of a character that was cut off at the end of the last part read.
+    let mut pending = Vec::new();
+    loop {
+        let read = file.read(&mut buf)?;
+        if read == 0 {
+            break;
+        }
+
+        pending.extend_from_slice(&buf[..read]);
+        let len = complete_utf8_len(&pending);
+        let text = Rope::from(String::from_utf8_lossy(&pending[..len]).as_ref());
+        pending.drain(..len);
+        if !send(text) {
+            return Ok(());
+        }
+    }
+
+    if !pending.is_empty() {
+        send(Rope::from(String::from_utf8_lossy(&pending).as_ref()));
+    }
+    Ok(())
+}
+
+/// The length of the bytes without the character that is cut off at their end, if any.
+fn complete_utf8_len(bytes: &[u8]) -> usize {
+    // A character is at most 4 bytes, so a cut off one starts in the last 3 bytes.
+    for back in 1..=bytes.len().min(3) {
+        let byte = bytes[bytes.len() - back];
+        // Skip the continuation bytes to find the first byte of the last character
+        if byte & 0xC0 == 0x80 {
+            continue;
+        }
+
+        let width = match byte {
+            0xF0.. => 4,
+            0xE0.. => 3,
+            0xC0.. => 2,
+            _ => 1,
+        };
+        return if width > back {
+            bytes.len() - back
+        } else {
+            bytes.len()
+        };
+    }
+    bytes.len()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeSet;
+
+    use floem_editor_core::{
+        cursor::{Cursor, CursorMode},
+        editor::EditType,
+        selection::Selection,
+    };
+    use floem_reactive::{Scope, SignalGet, SignalUpdate};
+    use lapce_xi_rope::Rope;
+
+    use crate::views::editor::{id::EditorId, text::Document};
+
+    use super::{complete_utf8_len, find_in_text, TextDocument};
+
+    #[test]
+    fn find_matches() {
+        let text = Rope::from("Error: a\nok\nerror: b error\n");
+        assert_eq!(find_in_text(text.clone(), "error", true), [20..25]);
+        assert_eq!(
+            find_in_text(text.clone(), "ERROR", false),
+            [0..5, 12..17, 20..25]
+        );
+        assert_eq!(find_in_text(text, "", false), []);
+    }
+
+    #[test]
+    fn cut_off_characters() {
+        let text = "a€b".as_bytes();
+        assert_eq!(complete_utf8_len(text), 5);
+        // The 3 bytes of `€` are cut off after its first and second bytes.
+        assert_eq!(complete_utf8_len(&text[..2]), 1);
+        assert_eq!(complete_utf8_len(&text[..3]), 1);
+        assert_eq!(complete_utf8_len(&text[..4]), 4);
+        assert_eq!(complete_utf8_len(&[]), 0);
+    }
+
+    #[test]
+    fn other_editor_cursors() {
+        let cx = Scope::new();
+        let doc = TextDocument::new(cx, "one\ntwo\nthree");
+        let cursor = cx.create_rw_signal(Cursor::new(CursorMode::Normal(4), None, None));
+        doc.add_editor_cursor(EditorId::next(), cursor);
+        let offset = move || match cursor.get().mode {
+            CursorMode::Normal(offset) => offset,
+            _ => unreachable!(),
+        };
+
+        // Text inserted at the cursor goes after it, and text before it moves it.
+        doc.edit_single(Selection::caret(4), "new ", EditType::InsertChars);
+        assert_eq!(offset(), 4);
+        doc.edit_single(Selection::caret(0), "0", EditType::InsertChars);
+        assert_eq!(offset(), 5);
+
+        // Loading the rest of a file doesn't move it.
+        doc.append_loaded(Rope::from("\nfour"));
+        assert_eq!(offset(), 5);
+
+        // A reload keeps its line and column.
+        cursor.set(Cursor::new(CursorMode::Normal(10), None, None));
+        doc.reload(Rope::from("1\ntwo 2\nthree\n"), true);
+        assert_eq!(offset(), 7);
+        doc.reload(Rope::from("1\nt"), true);
+        assert_eq!(offset(), 3);
+    }
+
+    #[test]
+    fn breakpoints_follow_lines() {
+        let cx = Scope::new();
+        let doc = TextDocument::new(cx, "one\ntwo\nthree\nfour");
+        let breakpoints = cx.create_rw_signal(BTreeSet::from([1, 3]));
+        doc.add_editor_breakpoints(EditorId::next(), breakpoints);
+        let lines = move || breakpoints.get().into_iter().collect::<Vec<_>>();
+
+        doc.edit_single(Selection::caret(0), "zero\n", EditType::InsertChars);
+        assert_eq!(lines(), [2, 4]);
+        // Text inserted at the start of a line stays on it.
+        doc.edit_single(Selection::caret(9), "x", EditType::InsertChars);
+        assert_eq!(lines(), [2, 4]);
+        doc.edit_single(Selection::region(5, 9), "", EditType::Delete);
+        assert_eq!(lines(), [1, 3]);
+        // The breakpoints of deleted lines go to the line after them.
+        doc.edit_single(Selection::region(5, 16), "", EditType::Delete);
+        assert_eq!(lines(), [1]);
+
+        doc.reload(Rope::from("a"), true);
+        assert_eq!(lines(), [0]);
+    }
+}
@@ -8,6 +8,7 @@ use crate::{
     keyboard::{Key, Modifiers, NamedKey},
     kurbo::{BezPath, Line, Point, Rect, Size, Vec2},
     peniko::Color,
+    pointer::PointerButton,
     reactive::{batch, create_effect, create_memo, create_rw_signal, Memo, RwSignal, Scope},
     style::{CursorStyle, Style},
     style_class,
@@ -18,16 +19,19 @@ use crate::{
     Renderer,
 };
 use floem_editor_core::{
+    buffer::rope_text::RopeText,
     cursor::{ColPosition, CursorAffinity, CursorMode},
     mode::{Mode, VisualMode},
 };
 use floem_reactive::{SignalGet, SignalTrack, SignalUpdate, SignalWith};
 
 use crate::views::editor::{
+    bracket::paint_matching_brackets,
     command::CommandExecuted,
-    gutter::editor_gutter_view,
+    gutter::{click_gutter_column, editor_gutter_view},
     keypress::{key::KeyInput, press::KeyPress},
     layout::LineExtraStyle,
+    sticky_header::{paint_sticky_headers, sticky_header_line_at},
     visual_line::{RVLine, VLineInfo},
 };
 
@@ -894,6 +898,7 @@ impl View for EditorView {
         // within the active screen lines without issue.
         let screen_lines = ed.screen_lines.get_untracked();
         EditorView::paint_cursor(cx, &ed, &screen_lines);
+        paint_matching_brackets(cx, &ed, &screen_lines);
         let screen_lines = ed.screen_lines.get_untracked();
         EditorView::paint_text(
             cx,
@@ -902,6 +907,7 @@ impl View for EditorView {
             self.is_active.get_untracked(),
             &screen_lines,
         );
+        paint_sticky_headers(cx, &ed, viewport, &screen_lines);
     }
 }
 
@@ -1097,7 +1103,7 @@ pub fn editor_container_view(
 }
 
 /// Default editor gutter
-/// Simply shows line numbers
+/// Shows line numbers, and the [columns](super::gutter::GutterColumn) added to the editor
 pub fn editor_gutter(editor: RwSignal<Editor>) -> impl IntoView {
     let ed = editor.get_untracked();
 
@@ -1114,6 +1120,16 @@ pub fn editor_gutter(editor: RwSignal<Editor>) -> impl IntoView {
                 scroll_delta.set(pointer_event.delta);
             }
         })
+        .on_event(EventListener::PointerDown, move |event| {
+            if let Event::PointerDown(pointer_event) = event {
+                let pos = pointer_event.pos;
+                let clicked = editor.with_untracked(|ed| click_gutter_column(ed, pos.x, pos.y));
+                if clicked {
+                    return EventPropagation::Stop;
+                }
+            }
+            EventPropagation::Continue
+        })
 }
 
 fn editor_content(
@@ -1146,7 +1162,17 @@ fn editor_content(
                 if let Event::PointerDown(pointer_event) = event {
                     id.request_active();
                     id.request_focus();
-                    editor.get_untracked().pointer_down(pointer_event);
+                    let ed = editor.get_untracked();
+                    // Clicking a sticky header jumps to its line.
+                    if pointer_event.button == PointerButton::Primary {
+                        if let Some(line) = sticky_header_line_at(&ed, pointer_event.pos) {
+                            let offset = ed.rope_text().first_non_blank_character_on_line(line);
+                            ed.cursor
+                                .update(|cursor| cursor.set_offset(offset, false, false));
+                            return;
+                        }
+                    }
+                    ed.pointer_down(pointer_event);
                 }
             })
             .on_event_cont(EventListener::PointerMove, move |event| {
@@ -1169,7 +1195,10 @@ fn editor_content(
                     return;
                 };
 
-                handle_key_event(&keypress, key_event.modifiers);
+                // A key that ran a command, like a key of the modal mode, isn't typed.
+                if handle_key_event(&keypress, key_event.modifiers) == CommandExecuted::Yes {
+                    return;
+                }
 
                 let mut mods = key_event.modifiers;
                 mods.set(Modifiers::SHIFT, false);
@@ -0,0 +1,508 @@
+//! Forms with validated fields.
+//!
+//! A [`Field`] holds a value in a signal, with validators that check it whenever it changes. A
+//! synchronous [validator](Field::validator) returns an error message for values it rejects. An
+//! [asynchronous validator](Field::async_validator) runs on another thread once the synchronous
+//! ones pass, for checks that are slow or need the network.
+//!
+//! A field keeps track of whether it's *touched*, which happens when its view loses the focus or
+//! the form is submitted, and of whether it's *dirty*, when its value differs from the initial
+//! one. A [bound](Field::bind) view is marked as [invalid](crate::views::Decorators::invalid)
+//! while a touched field has an error, so it can be styled with
+//! [`Style::invalid`](crate::style::Style::invalid), and [`Field::error_label`] shows the error.
+//!
+//! A [`Form`] groups fields. [`Form::submit`] touches and validates all of them, waits for the
+//! asynchronous validators, and then either runs the [submit handler](Form::on_submit) or focuses
+//! the first invalid field.
+//!
+//! ```rust
+//! # use floem::reactive::SignalGet;
+//! # use floem::views::{button, text_input, v_stack, Decorators};
+//! # use floem::views::form::Form;
+//! let form = Form::new();
+//! let name = form
+//!     .field(String::new())
+//!     .validator(|name: &String| match name.trim().is_empty() {
+//!         true => Err("A name is required".to_string()),
+//!         false => Ok(()),
+//!     });
+//! let form = form.on_submit(move || println!("Hello {}", name.value().get()));
+//!
+//! v_stack((
+//!     name.bind(text_input(name.value())),
+//!     name.error_label(),
+//!     button("Submit").action(move || form.submit()),
+//! ));
+//! ```
+//!
+//! The [`FormFields`](crate::FormFields) derive macro generates the fields of a form from a
+//! struct. For a struct `SignUp`, `SignUp::to_form` makes a `SignUpForm` with the `form` and a
+//! field for each of the struct's fields, except those marked `#[form_skip]`, and
+//! `SignUpForm::to_value` reads the values back. `#[form_validate(...)]` and
+//! `#[form_async_validate(...)]` take the validators of a field.
+//!
+//! ```rust
+//! # use floem::reactive::SignalUpdate;
+//! use floem::FormFields;
+//!
+//! #[derive(FormFields)]
+//! struct SignUp {
+//!     #[form_validate(|name: &String| match name.is_empty() {
+//!         true => Err("A name is required".to_string()),
+//!         false => Ok(()),
+//!     })]
+//!     name: String,
+//!     #[form_skip]
+//!     id: u64,
+//! }
+//!
+//! let sign_up = SignUp { name: String::new(), id: 7 }.to_form();
+//! assert_eq!(sign_up.name.error().as_deref(), Some("A name is required"));
+//!
+//! sign_up.name.value().set("Ada".to_string());
+//! assert_eq!(sign_up.name.error(), None);
+//! let value = sign_up.to_value();
+//! assert_eq!((value.name.as_str(), value.id), ("Ada", 7));
+//! ```
+
+use std::{rc::Rc, sync::Arc};
+
+use floem_reactive::{
+    create_effect, create_rw_signal, untrack, RwSignal, Scope, SignalGet, SignalTrack,
+    SignalUpdate, SignalWith,
+};
+
+use crate::{
+    event::EventListener,
+    ext_event::{create_ext_action, spawn_background},
+    id::ViewId,
+    style_class,
+    view::IntoView,
+    views::{label, Decorators, Label},
+};
+
+style_class!(pub FormErrorClass);
+
+type Validator<T> = Rc<dyn Fn(&T) -> Result<(), String>>;
+/// Starts an asynchronous validation of the current value, for the generation of validations.
+type AsyncValidation = Rc<dyn Fn(u64)>;
+
+/// A value of a form, with validation. See the [module documentation](self).
+pub struct Field<T: 'static> {
+    /// The scope the field was created in, which the results of the asynchronous validator are
+    /// dropped with.
+    cx: Scope,
+    value: RwSignal<T>,
+    initial: RwSignal<T>,
+    touched: RwSignal<bool>,
+    error: RwSignal<Option<String>>,
+    validators: RwSignal<Vec<Validator<T>>>,
+    async_validation: RwSignal<Option<AsyncValidation>>,
+    /// Counts the validations, so that the result of an asynchronous validation of an outdated
+    /// value is dropped.
+    generation: RwSignal<u64>,
+    validating: RwSignal<bool>,
+    view: RwSignal<Option<ViewId>>,
+}
+
+impl<T> Clone for Field<T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T> Copy for Field<T> {}
+
+impl<T: Clone + PartialEq + 'static> Field<T> {
+    /// Creates a field that starts with the `initial` value and has no validators.
+    pub fn new(initial: T) -> Self {
+        let field = Field {
+            cx: Scope::current(),
+            value: create_rw_signal(initial.clone()),
+            initial: create_rw_signal(initial),
+            touched: create_rw_signal(false),
+            error: create_rw_signal(None),
+            validators: create_rw_signal(Vec::new()),
+            async_validation: create_rw_signal(None),
+            generation: create_rw_signal(0),
+            validating: create_rw_signal(false),
+            view: create_rw_signal(None),
+        };
+        create_effect(move |_| {
+            field.value.track();
+            field.validate();
+        });
+        field
+    }
+
+    /// Adds a validator that returns an error message for the values it rejects. The validators
+    /// run in the order they were added, and the first error is the error of the field.
+    pub fn validator(self, validator: impl Fn(&T) -> Result<(), String> + 'static) -> Self {
+        self.validators
+            .update(|validators| validators.push(Rc::new(validator)));
+        self.validate();
+        self
+    }
+
+    /// Sets a validator that runs on another thread once the synchronous validators pass. The
+    /// field [is validating](Self::is_validating) until it returns, and its result is dropped if
+    /// the value changed in the meantime or the field's scope was disposed. On wasm, which has
+    /// no threads, the validator runs right away.
+    pub fn async_validator(
+        self,
+        validator: impl Fn(T) -> Result<(), String> + Send + Sync + 'static,
+    ) -> Self
+    where
+        T: Send,
+    {
+        let validator = Arc::new(validator);
+        let field = self;
+        let validation = move |generation| {
+            let validator = validator.clone();
+            let value = field.value.get_untracked();
+            let send = create_ext_action(field.cx, move |result: Result<(), String>| {
+                if field.generation.get_untracked() == generation {
+                    field.error.set(result.err());
+                    field.validating.set(false);
+                }
+            });
+            spawn_background(move || send(validator(value)));
+        };
+        self.async_validation.set(Some(Rc::new(validation)));
+        self.validate();
+        self
+    }
+
+    /// Validates the current value, which happens by itself whenever it changes. Returns `false`
+    /// if a synchronous validator rejects it. The asynchronous validator is started otherwise.
+    pub fn validate(&self) -> bool {
+        let generation = self.generation.get_untracked() + 1;
+        self.generation.set(generation);
+        let error = self.value.with_untracked(|value| {
+            self.validators.with_untracked(|validators| {
+                validators
+                    .iter()
+                    .find_map(|validator| validator(value).err())
+            })
+        });
+        let is_valid = error.is_none();
+        self.error.set(error);
+
+        match self.async_validation.get_untracked().filter(|_| is_valid) {
+            Some(validation) => {
+                self.validating.set(true);
+                validation(generation);
+            }
+            None => self.validating.set(false),
+        }
+        is_valid
+    }
+
+    /// The signal with the value of the field.
+    pub fn value(&self) -> RwSignal<T> {
+        self.value
+    }
+
+    /// The error message of the current value, even if the field isn't touched yet.
+    pub fn error(&self) -> Option<String> {
+        self.error.get()
+    }
+
+    /// Returns `true` if the value passed all the validators, including the asynchronous one.
+    pub fn is_valid(&self) -> bool {
+        self.error.with(Option::is_none) && !self.validating.get()
+    }
+
+    /// Returns `true` while the asynchronous validator is running.
+    pub fn is_validating(&self) -> bool {
+        self.validating.get()
+    }
+
+    /// Returns `true` if the view of the field lost the focus, or the form was submitted.
+    pub fn is_touched(&self) -> bool {
+        self.touched.get()
+    }
+
+    /// Returns `true` if the value differs from the initial value.
+    pub fn is_dirty(&self) -> bool {
+        self.value
+            .with(|value| self.initial.with(|initial| value != initial))
+    }
+
+    /// Returns `true` if the field is touched and has an error, which is when the error is shown.
+    pub fn shows_error(&self) -> bool {
+        self.touched.get() && self.error.with(Option::is_some)
+    }
+
+    /// Marks the field as touched, so that its error is shown.
+    pub fn touch(&self) {
+        self.touched.set(true);
+    }
+
+    /// Puts back the initial value, and marks the field as untouched.
+    pub fn reset(&self) {
+        self.value.set(self.initial.get_untracked());
+        self.touched.set(false);
+    }
+
+    /// Makes the current value the initial one, so the field isn't dirty anymore, like after the
+    /// form is saved.
+    pub fn commit(&self) {
+        self.initial.set(self.value.get_untracked());
+    }
+
+    /// Binds the view that edits the value, like a [`text_input`](crate::views::text_input) of
+    /// the value signal. The field is touched when the view loses the focus, and the view is
+    /// marked as invalid while the field shows an error. A submitted form focuses the view of its
+    /// first invalid field.
+    pub fn bind<V: IntoView + 'static>(self, view: V) -> V::V {
+        let view = view.into_view();
+        self.view.set(Some(view.id()));
+        view.on_event_cont(EventListener::FocusLost, move |_| self.touch())
+            .invalid(move || self.shows_error())
+    }
+
+    /// A label with the error of the field, which is hidden while the field doesn't
+    /// [show an error](Self::shows_error).
+    pub fn error_label(self) -> Label {
+        label(move || self.error.get().unwrap_or_default())
+            .class(FormErrorClass)
+            .style(move |s| s.apply_if(!self.shows_error(), |s| s.hide()))
+    }
+}
+
+/// The operations of a form on its fields, whatever their type.
+trait FormField {
+    fn validate(&self) -> bool;
+    fn is_valid(&self) -> bool;
+    fn is_validating(&self) -> bool;
+    fn is_dirty(&self) -> bool;
+    fn touch(&self);
+    fn reset(&self);
+    fn commit(&self);
+    fn view(&self) -> Option<ViewId>;
+}
+
+impl<T: Clone + PartialEq + 'static> FormField for Field<T> {
+    fn validate(&self) -> bool {
+        Field::validate(self)
+    }
+
+    fn is_valid(&self) -> bool {
+        Field::is_valid(self)
+    }
+
+    fn is_validating(&self) -> bool {
+        Field::is_validating(self)
+    }
+
+    fn is_dirty(&self) -> bool {
+        Field::is_dirty(self)
+    }
+
+    fn touch(&self) {
+        Field::touch(self)
+    }
+
+    fn reset(&self) {
+        Field::reset(self)
+    }
+
+    fn commit(&self) {
+        Field::commit(self)
+    }
+
+    fn view(&self) -> Option<ViewId> {
+        self.view.get_untracked()
+    }
+}
+
+/// A group of [fields](Field) that are submitted together. See the
+/// [module documentation](self).
+#[derive(Clone, Copy)]
+pub struct Form {
+    fields: RwSignal<Vec<Rc<dyn FormField>>>,
+    on_submit: RwSignal<Option<Rc<dyn Fn()>>>,
+    /// Set when the form is submitted, until the asynchronous validators are done.
+    submitting: RwSignal<bool>,
+}
+
+impl Default for Form {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Form {
+    /// Creates a form without fields.
+    pub fn new() -> Self {
+        let form = Form {
+            fields: create_rw_signal(Vec::new()),
+            on_submit: create_rw_signal(None),
+            submitting: create_rw_signal(false),
+        };
+        create_effect(move |_| {
+            if form.submitting.get() && !form.is_validating() {
+                form.submitting.set(false);
+                untrack(|| form.finish_submit());
+            }
+        });
+        form
+    }
+
+    /// Creates a field of the form, which starts with the `initial` value.
+    pub fn field<T: Clone + PartialEq + 'static>(&self, initial: T) -> Field<T> {
+        let field = Field::new(initial);
+        self.add(field);
+        field
+    }
+
+    /// Adds a field that was created on its own to the form.
+    pub fn add<T: Clone + PartialEq + 'static>(&self, field: Field<T>) {
+        self.fields.update(|fields| fields.push(Rc::new(field)));
+    }
+
+    /// Sets the handler that runs when the form is submitted and all of its fields are valid.
+    pub fn on_submit(self, on_submit: impl Fn() + 'static) -> Self {
+        self.on_submit.set(Some(Rc::new(on_submit)));
+        self
+    }
+
+    /// Touches and validates all the fields. Once the asynchronous validators are done, this
+    /// runs the submit handler if all the fields are valid, and focuses the view of the first
+    /// invalid field otherwise.
+    pub fn submit(&self) {
+        for field in self.fields.get_untracked() {
+            field.touch();
+            field.validate();
+        }
+        self.submitting.set(true);
+    }
+
+    fn finish_submit(&self) {
+        let fields = self.fields.get_untracked();
+        match fields.iter().find(|field| !field.is_valid()) {
+            Some(field) => {
+                if let Some(view) = field.view() {
+                    view.request_focus();
+                    view.scroll_to(None);
+                }
+            }
+            None => {
+                if let Some(on_submit) = self.on_submit.get_untracked() {
+                    on_submit();
+                }
+            }
+        }
+    }
+
+    /// Returns `true` if all the fields are valid.
+    pub fn is_valid(&self) -> bool {
+        self.fields
+            .with(|fields| fields.iter().all(|field| field.is_valid()))
+    }
+
+    /// Returns `true` while an asynchronous validator of a field is running.
+    pub fn is_validating(&self) -> bool {
+        self.fields
+            .with(|fields| fields.iter().any(|field| field.is_validating()))
+    }
+
+    /// Returns `true` while the form is waiting for asynchronous validators to submit.
+    pub fn is_submitting(&self) -> bool {
+        self.submitting.get()
+    }
+
+    /// Returns `true` if any field differs from its initial value.
+    pub fn is_dirty(&self) -> bool {
+        self.fields
+            .with(|fields| fields.iter().any(|field| field.is_dirty()))
+    }
+
+    /// Puts back the initial values of all the fields, and marks them as untouched.
+    pub fn reset(&self) {
+        for field in self.fields.get_untracked() {
+            field.reset();
+        }
+    }
+
+    /// Makes the current values of all the fields their initial values, like after the form is
+    /// saved.
+    pub fn commit(&self) {
+        for field in self.fields.get_untracked() {
+            field.commit();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cell::Cell;
+
+    use super::*;
+
+    fn required(value: &String) -> Result<(), String> {
+        if value.is_empty() {
+            Err("Required".to_string())
+        } else {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn fields_validate_when_their_value_changes() {
+        let field = Field::new(String::new())
+            .validator(required)
+            .validator(|value: &String| match value.len() > 3 {
+                true => Err("Too long".to_string()),
+                false => Ok(()),
+            });
+        assert_eq!(field.error().as_deref(), Some("Required"));
+        assert!(!field.shows_error());
+        assert!(!field.is_dirty());
+
+        field.value().set("abcd".to_string());
+        assert_eq!(field.error().as_deref(), Some("Too long"));
+        assert!(field.is_dirty());
+
+        field.touch();
+        assert!(field.shows_error());
+        field.value().set("abc".to_string());
+        assert!(field.is_valid());
+        assert!(!field.shows_error());
+
+        field.commit();
+        assert!(!field.is_dirty());
+        field.value().set(String::new());
+        field.reset();
+        assert_eq!(field.value().get(), "abc");
+        assert!(!field.is_touched());
+    }
+
+    #[test]
+    fn submit_runs_only_when_valid() {
+        let submitted = Rc::new(Cell::new(0));
+        let form = Form::new();
+        let name = form.field(String::new()).validator(required);
+        let agree = form.field(false);
+        let form = {
+            let submitted = submitted.clone();
+            form.on_submit(move || submitted.set(submitted.get() + 1))
+        };
+
+        form.submit();
+        assert_eq!(submitted.get(), 0);
+        assert!(name.shows_error());
+        assert!(agree.is_touched());
+        assert!(!form.is_valid());
+
+        name.value().set("Floem".to_string());
+        assert!(form.is_valid());
+        assert!(form.is_dirty());
+        form.submit();
+        assert_eq!(submitted.get(), 1);
+
+        form.commit();
+        assert!(!form.is_dirty());
+    }
+}
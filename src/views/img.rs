@@ -1,13 +1,40 @@
-use std::sync::Arc;
+use std::{
+    cell::RefCell,
+    io::Cursor,
+    path::PathBuf,
+    rc::Rc,
+    sync::{Arc, RwLock},
+};
 
-use floem_reactive::create_effect;
-use peniko::Blob;
+#[cfg(not(target_arch = "wasm32"))]
+use std::time::{Duration, Instant};
+#[cfg(target_arch = "wasm32")]
+use web_time::{Duration, Instant};
+
+use floem_reactive::{create_effect, with_scope, Scope};
+use image::{AnimationDecoder, ImageFormat};
+use indexmap::IndexMap;
+use peniko::{
+    kurbo::{Rect, Size},
+    Blob,
+};
 use sha2::{Digest, Sha256};
 
-use crate::{id::ViewId, style::Style, unit::UnitExt, view::View, Renderer};
+use crate::{
+    action::exec_after,
+    clock,
+    context::UpdateCx,
+    ext_event::{create_ext_action, spawn_background},
+    id::ViewId,
+    style::Style,
+    unit::UnitExt,
+    view::{AnyView, IntoView, View},
+    Renderer,
+};
 
 use taffy::tree::NodeId;
 
+#[derive(Debug, Clone, Copy, PartialEq)]
 pub struct ImageStyle {
     fit: ObjectFit,
     position: ObjectPosition,
@@ -15,6 +42,7 @@ pub struct ImageStyle {
 
 /// How the content of a replaced element, such as an img or video, should be resized to fit its container.
 /// See <https://developer.mozilla.org/en-US/docs/Web/CSS/object-fit>.
+#[derive(Debug, Clone, Copy, PartialEq)]
 pub enum ObjectFit {
     /// The replaced content is sized to fill the element's content box.
     /// The entire object will completely fill the box.
@@ -37,29 +65,40 @@ pub enum ObjectFit {
 ///
 /// Areas of the box which aren't covered by the replaced element's object will show the element's background.
 /// See <https://developer.mozilla.org/en-US/docs/Web/CSS/object-position>.
+#[derive(Debug, Clone, Copy, PartialEq)]
 pub struct ObjectPosition {
-    #[allow(unused)]
     horiz: HorizPosition,
-    #[allow(unused)]
     vert: VertPosition,
 }
 
+/// The horizontal alignment of an [`ObjectPosition`]. Pixels are from the left edge, and a
+/// percentage aligns that point of the content with the same point of the box.
+#[derive(Debug, Clone, Copy, PartialEq)]
 pub enum HorizPosition {
-    Top,
+    Left,
     Center,
-    Bot,
+    Right,
     Px(f64),
     Pct(f64),
 }
 
+/// The vertical alignment of an [`ObjectPosition`]. Pixels are from the top edge, and a
+/// percentage aligns that point of the content with the same point of the box.
+#[derive(Debug, Clone, Copy, PartialEq)]
 pub enum VertPosition {
-    Left,
+    Top,
     Center,
-    Right,
+    Bot,
     Px(f64),
     Pct(f64),
 }
 
+impl ObjectPosition {
+    pub fn new(vert: VertPosition, horiz: HorizPosition) -> Self {
+        Self { horiz, vert }
+    }
+}
+
 impl ImageStyle {
     pub const BASE: Self = ImageStyle {
         position: ObjectPosition {
@@ -78,23 +117,343 @@ impl ImageStyle {
         self.position = obj_pos;
         self
     }
+
+    /// The rect that an image with the `natural` size is drawn in, inside the content `rect`.
+    fn object_rect(&self, natural: Size, rect: Rect) -> Rect {
+        let size = if natural.is_empty() {
+            rect.size()
+        } else {
+            let contain = (rect.width() / natural.width).min(rect.height() / natural.height);
+            let cover = (rect.width() / natural.width).max(rect.height() / natural.height);
+            match self.fit {
+                ObjectFit::Fill => rect.size(),
+                ObjectFit::Contain => natural * contain,
+                ObjectFit::Cover => natural * cover,
+                ObjectFit::ScaleDown => natural * contain.min(1.0),
+                ObjectFit::None => natural,
+            }
+        };
+        let free = rect.size() - size;
+        let x = match self.position.horiz {
+            HorizPosition::Left => 0.0,
+            HorizPosition::Center => free.width / 2.0,
+            HorizPosition::Right => free.width,
+            HorizPosition::Px(px) => px,
+            HorizPosition::Pct(pct) => free.width * pct / 100.0,
+        };
+        let y = match self.position.vert {
+            VertPosition::Top => 0.0,
+            VertPosition::Center => free.height / 2.0,
+            VertPosition::Bot => free.height,
+            VertPosition::Px(px) => px,
+            VertPosition::Pct(pct) => free.height * pct / 100.0,
+        };
+        Rect::from_origin_size((rect.x0 + x, rect.y0 + y), size)
+    }
+}
+
+/// GIFs commonly have a delay of zero, which browsers play at this speed.
+const DEFAULT_FRAME_DELAY: Duration = Duration::from_millis(100);
+const MIN_FRAME_DELAY: Duration = Duration::from_millis(20);
+
+/// The decoded frames of an image. Only animated images have more than one frame.
+struct DecodedImage {
+    frames: Vec<ImageFrame>,
+    /// The number of bytes of the decoded pixels.
+    size: usize,
+}
+
+struct ImageFrame {
+    image: peniko::Image,
+    hash: Vec<u8>,
+    delay: Duration,
+}
+
+impl ImageFrame {
+    fn new(image: peniko::Image, delay: Duration) -> Self {
+        let hash = Sha256::digest(image.data.data()).to_vec();
+        Self { image, hash, delay }
+    }
+
+    fn from_rgba(buffer: image::RgbaImage, delay: Duration) -> Self {
+        let (width, height) = buffer.dimensions();
+        let blob = Blob::new(Arc::new(buffer.into_raw()));
+        let image = peniko::Image::new(blob, peniko::Format::Rgba8, width, height);
+        Self::new(image, delay)
+    }
+
+    /// How long the frame is shown in an animation.
+    fn duration(&self) -> Duration {
+        if self.delay < MIN_FRAME_DELAY {
+            DEFAULT_FRAME_DELAY
+        } else {
+            self.delay
+        }
+    }
+}
+
+impl DecodedImage {
+    fn new(frames: Vec<ImageFrame>) -> Self {
+        let size = frames
+            .iter()
+            .map(|frame| frame.image.data.data().len())
+            .sum();
+        Self { frames, size }
+    }
+}
+
+/// Decodes all the frames of an image. This can be slow, so it's done off the UI thread for
+/// images that are loaded asynchronously.
+fn decode_image(data: &[u8]) -> Result<DecodedImage, String> {
+    let frames = match decode_animation(data).map_err(|err| err.to_string())? {
+        Some(frames) if !frames.is_empty() => frames
+            .into_iter()
+            .map(|frame| {
+                let (numer, denom) = frame.delay().numer_denom_ms();
+                let delay = Duration::from_millis(u64::from(numer.checked_div(denom).unwrap_or(0)));
+                ImageFrame::from_rgba(frame.into_buffer(), delay)
+            })
+            .collect(),
+        _ => {
+            let image = image::load_from_memory(data).map_err(|err| err.to_string())?;
+            vec![ImageFrame::from_rgba(image.into_rgba8(), Duration::ZERO)]
+        }
+    };
+    Ok(DecodedImage::new(frames))
+}
+
+/// Decodes the frames of an animated GIF, WebP or PNG, or returns `None` if the image isn't
+/// animated.
+fn decode_animation(data: &[u8]) -> image::ImageResult<Option<Vec<image::Frame>>> {
+    match image::guess_format(data)? {
+        #[cfg(feature = "image-gif")]
+        ImageFormat::Gif => {
+            let decoder = image::codecs::gif::GifDecoder::new(Cursor::new(data))?;
+            decoder.into_frames().collect_frames().map(Some)
+        }
+        #[cfg(feature = "image-webp")]
+        ImageFormat::WebP => {
+            let decoder = image::codecs::webp::WebPDecoder::new(Cursor::new(data))?;
+            if !decoder.has_animation() {
+                return Ok(None);
+            }
+            decoder.into_frames().collect_frames().map(Some)
+        }
+        #[cfg(feature = "image-png")]
+        ImageFormat::Png => {
+            let mut decoder = image::codecs::png::PngDecoder::new(Cursor::new(data))?;
+            if !decoder.is_apng()? {
+                return Ok(None);
+            }
+            decoder.apng()?.into_frames().collect_frames().map(Some)
+        }
+        _ => Ok(None),
+    }
+}
+
+const DEFAULT_CACHE_CAPACITY: usize = 128 * 1024 * 1024;
+
+/// Decoded images by their source, least recently used first.
+struct ImageCache {
+    images: IndexMap<String, Rc<DecodedImage>>,
+    size: usize,
+    capacity: usize,
+}
+
+impl ImageCache {
+    fn new(capacity: usize) -> Self {
+        Self {
+            images: IndexMap::new(),
+            size: 0,
+            capacity,
+        }
+    }
+
+    fn get(&mut self, key: &str) -> Option<Rc<DecodedImage>> {
+        let image = self.images.shift_remove(key)?;
+        self.images.insert(key.to_string(), image.clone());
+        Some(image)
+    }
+
+    fn insert(&mut self, key: String, image: Rc<DecodedImage>) {
+        if let Some(old) = self.images.shift_remove(&key) {
+            self.size -= old.size;
+        }
+        if image.size > self.capacity {
+            return;
+        }
+        self.size += image.size;
+        self.images.insert(key, image);
+        self.evict();
+    }
+
+    fn evict(&mut self) {
+        while self.size > self.capacity {
+            let Some((_, image)) = self.images.shift_remove_index(0) else {
+                break;
+            };
+            self.size -= image.size;
+        }
+    }
+}
+
+thread_local! {
+    /// The decoded images that are shared by the image views.
+    static IMAGE_CACHE: RefCell<ImageCache> = RefCell::new(ImageCache::new(DEFAULT_CACHE_CAPACITY));
+}
+
+/// Sets the number of bytes of decoded pixels that are kept for images that are shown again.
+/// The default is 128 MiB.
+pub fn set_image_cache_capacity(bytes: usize) {
+    IMAGE_CACHE.with_borrow_mut(|cache| {
+        cache.capacity = bytes;
+        cache.evict();
+    });
+}
+
+type UriLoader = dyn Fn(&str) -> Result<Vec<u8>, String> + Send + Sync;
+
+static URI_LOADER: RwLock<Option<Arc<UriLoader>>> = RwLock::new(None);
+
+/// Sets the function that [`img_from_uri`] uses to load URIs other than `file://` ones, such as
+/// `https://` URIs. It's called on a background thread.
+pub fn set_uri_loader(loader: impl Fn(&str) -> Result<Vec<u8>, String> + Send + Sync + 'static) {
+    *URI_LOADER.write().unwrap() = Some(Arc::new(loader));
+}
+
+fn load_path(path: &str) -> Result<Vec<u8>, String> {
+    std::fs::read(path).map_err(|err| format!("{path}: {err}"))
+}
+
+fn load_uri(uri: &str) -> Result<Vec<u8>, String> {
+    if let Some(path) = uri.strip_prefix("file://") {
+        return load_path(path);
+    }
+    let loader = URI_LOADER.read().unwrap().clone();
+    match loader {
+        Some(loader) => loader(uri),
+        None => Err(format!("{uri}: no loader is set for this URI")),
+    }
+}
+
+enum ImgUpdate {
+    Loading(u64),
+    Loaded(u64, Rc<DecodedImage>),
+    Failed(u64, String),
+    NextFrame(u64),
 }
 
 pub struct Img {
     id: ViewId,
-    img: Option<peniko::Image>,
-    img_hash: Option<Vec<u8>>,
+    image: Option<Rc<DecodedImage>>,
+    frame: usize,
+    /// The time of the animation [clock](crate::clock) when the frame was shown.
+    frame_shown_at: Instant,
+    /// Increases every time the source of the image changes, so that a load that finishes after
+    /// a newer one started is ignored.
+    generation: u64,
+    loading: bool,
+    error: Option<String>,
     content_node: Option<NodeId>,
+    style: ImageStyle,
+    placeholder: Option<Box<dyn Fn() -> AnyView>>,
+    error_view: Option<Box<dyn Fn(String) -> AnyView>>,
+    scope: Scope,
+    child_scope: Option<Scope>,
 }
 
+/// An image decoded from the bytes returned by `image`.
+///
+/// The bytes are hashed and decoded on a background thread, or right away on wasm, which has no
+/// threads, and the [placeholder](Img::placeholder) is shown meanwhile. Animated GIF, WebP and
+/// PNG images play through their frames. The decoded image is cached by the hash of its bytes,
+/// so showing the same bytes again doesn't decode them again.
 pub fn img(image: impl Fn() -> Vec<u8> + 'static) -> Img {
-    let image = image::load_from_memory(&image()).ok();
-    let width = image.as_ref().map_or(0, |img| img.width());
-    let height = image.as_ref().map_or(0, |img| img.height());
-    let data = Arc::new(image.map_or(Default::default(), |img| img.into_rgba8().into_vec()));
-    let blob = Blob::new(data);
-    let image = peniko::Image::new(blob, peniko::Format::Rgba8, width, height);
-    img_dynamic(move || image.clone())
+    let id = ViewId::new();
+    // The image is dropped if it loads after the scope of the view is disposed.
+    let cx = Scope::current();
+    create_effect(move |generation: Option<u64>| {
+        let generation = generation.map_or(0, |generation| generation + 1);
+        let data = image();
+        id.update_state(ImgUpdate::Loading(generation));
+
+        let decoded = create_ext_action(cx, move |(key, result): (String, Result<_, _>)| {
+            id.update_state(loaded(generation, key, result));
+        });
+        let hashed = create_ext_action(cx, move |(key, data): (String, Vec<u8>)| {
+            if let Some(image) = IMAGE_CACHE.with_borrow_mut(|cache| cache.get(&key)) {
+                id.update_state(ImgUpdate::Loaded(generation, image));
+                return;
+            }
+            spawn_background(move || {
+                let result = decode_image(&data);
+                decoded((key, result));
+            });
+        });
+        spawn_background(move || {
+            let key = format!("{:x}", Sha256::digest(&data));
+            hashed((key, data));
+        });
+        generation
+    });
+    Img::new(id)
+}
+
+/// The update of a view whose image finished loading, caching the image.
+fn loaded(generation: u64, key: String, result: Result<DecodedImage, String>) -> ImgUpdate {
+    match result {
+        Ok(image) => {
+            let image = Rc::new(image);
+            IMAGE_CACHE.with_borrow_mut(|cache| cache.insert(key, image.clone()));
+            ImgUpdate::Loaded(generation, image)
+        }
+        Err(err) => ImgUpdate::Failed(generation, err),
+    }
+}
+
+/// An image that's read from the file at `path` and decoded on a background thread, or right
+/// away on wasm, which has no threads.
+///
+/// The [placeholder](Img::placeholder) is shown while the image loads, and the
+/// [error view](Img::error_view) if it fails to load. Decoded images are cached by their path.
+pub fn img_from_path(path: impl Fn() -> PathBuf + 'static) -> Img {
+    img_async(move || path().to_string_lossy().into_owned(), load_path)
+}
+
+/// An image that's loaded from `uri` and decoded on a background thread.
+///
+/// `file://` URIs are read from the file system, and other URIs are loaded by the function set
+/// with [`set_uri_loader`]. Otherwise this works like [`img_from_path`].
+pub fn img_from_uri(uri: impl Fn() -> String + 'static) -> Img {
+    img_async(uri, load_uri)
+}
+
+fn img_async(
+    source: impl Fn() -> String + 'static,
+    load: fn(&str) -> Result<Vec<u8>, String>,
+) -> Img {
+    let id = ViewId::new();
+    // The image is dropped if it loads after the scope of the view is disposed.
+    let cx = Scope::current();
+    create_effect(move |generation: Option<u64>| {
+        let generation = generation.map_or(0, |generation| generation + 1);
+        let key = source();
+        if let Some(image) = IMAGE_CACHE.with_borrow_mut(|cache| cache.get(&key)) {
+            id.update_state(ImgUpdate::Loaded(generation, image));
+            return generation;
+        }
+
+        id.update_state(ImgUpdate::Loading(generation));
+        let send = create_ext_action(cx, move |(key, result)| {
+            id.update_state(loaded(generation, key, result));
+        });
+        spawn_background(move || {
+            let result = load(&key).and_then(|data| decode_image(&data));
+            send((key, result));
+        });
+        generation
+    });
+    Img::new(id)
 }
 
 pub(crate) fn img_dynamic(image: impl Fn() -> peniko::Image + 'static) -> Img {
@@ -102,11 +461,124 @@ pub(crate) fn img_dynamic(image: impl Fn() -> peniko::Image + 'static) -> Img {
     create_effect(move |_| {
         id.update_state(image());
     });
-    Img {
-        id,
-        img: None,
-        img_hash: None,
-        content_node: None,
+    Img::new(id)
+}
+
+impl Img {
+    fn new(id: ViewId) -> Self {
+        Img {
+            id,
+            image: None,
+            frame: 0,
+            frame_shown_at: clock::now(),
+            generation: 0,
+            loading: false,
+            error: None,
+            content_node: None,
+            style: ImageStyle::BASE,
+            placeholder: None,
+            error_view: None,
+            scope: Scope::current(),
+            child_scope: None,
+        }
+    }
+
+    /// Sets how the image is resized to fit the content box of the view.
+    pub fn object_fit(mut self, fit: ObjectFit) -> Self {
+        self.style = self.style.fit(fit);
+        self
+    }
+
+    /// Sets where the image is placed within the content box of the view.
+    pub fn object_position(mut self, vert: VertPosition, horiz: HorizPosition) -> Self {
+        self.style = self.style.object_pos(ObjectPosition::new(vert, horiz));
+        self
+    }
+
+    /// Sets the view that's shown while the image loads.
+    pub fn placeholder<V: IntoView + 'static>(mut self, view: impl Fn() -> V + 'static) -> Self {
+        self.placeholder = Some(Box::new(move || view().into_any()));
+        self
+    }
+
+    /// Sets the view that's shown with the error message if the image fails to load.
+    pub fn error_view<V: IntoView + 'static>(
+        mut self,
+        view: impl Fn(String) -> V + 'static,
+    ) -> Self {
+        self.error_view = Some(Box::new(move |err| view(err).into_any()));
+        self
+    }
+
+    /// Replaces the placeholder or error view according to the state of the image.
+    fn update_children(&mut self, cx: &mut UpdateCx) {
+        for child in self.id.children() {
+            cx.app_state_mut().remove_view(child);
+        }
+        if let Some(scope) = self.child_scope.take() {
+            scope.dispose();
+        }
+
+        let scope = self.scope.create_child();
+        let child = with_scope(scope, || {
+            if self.image.is_some() {
+                None
+            } else if let Some(err) = &self.error {
+                self.error_view.as_ref().map(|view| view(err.clone()))
+            } else if self.loading {
+                self.placeholder.as_ref().map(|view| view())
+            } else {
+                None
+            }
+        });
+        match child {
+            Some(child) => {
+                self.id.set_children(vec![child]);
+                self.child_scope = Some(scope);
+            }
+            None => {
+                self.id.set_children(Vec::new());
+                scope.dispose();
+            }
+        }
+        self.id.request_all();
+    }
+
+    /// Schedules the next frame of an animated image on the animation [clock](crate::clock), so
+    /// that animated images pause and step along with the other animations.
+    fn schedule_next_frame(&self) {
+        let Some(image) = &self.image else {
+            return;
+        };
+        if image.frames.len() < 2 {
+            return;
+        }
+        let shown_for = clock::now().saturating_duration_since(self.frame_shown_at);
+        let delay = image.frames[self.frame]
+            .duration()
+            .saturating_sub(shown_for);
+        let id = self.id;
+        let generation = self.generation;
+        exec_after(delay, move |_| {
+            id.update_state(ImgUpdate::NextFrame(generation));
+        });
+    }
+
+    /// Shows the frame that's due at the current time of the clock, skipping the frames that
+    /// were due while the clock was stepped or sped up.
+    fn advance_frames(&mut self) {
+        let Some(image) = &self.image else {
+            return;
+        };
+        let now = clock::now();
+        loop {
+            let duration = image.frames[self.frame].duration();
+            if now.saturating_duration_since(self.frame_shown_at) < duration {
+                break;
+            }
+            self.frame_shown_at += duration;
+            self.frame = (self.frame + 1) % image.frames.len();
+        }
     }
 }
 
@@ -119,19 +591,66 @@ impl View for Img {
         "Img".into()
     }
 
-    fn update(&mut self, _cx: &mut crate::context::UpdateCx, state: Box<dyn std::any::Any>) {
-        if let Ok(img) = state.downcast::<peniko::Image>() {
-            let mut hasher = Sha256::new();
-            hasher.update(img.data.data());
-            self.img_hash = Some(hasher.finalize().to_vec());
-
-            self.img = Some(*img);
-            self.id.request_layout();
+    fn update(&mut self, cx: &mut UpdateCx, state: Box<dyn std::any::Any>) {
+        let state = match state.downcast::<peniko::Image>() {
+            Ok(img) => {
+                let image = DecodedImage::new(vec![ImageFrame::new(*img, Duration::ZERO)]);
+                let generation = self.generation;
+                Box::new(ImgUpdate::Loaded(generation, Rc::new(image)))
+            }
+            Err(state) => state,
+        };
+        let Ok(update) = state.downcast::<ImgUpdate>() else {
+            return;
+        };
+        match *update {
+            ImgUpdate::Loading(generation) if generation >= self.generation => {
+                self.generation = generation;
+                self.image = None;
+                self.error = None;
+                self.loading = true;
+                self.update_children(cx);
+            }
+            ImgUpdate::Loaded(generation, image) if generation >= self.generation => {
+                self.generation = generation;
+                self.image = Some(image);
+                self.frame = 0;
+                self.frame_shown_at = clock::now();
+                self.error = None;
+                self.loading = false;
+                self.update_children(cx);
+                self.schedule_next_frame();
+            }
+            ImgUpdate::Failed(generation, err) if generation >= self.generation => {
+                self.generation = generation;
+                self.image = None;
+                self.error = Some(err);
+                self.loading = false;
+                self.update_children(cx);
+            }
+            ImgUpdate::NextFrame(generation) if generation == self.generation => {
+                if self.image.is_some() {
+                    self.advance_frames();
+                    self.id.request_paint();
+                    self.schedule_next_frame();
+                }
+            }
+            _ => {}
         }
     }
 
     fn layout(&mut self, cx: &mut crate::context::LayoutCx) -> taffy::tree::NodeId {
-        cx.layout_node(self.id(), true, |_cx| {
+        cx.layout_node(self.id(), true, |cx| {
+            if self.image.is_none() {
+                // The placeholder or error view, if there is one.
+                return self
+                    .id
+                    .children()
+                    .into_iter()
+                    .map(|child| child.view().borrow_mut().layout(cx))
+                    .collect();
+            }
+
             if self.content_node.is_none() {
                 self.content_node = Some(
                     self.id
@@ -144,9 +663,9 @@ impl View for Img {
             let content_node = self.content_node.unwrap();
 
             let (width, height) = self
-                .img
+                .image
                 .as_ref()
-                .map(|img| (img.width, img.height))
+                .map(|image| (image.frames[0].image.width, image.frames[0].image.height))
                 .unwrap_or((0, 0));
 
             let style = Style::new()
@@ -160,15 +679,145 @@ impl View for Img {
     }
 
     fn paint(&mut self, cx: &mut crate::context::PaintCx) {
-        if let Some(ref img) = self.img {
-            let rect = self.id.get_content_rect();
-            cx.draw_img(
-                floem_renderer::Img {
-                    img: img.clone(),
-                    hash: self.img_hash.as_ref().unwrap(),
-                },
-                rect,
-            );
+        let Some(image) = &self.image else {
+            cx.paint_children(self.id);
+            return;
+        };
+        let frame = &image.frames[self.frame];
+        let rect = self.id.get_content_rect();
+        let natural = Size::new(frame.image.width as f64, frame.image.height as f64);
+        let target = self.style.object_rect(natural, rect);
+
+        cx.save();
+        cx.clip(&rect);
+        cx.draw_img(
+            floem_renderer::Img {
+                img: frame.image.clone(),
+                hash: &frame.hash,
+            },
+            target,
+        );
+        cx.restore();
+    }
+}
+
+impl Drop for Img {
+    fn drop(&mut self) {
+        if let Some(scope) = self.child_scope.take() {
+            scope.dispose();
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn decoded(size: usize) -> Rc<DecodedImage> {
+        Rc::new(DecodedImage {
+            frames: Vec::new(),
+            size,
+        })
+    }
+
+    #[test]
+    fn cache_evicts_least_recently_used() {
+        let mut cache = ImageCache::new(10);
+        cache.insert("a".to_string(), decoded(4));
+        cache.insert("b".to_string(), decoded(4));
+        assert!(cache.get("a").is_some());
+
+        cache.insert("c".to_string(), decoded(4));
+        assert!(cache.get("b").is_none());
+        assert!(cache.get("a").is_some());
+        assert!(cache.get("c").is_some());
+        assert_eq!(cache.size, 8);
+
+        // Images that don't fit aren't cached, and don't evict anything.
+        cache.insert("d".to_string(), decoded(11));
+        assert!(cache.get("d").is_none());
+        assert_eq!(cache.size, 8);
+    }
+
+    #[test]
+    fn frames_follow_the_clock() {
+        let frame = |millis| {
+            ImageFrame::from_rgba(image::RgbaImage::new(1, 1), Duration::from_millis(millis))
+        };
+        let mut img = Img::new(ViewId::new());
+        // A delay of zero is played at the default speed.
+        img.image = Some(Rc::new(DecodedImage::new(vec![
+            frame(50),
+            frame(0),
+            frame(30),
+        ])));
+
+        clock::pause();
+        img.frame_shown_at = clock::now();
+        clock::advance(Duration::from_millis(40));
+        img.advance_frames();
+        assert_eq!(img.frame, 0);
+
+        clock::advance(Duration::from_millis(20));
+        img.advance_frames();
+        assert_eq!(img.frame, 1);
+
+        // Frames that were due while the clock jumped ahead are skipped.
+        clock::advance(Duration::from_millis(130));
+        img.advance_frames();
+        assert_eq!(img.frame, 0);
+        clock::reset();
+    }
+
+    #[test]
+    fn object_rects() {
+        let rect = Rect::new(0.0, 0.0, 200.0, 100.0);
+        let natural = Size::new(50.0, 50.0);
+        let style = ImageStyle::BASE;
+
+        assert_eq!(style.object_rect(natural, rect), rect);
+        assert_eq!(
+            style.fit(ObjectFit::Contain).object_rect(natural, rect),
+            Rect::new(50.0, 0.0, 150.0, 100.0)
+        );
+        assert_eq!(
+            style.fit(ObjectFit::Cover).object_rect(natural, rect),
+            Rect::new(0.0, -50.0, 200.0, 150.0)
+        );
+        assert_eq!(
+            style.fit(ObjectFit::ScaleDown).object_rect(natural, rect),
+            Rect::new(75.0, 25.0, 125.0, 75.0)
+        );
+        assert_eq!(
+            style
+                .fit(ObjectFit::None)
+                .object_pos(ObjectPosition::new(VertPosition::Bot, HorizPosition::Left))
+                .object_rect(natural, rect),
+            Rect::new(0.0, 50.0, 50.0, 100.0)
+        );
+        assert_eq!(
+            style
+                .fit(ObjectFit::Contain)
+                .object_pos(ObjectPosition::new(
+                    VertPosition::Top,
+                    HorizPosition::Pct(100.0)
+                ))
+                .object_rect(natural, rect),
+            Rect::new(100.0, 0.0, 200.0, 100.0)
+        );
+    }
+
+    #[test]
+    fn decode_still_image() {
+        let mut data = Vec::new();
+        image::RgbaImage::new(2, 1)
+            .write_to(&mut Cursor::new(&mut data), ImageFormat::Png)
+            .unwrap();
+        let image = decode_image(&data).unwrap();
+        assert_eq!(image.frames.len(), 1);
+        assert_eq!(image.frames[0].image.width, 2);
+        assert_eq!(image.size, 8);
+
+        assert!(decode_image(b"not an image").is_err());
+    }
+}
@@ -5,7 +5,8 @@
 //! ## Composing Views
 //! The views in this module are the main building blocks for composing UIs in Floem.
 //! There is a collection of different `stacks` and `lists` that can be used to build collections of Views.
-//! There are also basic widgets such as [text_inputs](text_input::text_input), [labels](label::label), [images](img::img), and [svgs](svg::svg).
+//! There are also basic widgets such as [text_inputs](text_input::text_input), [labels](label::label), [images](img::img), and [svgs](svg::svg),
+//! and a [canvas](canvas()) for custom drawing with a paint closure.
 //!
 //! ## Example: Counter
 //! ```rust
@@ -34,6 +35,8 @@
 //! Lists differ from stacks in that they also have built-in support for the selection of items: up and down using arrow keys, top and bottom control using the home and end keys, and for the "acceptance" of an item using the Enter key.
 //! You could build this manually yourself using stacks but it is common enough that it is built-in as a list.
 //!
+//! A [tree view](tree_view()) is a virtual list of hierarchical items that can be expanded and collapsed, with children loaded only when their parent is expanded.
+//!
 //! For the most direct documentation for Floem Views see the [Functions](#functions) section of this module documentation.
 
 //! # View and Widget Traits
@@ -93,6 +96,9 @@ pub use dyn_stack::*;
 mod svg;
 pub use svg::*;
 
+mod canvas;
+pub use canvas::*;
+
 mod clip;
 pub use clip::*;
 
@@ -120,6 +126,9 @@ pub use virtual_list::*;
 mod virtual_stack;
 pub use virtual_stack::*;
 
+mod tree_view;
+pub use tree_view::*;
+
 pub mod scroll;
 pub use scroll::{scroll, Scroll, ScrollExt};
 
@@ -129,6 +138,26 @@ pub use tab::*;
 mod tooltip;
 pub use tooltip::*;
 
+pub mod popover;
+pub use popover::{popover, popover_at, Placement, Popover, PopoverClass};
+
+pub mod dialog;
+pub use dialog::{dialog, Dialog, DialogClass, DialogId, DialogScrimClass};
+
+mod command_palette;
+pub use command_palette::*;
+
+pub mod split;
+pub use split::{split, split_from_iter, Split, SplitAxis, SplitDividerClass, SplitPaneClass};
+
+pub mod dock;
+pub use dock::{
+    dock, DockActiveTabClass, DockDropPreviewClass, DockGroupClass, DockInsertAfterClass,
+    DockInsertBeforeClass, DockNode, DockTabClass, DockTabStripClass, DropZone,
+};
+
+pub mod form;
+
 mod stack;
 pub use stack::*;
 
@@ -0,0 +1,590 @@
+//! Overlays that are positioned relative to an anchor.
+//!
+//! A [`Popover`] is placed on a [`Placement`] side of its anchor and keeps itself inside the
+//! window: it flips to the opposite side when there isn't enough room, and shifts along its
+//! side so it doesn't overflow the window edges. It follows the anchor when the anchor moves or
+//! is scrolled.
+//!
+//! ```rust
+//! # use floem::views::{popover, text, Decorators, Placement};
+//! let anchor = text("Open");
+//! let anchor_id = anchor.id();
+//! let open = move || {
+//!     popover(anchor_id, Placement::BOTTOM, || text("Hello"))
+//!         .arrow(true)
+//!         .light_dismiss(true)
+//!         .show()
+//! };
+//! ```
+
+use std::cell::RefCell;
+
+use peniko::kurbo::{BezPath, Point, Rect, Size, Vec2};
+
+use crate::{
+    action::{add_overlay, remove_overlay},
+    context::{ComputeLayoutCx, PaintCx, StyleCx, UpdateCx},
+    id::ViewId,
+    prop, prop_extractor,
+    style::{Background, Style},
+    style_class,
+    view::{default_compute_layout, IntoView, View},
+    views::Decorators,
+    window_tracking::window_inner_screen_bounds,
+};
+
+style_class!(pub PopoverClass);
+
+prop!(pub ArrowSize: f64 {} = 6.0);
+
+/// The minimum distance a popover keeps from the window edges.
+prop!(pub WindowMargin: f64 {} = 4.0);
+
+prop_extractor! {
+    PopoverStyle {
+        arrow_size: ArrowSize,
+        window_margin: WindowMargin,
+        background: Background,
+    }
+}
+
+/// The side of the anchor a popover is placed on.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Side {
+    Top,
+    Bottom,
+    Left,
+    Right,
+}
+
+impl Side {
+    pub fn opposite(self) -> Self {
+        match self {
+            Side::Top => Side::Bottom,
+            Side::Bottom => Side::Top,
+            Side::Left => Side::Right,
+            Side::Right => Side::Left,
+        }
+    }
+
+    fn is_vertical(self) -> bool {
+        matches!(self, Side::Top | Side::Bottom)
+    }
+}
+
+/// How a popover is aligned with its anchor along the side it's placed on.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Align {
+    /// Aligns the left or top edges.
+    Start,
+    Center,
+    /// Aligns the right or bottom edges.
+    End,
+}
+
+/// Where a popover is placed relative to its anchor.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Placement {
+    pub side: Side,
+    pub align: Align,
+}
+
+impl Placement {
+    pub const TOP: Self = Self::new(Side::Top, Align::Center);
+    pub const TOP_START: Self = Self::new(Side::Top, Align::Start);
+    pub const TOP_END: Self = Self::new(Side::Top, Align::End);
+    pub const BOTTOM: Self = Self::new(Side::Bottom, Align::Center);
+    pub const BOTTOM_START: Self = Self::new(Side::Bottom, Align::Start);
+    pub const BOTTOM_END: Self = Self::new(Side::Bottom, Align::End);
+    pub const LEFT: Self = Self::new(Side::Left, Align::Center);
+    pub const LEFT_START: Self = Self::new(Side::Left, Align::Start);
+    pub const LEFT_END: Self = Self::new(Side::Left, Align::End);
+    pub const RIGHT: Self = Self::new(Side::Right, Align::Center);
+    pub const RIGHT_START: Self = Self::new(Side::Right, Align::Start);
+    pub const RIGHT_END: Self = Self::new(Side::Right, Align::End);
+
+    pub const fn new(side: Side, align: Align) -> Self {
+        Self { side, align }
+    }
+
+    pub fn flipped(self) -> Self {
+        Self::new(self.side.opposite(), self.align)
+    }
+}
+
+/// The result of [`compute_popover_position`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PopoverPosition {
+    /// The top left corner of the popover.
+    pub origin: Point,
+    /// The placement that was used, which is flipped if the requested side didn't fit.
+    pub placement: Placement,
+    /// Where the arrow should point to along the edge of the popover facing the anchor,
+    /// relative to the popover's origin.
+    pub arrow_offset: f64,
+}
+
+/// Position a popover of `size` next to `anchor`, keeping it within `bounds` if possible.
+///
+/// `gap` is the distance between the anchor and the popover. The popover is moved to the
+/// opposite side of the anchor if it overflows `bounds` on the requested side and there is more
+/// room on the other side, and is then shifted along that side to stay within `bounds`.
+pub fn compute_popover_position(
+    anchor: Rect,
+    size: Size,
+    bounds: Rect,
+    placement: Placement,
+    gap: f64,
+) -> PopoverPosition {
+    let place = |placement: Placement| {
+        let cross = |start: f64, end: f64, len: f64| match placement.align {
+            Align::Start => start,
+            Align::Center => (start + end - len) / 2.0,
+            Align::End => end - len,
+        };
+        match placement.side {
+            Side::Top => Point::new(
+                cross(anchor.x0, anchor.x1, size.width),
+                anchor.y0 - gap - size.height,
+            ),
+            Side::Bottom => Point::new(cross(anchor.x0, anchor.x1, size.width), anchor.y1 + gap),
+            Side::Left => Point::new(
+                anchor.x0 - gap - size.width,
+                cross(anchor.y0, anchor.y1, size.height),
+            ),
+            Side::Right => Point::new(anchor.x1 + gap, cross(anchor.y0, anchor.y1, size.height)),
+        }
+    };
+    // How far the popover sticks out of `bounds` on the side facing away from the anchor.
+    let overflow = |side: Side, origin: Point| match side {
+        Side::Top => bounds.y0 - origin.y,
+        Side::Bottom => origin.y + size.height - bounds.y1,
+        Side::Left => bounds.x0 - origin.x,
+        Side::Right => origin.x + size.width - bounds.x1,
+    };
+
+    let mut placement = placement;
+    let mut origin = place(placement);
+    if overflow(placement.side, origin) > 0.0 {
+        let flipped = placement.flipped();
+        let flipped_origin = place(flipped);
+        if overflow(flipped.side, flipped_origin) < overflow(placement.side, origin) {
+            placement = flipped;
+            origin = flipped_origin;
+        }
+    }
+
+    // Shift along the side, preferring to show the start of the popover if it's too big.
+    let shift = |pos: f64, len: f64, min: f64, max: f64| pos.min(max - len).max(min);
+    if placement.side.is_vertical() {
+        origin.x = shift(origin.x, size.width, bounds.x0, bounds.x1);
+    } else {
+        origin.y = shift(origin.y, size.height, bounds.y0, bounds.y1);
+    }
+
+    let arrow_offset = if placement.side.is_vertical() {
+        anchor.center().x - origin.x
+    } else {
+        anchor.center().y - origin.y
+    };
+
+    PopoverPosition {
+        origin,
+        placement,
+        arrow_offset,
+    }
+}
+
+#[derive(Clone, Copy)]
+enum Anchor {
+    View(ViewId),
+    Point(Point),
+}
+
+impl Anchor {
+    /// The anchor rectangle in window coordinates.
+    fn rect(&self) -> Option<Rect> {
+        match self {
+            Anchor::View(id) => {
+                let size = id.get_size()?;
+                let origin = id.state().borrow().window_origin;
+                Some(size.to_rect().with_origin(origin))
+            }
+            Anchor::Point(point) => Some(Rect::from_origin_size(*point, Size::ZERO)),
+        }
+    }
+}
+
+struct PopoverEntry {
+    id: ViewId,
+    anchor: Anchor,
+    light_dismiss: bool,
+    close_on_escape: bool,
+}
+
+thread_local! {
+    /// The popovers that are shown, in the order they were opened.
+    static POPOVERS: RefCell<Vec<PopoverEntry>> = const { RefCell::new(Vec::new()) };
+}
+
+struct Dismiss;
+
+/// Dismiss the light-dismiss popovers of the window with the `root` view which don't contain
+/// `point`, given in window coordinates. Clicking a popover's anchor doesn't dismiss it, so the
+/// anchor can toggle it.
+pub(crate) fn light_dismiss_popovers(root: ViewId, point: Point) {
+    POPOVERS.with_borrow(|popovers| {
+        for entry in popovers.iter().rev() {
+            if !entry.light_dismiss || entry.id.root() != Some(root) {
+                continue;
+            }
+            let inside = |rect: Option<Rect>| rect.is_some_and(|rect| rect.contains(point));
+            if inside(Some(entry.id.layout_rect()))
+                || matches!(entry.anchor, Anchor::View(_)) && inside(entry.anchor.rect())
+            {
+                // Popovers opened from within this one stay open too.
+                break;
+            }
+            entry.id.update_state(Dismiss);
+        }
+    });
+}
+
+/// Dismiss the most recently opened popover of the window with the `root` view that closes on
+/// Escape. Returns `true` if there was one.
+pub(crate) fn escape_popover(root: ViewId) -> bool {
+    POPOVERS.with_borrow(|popovers| {
+        let entry = popovers
+            .iter()
+            .rev()
+            .find(|entry| entry.close_on_escape && entry.id.root() == Some(root));
+        if let Some(entry) = entry {
+            entry.id.update_state(Dismiss);
+            true
+        } else {
+            false
+        }
+    })
+}
+
+/// An overlay positioned next to an anchor. See the [module documentation](self) and
+/// [`popover`].
+pub struct Popover {
+    id: ViewId,
+    anchor: Anchor,
+    placement: Placement,
+    gap: f64,
+    arrow: bool,
+    light_dismiss: bool,
+    close_on_escape: bool,
+    on_dismiss: Option<Box<dyn Fn()>>,
+    overlay: Option<ViewId>,
+    position: Option<PopoverPosition>,
+    style: PopoverStyle,
+}
+
+/// Creates a [`Popover`] placed next to the `anchor` view.
+///
+/// The popover isn't visible until [`Popover::show`] is called.
+pub fn popover<V: IntoView + 'static>(
+    anchor: ViewId,
+    placement: Placement,
+    content: impl FnOnce() -> V,
+) -> Popover {
+    Popover::new(Anchor::View(anchor), placement, content().into_any())
+}
+
+/// Creates a [`Popover`] placed next to a point in window coordinates, such as the pointer
+/// position.
+///
+/// The popover isn't visible until [`Popover::show`] is called.
+pub fn popover_at<V: IntoView + 'static>(
+    point: Point,
+    placement: Placement,
+    content: impl FnOnce() -> V,
+) -> Popover {
+    Popover::new(Anchor::Point(point), placement, content().into_any())
+}
+
+impl Popover {
+    fn new(anchor: Anchor, placement: Placement, content: Box<dyn View>) -> Self {
+        let id = ViewId::new();
+        id.set_children(vec![content]);
+        Self {
+            id,
+            anchor,
+            placement,
+            gap: 0.0,
+            arrow: false,
+            light_dismiss: false,
+            close_on_escape: true,
+            on_dismiss: None,
+            overlay: None,
+            position: None,
+            style: Default::default(),
+        }
+        .class(PopoverClass)
+    }
+
+    /// Sets the distance between the anchor and the popover, not including the arrow.
+    pub fn gap(mut self, gap: f64) -> Self {
+        self.gap = gap;
+        self
+    }
+
+    /// Draws an arrow pointing at the anchor, filled with the popover's background.
+    /// Its size is set with the [`ArrowSize`] style property.
+    pub fn arrow(mut self, arrow: bool) -> Self {
+        self.arrow = arrow;
+        self
+    }
+
+    /// Dismiss the popover when the pointer is pressed outside of it and its anchor.
+    pub fn light_dismiss(mut self, light_dismiss: bool) -> Self {
+        self.light_dismiss = light_dismiss;
+        self
+    }
+
+    /// Dismiss the popover when Escape is pressed and the focused view doesn't handle it.
+    /// This is enabled by default.
+    pub fn close_on_escape(mut self, close_on_escape: bool) -> Self {
+        self.close_on_escape = close_on_escape;
+        self
+    }
+
+    /// Sets a callback that's run when the popover is dismissed by a light-dismiss click or
+    /// Escape. The popover is removed afterwards.
+    pub fn on_dismiss(mut self, on_dismiss: impl Fn() + 'static) -> Self {
+        self.on_dismiss = Some(Box::new(on_dismiss));
+        self
+    }
+
+    /// Shows the popover on the current window.
+    ///
+    /// Returns the id of the overlay, which can be passed to
+    /// [`remove_overlay`](crate::action::remove_overlay) to hide the popover.
+    pub fn show(mut self) -> ViewId {
+        POPOVERS.with_borrow_mut(|popovers| {
+            popovers.push(PopoverEntry {
+                id: self.id,
+                anchor: self.anchor,
+                light_dismiss: self.light_dismiss,
+                close_on_escape: self.close_on_escape,
+            })
+        });
+        add_overlay(Point::ZERO, move |overlay| {
+            self.overlay = Some(overlay);
+            self
+        })
+    }
+
+    fn arrow_size(&self) -> f64 {
+        if self.arrow {
+            self.style.arrow_size()
+        } else {
+            0.0
+        }
+    }
+
+    fn arrow_path(&self, position: &PopoverPosition, size: Size) -> BezPath {
+        let arrow_size = self.arrow_size();
+        let side = position.placement.side;
+        let edge_len = if side.is_vertical() {
+            size.width
+        } else {
+            size.height
+        };
+        // Keep the arrow on the edge even if the popover was shifted past the anchor.
+        let along = position
+            .arrow_offset
+            .clamp(arrow_size, (edge_len - arrow_size).max(arrow_size));
+        let (base, tip_direction, along_direction) = match side {
+            Side::Top => (
+                Point::new(along, size.height),
+                Vec2::new(0.0, 1.0),
+                Vec2::new(1.0, 0.0),
+            ),
+            Side::Bottom => (
+                Point::new(along, 0.0),
+                Vec2::new(0.0, -1.0),
+                Vec2::new(1.0, 0.0),
+            ),
+            Side::Left => (
+                Point::new(size.width, along),
+                Vec2::new(1.0, 0.0),
+                Vec2::new(0.0, 1.0),
+            ),
+            Side::Right => (
+                Point::new(0.0, along),
+                Vec2::new(-1.0, 0.0),
+                Vec2::new(0.0, 1.0),
+            ),
+        };
+        let mut path = BezPath::new();
+        path.move_to(base - along_direction * arrow_size);
+        path.line_to(base + tip_direction * arrow_size);
+        path.line_to(base + along_direction * arrow_size);
+        path.close_path();
+        path
+    }
+}
+
+impl View for Popover {
+    fn id(&self) -> ViewId {
+        self.id
+    }
+
+    fn debug_name(&self) -> std::borrow::Cow<'static, str> {
+        "Popover".into()
+    }
+
+    fn view_style(&self) -> Option<Style> {
+        let origin = self.position.map(|p| p.origin).unwrap_or_default();
+        Some(
+            Style::new()
+                .absolute()
+                .inset_left(origin.x)
+                .inset_top(origin.y),
+        )
+    }
+
+    fn update(&mut self, _cx: &mut UpdateCx, state: Box<dyn std::any::Any>) {
+        if state.downcast::<Dismiss>().is_ok() {
+            if let Some(on_dismiss) = &self.on_dismiss {
+                on_dismiss();
+            }
+            if let Some(overlay) = self.overlay.take() {
+                remove_overlay(overlay);
+            }
+        }
+    }
+
+    fn style_pass(&mut self, cx: &mut StyleCx<'_>) {
+        if self.style.read(cx) {
+            cx.app_state_mut().request_paint(self.id);
+        }
+        for child in self.id.children() {
+            cx.style_view(child);
+        }
+    }
+
+    fn compute_layout(&mut self, cx: &mut ComputeLayoutCx) -> Option<Rect> {
+        let anchor = self.anchor.rect();
+        let size = self.id.get_size().unwrap_or_default();
+        let scale = cx.app_state().scale;
+        let window_size = self
+            .id
+            .window_id()
+            .and_then(|window_id| window_inner_screen_bounds(&window_id))
+            .map(|bounds| bounds.size())
+            .unwrap_or(cx.app_state().root_size);
+        let bounds = (window_size / scale)
+            .to_rect()
+            .inset(-self.style.window_margin());
+
+        let position = anchor.map(|anchor| {
+            compute_popover_position(
+                anchor,
+                size,
+                bounds,
+                self.placement,
+                self.gap + self.arrow_size(),
+            )
+        });
+        if position != self.position {
+            // The popover is positioned by its style, so it needs to be styled and laid out
+            // again. This converges since the position doesn't affect the size.
+            self.position = position;
+            self.id.request_style();
+            self.id.request_layout();
+            self.id.request_paint();
+        }
+
+        default_compute_layout(self.id, cx)
+    }
+
+    fn paint(&mut self, cx: &mut PaintCx) {
+        cx.paint_children(self.id);
+        if let (true, Some(position), Some(background)) =
+            (self.arrow, self.position, self.style.background())
+        {
+            let size = self.id.get_size().unwrap_or_default();
+            cx.fill(&self.arrow_path(&position, size), &background, 0.0);
+        }
+    }
+}
+
+impl Drop for Popover {
+    fn drop(&mut self) {
+        let id = self.id;
+        POPOVERS.with_borrow_mut(|popovers| popovers.retain(|entry| entry.id != id));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const BOUNDS: Rect = Rect::new(0.0, 0.0, 400.0, 300.0);
+
+    #[test]
+    fn places_on_requested_side() {
+        let anchor = Rect::new(100.0, 100.0, 140.0, 120.0);
+        let position = compute_popover_position(
+            anchor,
+            Size::new(60.0, 30.0),
+            BOUNDS,
+            Placement::BOTTOM,
+            4.0,
+        );
+        assert_eq!(position.origin, Point::new(90.0, 124.0));
+        assert_eq!(position.placement, Placement::BOTTOM);
+        assert_eq!(position.arrow_offset, 30.0);
+    }
+
+    #[test]
+    fn flips_when_there_is_no_room() {
+        let anchor = Rect::new(100.0, 270.0, 140.0, 290.0);
+        let position = compute_popover_position(
+            anchor,
+            Size::new(60.0, 30.0),
+            BOUNDS,
+            Placement::BOTTOM_START,
+            0.0,
+        );
+        assert_eq!(position.placement, Placement::TOP_START);
+        assert_eq!(position.origin, Point::new(100.0, 240.0));
+    }
+
+    #[test]
+    fn keeps_side_when_flipping_is_worse() {
+        let anchor = Rect::new(100.0, 20.0, 140.0, 280.0);
+        let position =
+            compute_popover_position(anchor, Size::new(60.0, 30.0), BOUNDS, Placement::TOP, 0.0);
+        assert_eq!(position.placement, Placement::TOP);
+    }
+
+    #[test]
+    fn shifts_inside_bounds() {
+        let anchor = Rect::new(380.0, 100.0, 400.0, 120.0);
+        let position = compute_popover_position(
+            anchor,
+            Size::new(100.0, 30.0),
+            BOUNDS,
+            Placement::BOTTOM,
+            0.0,
+        );
+        assert_eq!(position.origin, Point::new(300.0, 120.0));
+        // The arrow still points at the anchor.
+        assert_eq!(position.arrow_offset, 90.0);
+
+        let position = compute_popover_position(
+            anchor,
+            Size::new(500.0, 30.0),
+            BOUNDS,
+            Placement::BOTTOM,
+            0.0,
+        );
+        assert_eq!(position.origin.x, 0.0);
+    }
+}
@@ -5,9 +5,12 @@ use peniko::{Brush, Color};
 use crate::style::CustomStylable;
 use crate::unit::PxPct;
 use crate::{
+    animate::request_animation_frame,
     app_state::AppState,
+    clock,
     context::{ComputeLayoutCx, PaintCx},
     event::{Event, EventPropagation},
+    gesture::GesturePhase,
     id::ViewId,
     prop, prop_extractor,
     style::{Background, BorderColor, BorderRadius, Style, StyleSelector},
@@ -19,12 +22,32 @@ use crate::{
 
 use super::Decorators;
 
+#[cfg(not(target_arch = "wasm32"))]
+use std::time::Instant;
+#[cfg(target_arch = "wasm32")]
+use web_time::Instant;
+
 enum ScrollState {
     EnsureVisible(Rect),
     ScrollDelta(Vec2),
     ScrollTo(Point),
     ScrollToPercent(f32),
     ScrollToView(ViewId),
+    KineticFrame,
+}
+
+/// How much of its velocity a kinetic scroll keeps after one second.
+const KINETIC_DECAY_PER_SECOND: f64 = 0.05;
+
+/// Kinetic scrolling stops once it's slower than this, in logical pixels per second.
+const KINETIC_MIN_VELOCITY: f64 = 20.0;
+
+/// The scroll that keeps going after a touch pan is released.
+#[derive(Debug, Clone, Copy)]
+struct KineticScroll {
+    /// In logical pixels per second.
+    velocity: Vec2,
+    last_frame: Instant,
 }
 
 /// Minimum length for any scrollbar to be when measured on that
@@ -113,6 +136,7 @@ pub struct Scroll {
     track_style: ScrollTrackStyle,
     track_hover_style: ScrollTrackStyle,
     scroll_style: ScrollStyle,
+    kinetic: Option<KineticScroll>,
 }
 
 pub fn scroll<V: IntoView + 'static>(child: V) -> Scroll {
@@ -141,6 +165,7 @@ pub fn scroll<V: IntoView + 'static>(child: V) -> Scroll {
         track_style: Default::default(),
         track_hover_style: Default::default(),
         scroll_style: Default::default(),
+        kinetic: None,
     }
     .class(ScrollClass)
 }
@@ -208,6 +233,47 @@ impl Scroll {
         self.clamp_child_viewport(app_state, self.child_viewport.with_origin(new_origin));
     }
 
+    fn start_kinetic_scroll(&mut self, velocity: Vec2) {
+        if velocity.hypot() < KINETIC_MIN_VELOCITY {
+            self.kinetic = None;
+            return;
+        }
+        self.kinetic = Some(KineticScroll {
+            velocity,
+            last_frame: clock::now(),
+        });
+        self.schedule_kinetic_frame();
+    }
+
+    fn schedule_kinetic_frame(&self) {
+        let id = self.id;
+        request_animation_frame(move || {
+            id.update_state(ScrollState::KineticFrame);
+        });
+    }
+
+    fn kinetic_frame(&mut self, app_state: &mut AppState) {
+        let Some(kinetic) = self.kinetic.as_mut() else {
+            return;
+        };
+        let now = clock::now();
+        let dt = now
+            .saturating_duration_since(kinetic.last_frame)
+            .as_secs_f64();
+        kinetic.last_frame = now;
+        let delta = kinetic.velocity * dt;
+        kinetic.velocity *= KINETIC_DECAY_PER_SECOND.powf(dt);
+        let velocity = kinetic.velocity;
+
+        let moved = self.clamp_child_viewport(app_state, self.child_viewport + delta);
+        if moved.is_none() || velocity.hypot() < KINETIC_MIN_VELOCITY {
+            // Stopped by friction or by hitting the edge of the content.
+            self.kinetic = None;
+        } else {
+            self.schedule_kinetic_frame();
+        }
+    }
+
     fn do_scroll_to(&mut self, app_state: &mut AppState, origin: Point) {
         self.clamp_child_viewport(app_state, self.child_viewport.with_origin(origin));
     }
@@ -660,6 +726,9 @@ impl View for Scroll {
                 ScrollState::ScrollToView(id) => {
                     self.do_scroll_to_view(cx.app_state, id, None);
                 }
+                ScrollState::KineticFrame => {
+                    self.kinetic_frame(cx.app_state);
+                }
             }
             self.id.request_layout();
         }
@@ -716,6 +785,8 @@ impl View for Scroll {
 
         match &event {
             Event::PointerDown(event) => {
+                // Touching the content catches a kinetic scroll.
+                self.kinetic = None;
                 if !self.scroll_style.hide_bar() && event.button.is_primary() {
                     self.held = BarHeldState::None;
 
@@ -855,6 +926,40 @@ impl View for Scroll {
             };
         }
 
+        if let Event::Pan(pan) = &event {
+            if let Some(listener) = event.listener() {
+                if self
+                    .id
+                    .apply_event(&listener, event)
+                    .is_some_and(|prop| prop.is_processed())
+                {
+                    return EventPropagation::Stop;
+                }
+            }
+            // The content follows the finger. At the edge in the direction of the pan, the pan
+            // goes on to the scroll views around this one.
+            let any_change = match pan.phase {
+                GesturePhase::Started | GesturePhase::Changed => {
+                    self.kinetic = None;
+                    self.clamp_child_viewport(cx.app_state, self.child_viewport - pan.delta)
+                }
+                GesturePhase::Ended => {
+                    self.clamp_child_viewport(cx.app_state, self.child_viewport - pan.delta);
+                    self.start_kinetic_scroll(-pan.velocity);
+                    Some(())
+                }
+                GesturePhase::Cancelled => {
+                    self.kinetic = None;
+                    Some(())
+                }
+            };
+            return if any_change.is_none() {
+                EventPropagation::Continue
+            } else {
+                EventPropagation::Stop
+            };
+        }
+
         EventPropagation::Continue
     }
 
@@ -0,0 +1,542 @@
+//! Split panes with draggable dividers.
+//!
+//! A [`Split`] lays out its panes side by side, or stacked with [`Split::vertical`], with a
+//! divider between each pair of neighboring panes. Dragging a divider resizes the two panes next
+//! to it, within the [minimum](Split::min_size) and [maximum](Split::max_size) sizes of the
+//! panes, which also hold when the split itself is resized. A [collapsible](Split::collapsible)
+//! pane collapses when it's dragged below half of its minimum size.
+//!
+//! A divider can also be focused with Tab and moved with the arrow keys, by larger steps while
+//! Shift is held. Home and End move it as far as it goes.
+//!
+//! The sizes of the panes are kept as fractions of the space that's shared by the panes, so the
+//! panes keep their proportions when the split is resized. Those fractions can be bound to a
+//! signal with [`Split::ratio`] or [`Split::fractions`] to persist them.
+//!
+//! ```rust
+//! # use floem::reactive::create_rw_signal;
+//! # use floem::views::{label, split::split};
+//! let ratio = create_rw_signal(0.3);
+//! split(label(|| "Sidebar"), label(|| "Content"))
+//!     .min_size(0, 100.0)
+//!     .collapsible(0, true)
+//!     .ratio(ratio);
+//! ```
+
+use floem_reactive::{
+    create_effect, create_rw_signal, RwSignal, SignalGet, SignalUpdate, SignalWith,
+};
+use peniko::kurbo::{Point, Rect};
+
+use crate::{
+    context::{EventCx, UpdateCx},
+    event::{Event, EventListener, EventPropagation},
+    id::ViewId,
+    keyboard::{Key, Modifiers, NamedKey},
+    style::{CursorStyle, Style},
+    style_class,
+    view::{IntoView, View},
+    views::{clip, empty, Decorators, Empty},
+};
+
+style_class!(pub SplitPaneClass);
+style_class!(pub SplitDividerClass);
+
+/// The distance that the arrow keys move a focused divider.
+const KEYBOARD_STEP: f64 = 10.0;
+/// The distance that the arrow keys move a focused divider while Shift is held.
+const KEYBOARD_LARGE_STEP: f64 = 50.0;
+/// How far outside of a divider it can still be grabbed.
+const DIVIDER_GRAB_MARGIN: f64 = 2.0;
+
+/// The direction in which the panes of a [`Split`] are laid out.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "kebab-case"))]
+pub enum SplitAxis {
+    /// The panes are side by side, with vertical dividers.
+    Horizontal,
+    /// The panes are stacked, with horizontal dividers.
+    Vertical,
+}
+
+impl SplitAxis {
+    fn main(self, point: Point) -> f64 {
+        match self {
+            SplitAxis::Horizontal => point.x,
+            SplitAxis::Vertical => point.y,
+        }
+    }
+}
+
+/// The size limits of a pane, in pixels.
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct PaneLimits {
+    min: f64,
+    max: f64,
+    collapsible: bool,
+}
+
+impl Default for PaneLimits {
+    fn default() -> Self {
+        Self {
+            min: 0.0,
+            max: f64::INFINITY,
+            collapsible: false,
+        }
+    }
+}
+
+impl PaneLimits {
+    /// Keeps the size of the pane along the main axis within the limits, also when the split
+    /// itself is resized.
+    fn apply(&self, s: Style, axis: SplitAxis) -> Style {
+        let max = self.max.is_finite().then_some(self.max.max(self.min));
+        match axis {
+            SplitAxis::Horizontal => s
+                .min_width(self.min)
+                .apply_opt(max, |s, max| s.max_width(max)),
+            SplitAxis::Vertical => s
+                .min_height(self.min)
+                .apply_opt(max, |s, max| s.max_height(max)),
+        }
+    }
+
+    fn constrain(&self, size: f64) -> f64 {
+        if self.collapsible && self.min > 0.0 && size < self.min / 2.0 {
+            0.0
+        } else {
+            size.clamp(self.min, self.max.max(self.min))
+        }
+    }
+}
+
+/// Moves the divider after the pane at `divider` by `delta` pixels, resizing the panes on both
+/// sides of it within their limits. The other panes keep their sizes.
+fn resize_panes(sizes: &[f64], divider: usize, delta: f64, limits: &[PaneLimits]) -> Vec<f64> {
+    let mut sizes = sizes.to_vec();
+    let (first, second) = (divider, divider + 1);
+    let total = sizes[first] + sizes[second];
+
+    let first_size = limits[first]
+        .constrain(sizes[first] + delta)
+        .clamp(0.0, total);
+    // The second pane has the last word, so neither pane is pushed past the split.
+    let second_size = limits[second]
+        .constrain(total - first_size)
+        .clamp(0.0, total);
+    sizes[first] = total - second_size;
+    sizes[second] = second_size;
+    sizes
+}
+
+enum SplitMessage {
+    /// Move a divider from the keyboard.
+    Nudge { divider: usize, delta: f64 },
+    /// Move a divider as far as it goes, towards the start if `start` is `true`.
+    Extreme { divider: usize, start: bool },
+}
+
+struct Drag {
+    divider: usize,
+    start: f64,
+    sizes: Vec<f64>,
+}
+
+/// A container that shares its space between panes with draggable dividers between them. See
+/// the [module documentation](self).
+pub struct Split {
+    id: ViewId,
+    axis: RwSignal<SplitAxis>,
+    panes: Vec<ViewId>,
+    dividers: Vec<ViewId>,
+    limits: RwSignal<Vec<PaneLimits>>,
+    /// The fraction of the shared space that each pane takes.
+    fractions: RwSignal<Vec<f64>>,
+    on_resize: Vec<Box<dyn Fn(&[f64])>>,
+    drag: Option<Drag>,
+}
+
+/// A [`Split`] with two panes.
+pub fn split<A: IntoView + 'static, B: IntoView + 'static>(first: A, second: B) -> Split {
+    split_from_iter([first.into_any(), second.into_any()])
+}
+
+/// A [`Split`] with a pane for each view of the iterator.
+pub fn split_from_iter<V: IntoView + 'static>(panes: impl IntoIterator<Item = V>) -> Split {
+    let id = ViewId::new();
+    let axis = create_rw_signal(SplitAxis::Horizontal);
+    let panes = panes.into_iter().collect::<Vec<_>>();
+    let count = panes.len();
+    let fractions = create_rw_signal(vec![1.0 / count as f64; count]);
+    let limits = create_rw_signal(vec![PaneLimits::default(); count]);
+
+    let mut children = Vec::new();
+    let mut pane_ids = Vec::new();
+    let mut divider_ids = Vec::new();
+    for (index, pane) in panes.into_iter().enumerate() {
+        if index > 0 {
+            let divider = split_divider(id, index - 1, axis);
+            divider_ids.push(divider.id());
+            children.push(divider.into_any());
+        }
+        let pane = clip(pane).class(SplitPaneClass).style(move |s| {
+            let fraction = fractions.with(|fractions| fractions.get(index).copied().unwrap_or(0.0));
+            let limits = limits.with(|limits| limits.get(index).copied().unwrap_or_default());
+            let s = s
+                .flex_basis(0.0)
+                .flex_grow(fraction as f32)
+                .flex_shrink(0.0)
+                .min_width(0.0)
+                .min_height(0.0);
+            limits
+                .apply(s, axis.get())
+                .apply_if(fraction == 0.0, |s| s.hide())
+        });
+        pane_ids.push(pane.id());
+        children.push(pane.into_any());
+    }
+    id.set_children(children);
+
+    Split {
+        id,
+        axis,
+        panes: pane_ids,
+        dividers: divider_ids,
+        limits,
+        fractions,
+        on_resize: Vec::new(),
+        drag: None,
+    }
+}
+
+fn split_divider(split: ViewId, divider: usize, axis: RwSignal<SplitAxis>) -> Empty {
+    empty()
+        .class(SplitDividerClass)
+        .keyboard_navigable()
+        .on_event(EventListener::KeyDown, move |event| {
+            let Event::KeyDown(key_event) = event else {
+                return EventPropagation::Continue;
+            };
+            let step = if key_event.modifiers.contains(Modifiers::SHIFT) {
+                KEYBOARD_LARGE_STEP
+            } else {
+                KEYBOARD_STEP
+            };
+            let message = match (&key_event.key.logical_key, axis.get_untracked()) {
+                (Key::Named(NamedKey::ArrowLeft), SplitAxis::Horizontal)
+                | (Key::Named(NamedKey::ArrowUp), SplitAxis::Vertical) => SplitMessage::Nudge {
+                    divider,
+                    delta: -step,
+                },
+                (Key::Named(NamedKey::ArrowRight), SplitAxis::Horizontal)
+                | (Key::Named(NamedKey::ArrowDown), SplitAxis::Vertical) => SplitMessage::Nudge {
+                    divider,
+                    delta: step,
+                },
+                (Key::Named(NamedKey::Home), _) => SplitMessage::Extreme {
+                    divider,
+                    start: true,
+                },
+                (Key::Named(NamedKey::End), _) => SplitMessage::Extreme {
+                    divider,
+                    start: false,
+                },
+                _ => return EventPropagation::Continue,
+            };
+            split.update_state(message);
+            EventPropagation::Stop
+        })
+        .style(move |s| match axis.get() {
+            SplitAxis::Horizontal => s.height_full().cursor(CursorStyle::ColResize),
+            SplitAxis::Vertical => s.width_full().cursor(CursorStyle::RowResize),
+        })
+}
+
+impl Split {
+    /// Stacks the panes vertically, instead of side by side.
+    pub fn vertical(self) -> Self {
+        self.axis.set(SplitAxis::Vertical);
+        self
+    }
+
+    /// Sets the minimum size of the pane at `index`, in pixels.
+    pub fn min_size(self, index: usize, min: f64) -> Self {
+        self.limits.update(|limits| limits[index].min = min);
+        self
+    }
+
+    /// Sets the maximum size of the pane at `index`, in pixels.
+    pub fn max_size(self, index: usize, max: f64) -> Self {
+        self.limits.update(|limits| limits[index].max = max);
+        self
+    }
+
+    /// Lets the pane at `index` collapse when its divider is dragged below half of its minimum
+    /// size.
+    pub fn collapsible(self, index: usize, collapsible: bool) -> Self {
+        self.limits
+            .update(|limits| limits[index].collapsible = collapsible);
+        self
+    }
+
+    /// Binds the fraction of the space that the panes take to `fractions`, which has one value
+    /// for each pane, adding up to `1.0`. Values with a different number of panes are ignored.
+    pub fn fractions(mut self, fractions: RwSignal<Vec<f64>>) -> Self {
+        let own = self.fractions;
+        let count = self.panes.len();
+        create_effect(move |_| {
+            let fractions = fractions.get();
+            if fractions.len() == count && own.get_untracked() != fractions {
+                own.set(fractions);
+            }
+        });
+        self.on_resize
+            .push(Box::new(move |new| fractions.set(new.to_vec())));
+        self
+    }
+
+    /// Binds the fraction of the space that the first of two panes takes to `ratio`.
+    pub fn ratio(mut self, ratio: RwSignal<f64>) -> Self {
+        let own = self.fractions;
+        create_effect(move |_| {
+            let ratio = ratio.get().clamp(0.0, 1.0);
+            let fractions = vec![ratio, 1.0 - ratio];
+            if own.get_untracked() != fractions {
+                own.set(fractions);
+            }
+        });
+        self.on_resize.push(Box::new(move |new| {
+            if let Some(first) = new.first() {
+                ratio.set(*first);
+            }
+        }));
+        self
+    }
+
+    /// Sets a callback that's run with the new fractions of the panes when a divider is moved.
+    pub fn on_resize(mut self, on_resize: impl Fn(&[f64]) + 'static) -> Self {
+        self.on_resize.push(Box::new(on_resize));
+        self
+    }
+
+    fn pane_sizes(&self) -> Vec<f64> {
+        let axis = self.axis.get_untracked();
+        self.panes
+            .iter()
+            .map(|pane| {
+                let size = pane.get_size().unwrap_or_default();
+                match axis {
+                    SplitAxis::Horizontal => size.width,
+                    SplitAxis::Vertical => size.height,
+                }
+            })
+            .collect()
+    }
+
+    fn set_pane_sizes(&mut self, sizes: &[f64]) {
+        let total: f64 = sizes.iter().sum();
+        if total <= 0.0 {
+            return;
+        }
+        let fractions = sizes.iter().map(|size| size / total).collect::<Vec<_>>();
+        if self.fractions.get_untracked() == fractions {
+            return;
+        }
+        self.fractions.set(fractions.clone());
+        for on_resize in &self.on_resize {
+            on_resize(&fractions);
+        }
+    }
+
+    fn divider_at(&self, point: Point) -> Option<usize> {
+        self.dividers.iter().position(|divider| {
+            let Some(layout) = divider.get_layout() else {
+                return false;
+            };
+            let rect = Rect::from_origin_size(
+                (layout.location.x as f64, layout.location.y as f64),
+                (layout.size.width as f64, layout.size.height as f64),
+            );
+            let margin = DIVIDER_GRAB_MARGIN;
+            match self.axis.get_untracked() {
+                SplitAxis::Horizontal => rect.inflate(margin, 0.0),
+                SplitAxis::Vertical => rect.inflate(0.0, margin),
+            }
+            .contains(point)
+        })
+    }
+}
+
+impl View for Split {
+    fn id(&self) -> ViewId {
+        self.id
+    }
+
+    fn debug_name(&self) -> std::borrow::Cow<'static, str> {
+        "Split".into()
+    }
+
+    fn view_style(&self) -> Option<Style> {
+        let style = match self.axis.get_untracked() {
+            SplitAxis::Horizontal => Style::new().flex_row(),
+            SplitAxis::Vertical => Style::new().flex_col(),
+        };
+        Some(style.apply_if(self.drag.is_some(), |s| {
+            s.cursor(match self.axis.get_untracked() {
+                SplitAxis::Horizontal => CursorStyle::ColResize,
+                SplitAxis::Vertical => CursorStyle::RowResize,
+            })
+        }))
+    }
+
+    fn update(&mut self, _cx: &mut UpdateCx, state: Box<dyn std::any::Any>) {
+        if let Ok(message) = state.downcast::<SplitMessage>() {
+            let sizes = self.pane_sizes();
+            let (divider, delta) = match *message {
+                SplitMessage::Nudge { divider, delta } => (divider, delta),
+                SplitMessage::Extreme { divider, start } => {
+                    let total = sizes[divider] + sizes[divider + 1];
+                    (divider, if start { -total } else { total })
+                }
+            };
+            let sizes = self
+                .limits
+                .with_untracked(|limits| resize_panes(&sizes, divider, delta, limits));
+            self.set_pane_sizes(&sizes);
+        }
+    }
+
+    fn event_before_children(&mut self, cx: &mut EventCx, event: &Event) -> EventPropagation {
+        let axis = self.axis.get_untracked();
+        match event {
+            Event::PointerDown(pointer_event) if pointer_event.button.is_primary() => {
+                if let Some(divider) = self.divider_at(pointer_event.pos) {
+                    self.drag = Some(Drag {
+                        divider,
+                        start: axis.main(pointer_event.pos),
+                        sizes: self.pane_sizes(),
+                    });
+                    cx.update_active(self.id);
+                    self.dividers[divider].request_focus();
+                    self.id.request_style();
+                    return EventPropagation::Stop;
+                }
+            }
+            Event::PointerMove(pointer_event) => {
+                if let Some(drag) = &self.drag {
+                    let delta = axis.main(pointer_event.pos) - drag.start;
+                    let sizes = self.limits.with_untracked(|limits| {
+                        resize_panes(&drag.sizes, drag.divider, delta, limits)
+                    });
+                    self.set_pane_sizes(&sizes);
+                    return EventPropagation::Stop;
+                }
+            }
+            Event::PointerUp(_) => {
+                if self.drag.take().is_some() {
+                    self.id.request_style();
+                    return EventPropagation::Stop;
+                }
+            }
+            _ => {}
+        }
+        EventPropagation::Continue
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        style::{MaxHeight, MaxWidth, MinHeight, MinWidth},
+        unit::PxPctAuto,
+    };
+
+    #[test]
+    fn resize_within_limits() {
+        let limits = [PaneLimits::default(); 3];
+        assert_eq!(
+            resize_panes(&[100.0, 100.0, 100.0], 0, 30.0, &limits),
+            vec![130.0, 70.0, 100.0]
+        );
+        // Panes can't be pushed past the split.
+        assert_eq!(
+            resize_panes(&[100.0, 100.0, 100.0], 1, 300.0, &limits),
+            vec![100.0, 200.0, 0.0]
+        );
+
+        let limits = [
+            PaneLimits {
+                min: 50.0,
+                max: 120.0,
+                collapsible: false,
+            },
+            PaneLimits {
+                min: 60.0,
+                ..Default::default()
+            },
+        ];
+        assert_eq!(
+            resize_panes(&[100.0, 100.0], 0, 50.0, &limits),
+            vec![120.0, 80.0]
+        );
+        assert_eq!(
+            resize_panes(&[100.0, 100.0], 0, -80.0, &limits),
+            vec![50.0, 150.0]
+        );
+        // The second pane wins when both limits can't be met.
+        assert_eq!(
+            resize_panes(&[80.0, 80.0], 0, 20.0, &limits),
+            vec![100.0, 60.0]
+        );
+    }
+
+    #[test]
+    fn limits_as_style() {
+        let limits = PaneLimits {
+            min: 50.0,
+            max: 120.0,
+            collapsible: false,
+        };
+        let style = limits.apply(Style::new(), SplitAxis::Horizontal);
+        assert_eq!(style.get(MinWidth), PxPctAuto::Px(50.0));
+        assert_eq!(style.get(MaxWidth), PxPctAuto::Px(120.0));
+        assert_eq!(style.get(MinHeight), PxPctAuto::Auto);
+
+        // No maximum size isn't turned into a style.
+        let style = PaneLimits::default().apply(Style::new(), SplitAxis::Vertical);
+        assert_eq!(style.get(MinHeight), PxPctAuto::Px(0.0));
+        assert_eq!(style.get(MaxHeight), PxPctAuto::Auto);
+    }
+
+    #[test]
+    fn collapsible_panes_snap_closed() {
+        let limits = [
+            PaneLimits {
+                min: 100.0,
+                collapsible: true,
+                ..Default::default()
+            },
+            PaneLimits::default(),
+        ];
+        // Below the minimum size, but not below half of it.
+        assert_eq!(
+            resize_panes(&[200.0, 200.0], 0, -140.0, &limits),
+            vec![100.0, 300.0]
+        );
+        assert_eq!(
+            resize_panes(&[200.0, 200.0], 0, -160.0, &limits),
+            vec![0.0, 400.0]
+        );
+        // Dragging a collapsed pane open again.
+        assert_eq!(
+            resize_panes(&[0.0, 400.0], 0, 40.0, &limits),
+            vec![0.0, 400.0]
+        );
+        assert_eq!(
+            resize_panes(&[0.0, 400.0], 0, 70.0, &limits),
+            vec![100.0, 300.0]
+        );
+    }
+}
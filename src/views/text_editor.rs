@@ -1,4 +1,4 @@
-use std::rc::Rc;
+use std::{path::PathBuf, rc::Rc};
 
 use floem_editor_core::{buffer::rope_text::RopeTextVal, indent::IndentStyle};
 use floem_reactive::{create_updater, with_scope, RwSignal, Scope, SignalUpdate, SignalWith};
@@ -23,14 +23,15 @@ use crate::{
 };
 
 use super::editor::{
-    gutter::{DimColor, GutterClass, LeftOfCenterPadding, RightOfCenterPadding},
+    gutter::{DimColor, GutterClass, LeftOfCenterPadding, LineNumbers, RightOfCenterPadding},
     keypress::press::KeyPress,
     text::{RenderWhitespace, WrapMethod},
     view::EditorViewClass,
-    CurrentLineColor, CursorSurroundingLines, IndentGuideColor, IndentStyleProp, Modal,
-    ModalRelativeLine, PhantomColor, PlaceholderColor, PreeditUnderlineColor, RenderWhitespaceProp,
-    ScrollBeyondLastLine, SelectionColor, ShowIndentGuide, SmartTab, VisibleWhitespaceColor,
-    WrapProp,
+    BracketColorization, BracketColors, CurrentLineColor, CursorSurroundingLines, IndentGuideColor,
+    IndentStyleProp, MatchingBracketColor, Modal, ModalLineNumbers, ModalRelativeLine,
+    PhantomColor, PlaceholderColor, PreeditUnderlineColor, RenderWhitespaceProp,
+    ScrollBeyondLastLine, SelectionColor, ShowIndentGuide, ShowMatchingBracket, ShowStickyHeaders,
+    SmartTab, StickyHeaderBackground, VisibleWhitespaceColor, WrapProp,
 };
 
 /// A text editor view.
@@ -69,6 +70,14 @@ pub fn text_editor(text: impl Into<Rope>) -> TextEditor {
     }
 }
 
+/// A text editor of the file at `path`, which is read in the background and shown as it's read.
+/// A large file isn't wrapped and has no phantom text, so that it stays responsive. See
+/// [`TextDocument::load_file`].
+pub fn text_editor_file(path: impl Into<PathBuf>) -> TextEditor {
+    let doc = TextDocument::load_file(Scope::current(), path);
+    text_editor("").use_doc(Rc::new(doc))
+}
+
 pub fn text_editor_keys(
     text: impl Into<Rope>,
     handle_key_event: impl Fn(RwSignal<Editor>, &KeyPress, Modifiers) -> CommandExecuted + 'static,
@@ -283,6 +292,55 @@ impl EditorCustomStyle {
         self
     }
 
+    /// Sets whether the lines opening the scopes around the top of the viewport are pinned to
+    /// the top of the editor.
+    pub fn sticky_headers(mut self, show: bool) -> Self {
+        self.0 = self
+            .0
+            .class(EditorViewClass, |s| s.set(ShowStickyHeaders, show));
+        self
+    }
+
+    /// Sets the background color of the sticky headers.
+    pub fn sticky_header_background(mut self, color: Color) -> Self {
+        self.0 = self
+            .0
+            .class(EditorViewClass, |s| s.set(StickyHeaderBackground, color));
+        self
+    }
+
+    /// Sets whether the bracket at the caret and its matching bracket are highlighted.
+    pub fn matching_bracket(mut self, show: bool) -> Self {
+        self.0 = self
+            .0
+            .class(EditorViewClass, |s| s.set(ShowMatchingBracket, show));
+        self
+    }
+
+    /// Sets the color of the box around the matching brackets.
+    pub fn matching_bracket_color(mut self, color: Color) -> Self {
+        self.0 = self
+            .0
+            .class(EditorViewClass, |s| s.set(MatchingBracketColor, color));
+        self
+    }
+
+    /// Sets whether `()`, `[]` and `{}` are colored by how deeply they are nested.
+    pub fn bracket_colorization(mut self, colorize: bool) -> Self {
+        self.0 = self
+            .0
+            .class(EditorViewClass, |s| s.set(BracketColorization, colorize));
+        self
+    }
+
+    /// Sets the colors of the brackets by depth, which repeat for deeper brackets.
+    pub fn bracket_colors(mut self, colors: Vec<Color>) -> Self {
+        self.0 = self
+            .0
+            .class(EditorViewClass, |s| s.set(BracketColors, colors.clone()));
+        self
+    }
+
     /// Sets the editor's mode to modal or non-modal.
     pub fn modal(mut self, modal: bool) -> Self {
         self.0 = self.0.class(EditorViewClass, |s| s.set(Modal, modal));
@@ -297,6 +355,14 @@ impl EditorCustomStyle {
         self
     }
 
+    /// Sets how line numbers are shown outside of insert mode in modal mode.
+    pub fn modal_line_numbers(mut self, line_numbers: LineNumbers) -> Self {
+        self.0 = self
+            .0
+            .class(EditorViewClass, |s| s.set(ModalLineNumbers, line_numbers));
+        self
+    }
+
     /// Enables or disables smart tab behavior, which inserts the indent style detected in the file when the tab key is pressed.
     pub fn smart_tab(mut self, smart_tab: bool) -> Self {
         self.0 = self
@@ -10,12 +10,13 @@ use web_time::Duration;
 use crate::style::{Style, StyleClass as _};
 use crate::views::Decorators;
 use crate::{
-    action::{add_overlay, exec_after, remove_overlay, TimerToken},
+    action::{exec_after, remove_overlay, TimerToken},
     context::{EventCx, UpdateCx},
     event::{Event, EventPropagation},
     id::ViewId,
     prop, prop_extractor, style_class,
     view::{default_compute_layout, IntoView, View},
+    views::{popover_at, Placement},
 };
 
 style_class!(pub TooltipClass);
@@ -80,12 +81,15 @@ impl View for Tooltip {
                     let tip = self.tip.clone();
 
                     let tip_style = self.tip_style.clone();
-                    let overlay_id = add_overlay(
-                        window_origin
-                            + self.hover.unwrap().0.to_vec2()
-                            + (10. / self.scale, 10. / self.scale),
-                        move |_| tip().style(move |_| tip_style.clone()),
-                    );
+                    let offset = 10. / self.scale;
+                    let overlay_id = popover_at(
+                        window_origin + self.hover.unwrap().0.to_vec2() + (offset, 0.),
+                        Placement::BOTTOM_START,
+                        move || tip().style(move |_| tip_style.clone()),
+                    )
+                    .gap(offset)
+                    .close_on_escape(false)
+                    .show();
                     // overlay_id.request_all();
                     *self.overlay.borrow_mut() = Some(overlay_id);
                 }
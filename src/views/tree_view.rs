@@ -0,0 +1,839 @@
+use std::{
+    collections::{HashMap, HashSet},
+    hash::Hash,
+    rc::Rc,
+};
+
+use floem_reactive::{
+    create_effect, create_rw_signal, RwSignal, SignalGet, SignalTrack, SignalUpdate, SignalWith,
+};
+use peniko::kurbo::{Rect, Size};
+
+use super::{
+    container, empty, h_stack, svg, virtual_stack, Decorators, VirtualDirection, VirtualItemSize,
+};
+use crate::{
+    context::{ComputeLayoutCx, StyleCx},
+    event::{Event, EventListener, EventPropagation},
+    id::ViewId,
+    keyboard::{Key, Modifiers, NamedKey},
+    style::Style,
+    style_class,
+    view::{IntoView, View},
+};
+
+style_class!(pub TreeViewClass);
+style_class!(pub TreeItemClass);
+style_class!(pub TreeChevronClass);
+
+const CHEVRON_RIGHT_SVG: &str = r#"<svg xmlns="http://www.w3.org/2000/svg" viewBox="0 0 16 16"><polygon points="6,3 11,8 6,13 5,12 9,8 5,4" /></svg>"#;
+const CHEVRON_DOWN_SVG: &str = r#"<svg xmlns="http://www.w3.org/2000/svg" viewBox="0 0 16 16"><polygon points="3,6 8,11 13,6 12,5 8,9 4,5" /></svg>"#;
+
+/// Controls how many rows of a [`TreeView`] can be selected at the same time.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TreeSelectionMode {
+    /// At most one row is selected.
+    Single,
+    /// Rows can be added to the selection with Ctrl/Cmd-click and ranges with Shift.
+    Multiple,
+}
+
+/// Where a dragged row would be dropped relative to the row it is over.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TreeDropPosition {
+    /// Insert the dragged node as a sibling before the target.
+    Before,
+    /// Insert the dragged node as a child of the target.
+    Into,
+    /// Insert the dragged node as a sibling after the target.
+    After,
+}
+
+/// A drop of one tree row onto another, passed to [`TreeView::on_drop`].
+#[derive(Debug, Clone)]
+pub struct TreeDrop<K> {
+    /// The key of the row that was dragged.
+    pub dragged: K,
+    /// The key of the row that the dragged row was dropped on.
+    pub target: K,
+    pub position: TreeDropPosition,
+}
+
+/// A row of a flattened tree, as shown by a [`TreeView`].
+#[derive(Clone)]
+pub struct TreeNode<T, K> {
+    pub item: T,
+    pub key: K,
+    pub depth: usize,
+    pub parent: Option<usize>,
+    pub expanded: bool,
+    /// `false` once the node is known to have no children.
+    pub has_children: bool,
+}
+
+type LeafFn<T> = Rc<dyn Fn(&T) -> bool>;
+type DropFn<K> = Rc<dyn Fn(TreeDrop<K>) -> bool>;
+type CanDropFn<K> = Rc<dyn Fn(&K, &K, TreeDropPosition) -> bool>;
+
+enum TreeUpdate {
+    SelectionChanged,
+    ScrollToCursor,
+    Accept,
+}
+
+/// A hierarchical, virtualized list of rows. See [`tree_view`].
+pub struct TreeView<T: 'static, K: 'static> {
+    id: ViewId,
+    child: ViewId,
+    child_size: Size,
+    rows: RwSignal<im::Vector<TreeNode<T, K>>>,
+    row_index: RwSignal<HashMap<K, usize>>,
+    row_height: RwSignal<f64>,
+    indent: RwSignal<f64>,
+    expanded: RwSignal<HashSet<K>>,
+    selection: RwSignal<Vec<K>>,
+    selection_mode: RwSignal<TreeSelectionMode>,
+    cursor: RwSignal<Option<K>>,
+    leaf_fn: RwSignal<Option<LeafFn<T>>>,
+    drop_fn: RwSignal<Option<DropFn<K>>>,
+    can_drop_fn: RwSignal<Option<CanDropFn<K>>>,
+    drop_target: RwSignal<Option<(K, TreeDropPosition)>>,
+    on_accept: Option<Box<dyn Fn(Vec<K>)>>,
+}
+
+impl<T: Clone + 'static, K: Eq + Hash + Clone + 'static> TreeView<T, K> {
+    /// The keys of the selected rows, in the order they were selected.
+    pub fn selection(&self) -> RwSignal<Vec<K>> {
+        self.selection
+    }
+
+    /// The keys of the expanded nodes.
+    ///
+    /// Children are only requested from the children function for nodes in this set.
+    pub fn expanded(&self) -> RwSignal<HashSet<K>> {
+        self.expanded
+    }
+
+    /// The row that keyboard navigation starts from.
+    pub fn cursor(&self) -> RwSignal<Option<K>> {
+        self.cursor
+    }
+
+    /// The row and position that a dragged row is currently hovering, if any.
+    pub fn drop_target(&self) -> RwSignal<Option<(K, TreeDropPosition)>> {
+        self.drop_target
+    }
+
+    pub fn selection_mode(self, mode: TreeSelectionMode) -> Self {
+        self.selection_mode.set(mode);
+        self
+    }
+
+    /// Set the height of every row. The default is 20px.
+    pub fn row_height(self, height: f64) -> Self {
+        self.row_height.set(height);
+        self
+    }
+
+    /// Set how far each level of the tree is indented. The default is 16px.
+    pub fn indent(self, indent: f64) -> Self {
+        self.indent.set(indent);
+        self
+    }
+
+    /// Tell the tree which nodes are leaves without loading their children.
+    ///
+    /// Without this, every collapsed node shows a chevron until it is expanded and turns out to be empty.
+    pub fn is_leaf(self, is_leaf: impl Fn(&T) -> bool + 'static) -> Self {
+        self.leaf_fn.set(Some(Rc::new(is_leaf)));
+        self
+    }
+
+    pub fn on_select(self, on_select: impl Fn(Vec<K>) + 'static) -> Self {
+        let selection = self.selection;
+        create_effect(move |_| {
+            let selection = selection.get();
+            on_select(selection);
+        });
+        self
+    }
+
+    /// Called with the selection when a row is double clicked or Enter is pressed.
+    pub fn on_accept(mut self, on_accept: impl Fn(Vec<K>) + 'static) -> Self {
+        self.on_accept = Some(Box::new(on_accept));
+        self
+    }
+
+    /// Make rows draggable and call `on_drop` when a row is dropped on another row.
+    ///
+    /// The tree does not move anything itself; the handler is expected to update the data that the
+    /// root and children functions read. Return `true` if the drop was handled.
+    pub fn on_drop(self, on_drop: impl Fn(TreeDrop<K>) -> bool + 'static) -> Self {
+        self.drop_fn.set(Some(Rc::new(on_drop)));
+        self
+    }
+
+    /// Restrict where rows can be dropped. The arguments are the dragged key, the target key and
+    /// the drop position.
+    pub fn can_drop(self, can_drop: impl Fn(&K, &K, TreeDropPosition) -> bool + 'static) -> Self {
+        self.can_drop_fn.set(Some(Rc::new(can_drop)));
+        self
+    }
+}
+
+/// A virtualized tree of rows with expand and collapse chevrons, suited for file browsers and outlines.
+///
+/// `children_fn` is only called for expanded nodes, so children are loaded lazily as the user
+/// expands the tree. Rows all have the same height, which lets the tree stay fast with huge
+/// numbers of nodes; it must be placed in a [scroll view](super::scroll()).
+///
+/// Keyboard navigation works like a [list](super::list()) with the up and down arrow, home and
+/// end keys. The right arrow expands a node or moves to its first child and the left arrow
+/// collapses a node or moves to its parent. Enter or a double click accepts the selection.
+///
+/// ## Example
+/// ```rust
+/// use floem::views::*;
+///
+/// #[derive(Clone)]
+/// struct Entry {
+///     path: String,
+///     depth: usize,
+/// }
+///
+/// scroll(
+///     tree_view(
+///         || Entry { path: "/".to_string(), depth: 0 },
+///         |entry: &Entry| {
+///             let depth = entry.depth + 1;
+///             let path = entry.path.clone();
+///             (0..3).map(move |i| Entry { path: format!("{path}{i}/"), depth })
+///         },
+///         |entry| entry.path.clone(),
+///         |entry| text(entry.path),
+///     )
+///     .is_leaf(|entry| entry.depth >= 3),
+/// );
+/// ```
+pub fn tree_view<T, RF, CF, I, KF, K, VF, V>(
+    root_fn: RF,
+    children_fn: CF,
+    key_fn: KF,
+    view_fn: VF,
+) -> TreeView<T, K>
+where
+    T: Clone + 'static,
+    RF: Fn() -> T + 'static,
+    CF: Fn(&T) -> I + 'static,
+    I: IntoIterator<Item = T>,
+    KF: Fn(&T) -> K + 'static,
+    K: Eq + Hash + Clone + 'static,
+    VF: Fn(T) -> V + 'static,
+    V: IntoView + 'static,
+{
+    let id = ViewId::new();
+    let rows = create_rw_signal(im::Vector::new());
+    let row_index = create_rw_signal(HashMap::new());
+    let row_height = create_rw_signal(20.0);
+    let indent = create_rw_signal(16.0);
+    let expanded = create_rw_signal(HashSet::new());
+    let selection = create_rw_signal(Vec::new());
+    let selection_mode = create_rw_signal(TreeSelectionMode::Single);
+    let cursor = create_rw_signal(None);
+    let anchor: RwSignal<Option<K>> = create_rw_signal(None);
+    let leaf_fn: RwSignal<Option<LeafFn<T>>> = create_rw_signal(None);
+    let drop_fn: RwSignal<Option<DropFn<K>>> = create_rw_signal(None);
+    let can_drop_fn: RwSignal<Option<CanDropFn<K>>> = create_rw_signal(None);
+    let drop_target = create_rw_signal(None);
+    let dragged: RwSignal<Option<K>> = create_rw_signal(None);
+
+    let key_fn = Rc::new(key_fn);
+
+    {
+        let key_fn = key_fn.clone();
+        create_effect(move |_| {
+            let root = root_fn();
+            let (new_rows, new_index) = expanded.with(|expanded| {
+                leaf_fn.with(|leaf_fn| {
+                    flatten_tree(
+                        root,
+                        &children_fn,
+                        key_fn.as_ref(),
+                        leaf_fn.as_deref(),
+                        expanded,
+                    )
+                })
+            });
+            row_index.set(new_index);
+            rows.set(new_rows);
+        });
+    }
+
+    create_effect(move |_| {
+        selection.track();
+        id.update_state(TreeUpdate::SelectionChanged);
+    });
+
+    let select = move |key: K, modifiers: Modifiers| {
+        let multiple = selection_mode.get_untracked() == TreeSelectionMode::Multiple;
+        if multiple && modifiers.shift() {
+            let anchor_key = anchor.get_untracked().unwrap_or_else(|| key.clone());
+            let range =
+                row_index.with_untracked(|index| match (index.get(&anchor_key), index.get(&key)) {
+                    (Some(&a), Some(&b)) => Some((a.min(b), a.max(b))),
+                    _ => None,
+                });
+            if let Some((start, end)) = range {
+                let keys = rows.with_untracked(|rows| {
+                    rows.iter()
+                        .skip(start)
+                        .take(end - start + 1)
+                        .map(|row| row.key.clone())
+                        .collect::<Vec<_>>()
+                });
+                selection.set(keys);
+            }
+        } else if multiple && (modifiers.control() || modifiers.meta()) {
+            selection.update(|selection| {
+                if let Some(pos) = selection.iter().position(|k| k == &key) {
+                    selection.remove(pos);
+                } else {
+                    selection.push(key.clone());
+                }
+            });
+            anchor.set(Some(key.clone()));
+        } else {
+            if selection.with_untracked(|s| s.len() != 1 || s[0] != key) {
+                selection.set(vec![key.clone()]);
+            }
+            anchor.set(Some(key.clone()));
+        }
+        cursor.set(Some(key));
+    };
+    let select = Rc::new(select);
+
+    let toggle_expanded = move |key: K, expand: bool| {
+        expanded.update(|expanded| {
+            if expand {
+                expanded.insert(key);
+            } else {
+                expanded.remove(&key);
+            }
+        });
+    };
+
+    let view_fn = Rc::new(view_fn);
+    let select_ = select.clone();
+    let stack = virtual_stack(
+        VirtualDirection::Vertical,
+        VirtualItemSize::Fixed(Box::new(move || row_height.get())),
+        move || rows.get(),
+        row_key,
+        move |node: TreeNode<T, K>| {
+            let key = node.key.clone();
+            let has_children = node.has_children;
+            let depth = node.depth;
+
+            let chevron_key = key.clone();
+            let chevron = svg(CHEVRON_RIGHT_SVG)
+                .update_value(move || {
+                    if !has_children {
+                        ""
+                    } else if expanded.with(|e| e.contains(&chevron_key)) {
+                        CHEVRON_DOWN_SVG
+                    } else {
+                        CHEVRON_RIGHT_SVG
+                    }
+                })
+                .class(TreeChevronClass)
+                .on_click_stop({
+                    let key = key.clone();
+                    move |_| {
+                        if has_children {
+                            let is_expanded = expanded.with_untracked(|e| e.contains(&key));
+                            toggle_expanded(key.clone(), !is_expanded);
+                        }
+                    }
+                });
+
+            let row = h_stack((
+                empty().style(move |s| s.width(indent.get() * depth as f64)),
+                chevron,
+                view_fn(node.item),
+            ))
+            .style(|s| s.items_center());
+
+            let row_id = ViewId::new();
+            let child = container(row)
+                .class(TreeItemClass)
+                .style(move |s| s.height(row_height.get()).width_full());
+            let child_id = child.id();
+            row_id.set_children(vec![child]);
+            if drop_fn.with_untracked(Option::is_some) {
+                row_id.draggable();
+            }
+
+            let select = select_.clone();
+            let click_key = key.clone();
+            let accept_key = key.clone();
+            let drag_key = key.clone();
+            let over_key = key.clone();
+            let drop_key = key.clone();
+            let selected_key = key;
+            TreeItem {
+                id: row_id,
+                child: child_id,
+                is_selected: Box::new(move || {
+                    selection.with_untracked(|s| s.contains(&selected_key))
+                }),
+            }
+            .on_event_cont(EventListener::PointerDown, move |e| {
+                if let Event::PointerDown(pointer_event) = e {
+                    if pointer_event.button.is_primary() {
+                        select(click_key.clone(), pointer_event.modifiers);
+                    }
+                }
+            })
+            .on_double_click_stop(move |_| {
+                if has_children {
+                    let is_expanded = expanded.with_untracked(|e| e.contains(&accept_key));
+                    toggle_expanded(accept_key.clone(), !is_expanded);
+                }
+                id.update_state(TreeUpdate::Accept);
+            })
+            .on_event_cont(EventListener::DragStart, move |_| {
+                dragged.set(Some(drag_key.clone()));
+            })
+            .on_event_cont(EventListener::DragOver, move |e| {
+                let Some(dragged_key) = dragged.get_untracked() else {
+                    return;
+                };
+                let Some(point) = e.point() else {
+                    return;
+                };
+                let height = row_height.get_untracked();
+                let position = if point.y < height / 4.0 {
+                    TreeDropPosition::Before
+                } else if point.y > height * 3.0 / 4.0 {
+                    TreeDropPosition::After
+                } else {
+                    TreeDropPosition::Into
+                };
+                let allowed = dragged_key != over_key
+                    && can_drop_fn.with_untracked(|can_drop| {
+                        can_drop
+                            .as_ref()
+                            .map_or(true, |f| f(&dragged_key, &over_key, position))
+                    });
+                let target = allowed.then(|| (over_key.clone(), position));
+                if drop_target.with_untracked(|t| t != &target) {
+                    drop_target.set(target);
+                }
+            })
+            .on_event(EventListener::Drop, move |_| {
+                let target = drop_target.get_untracked();
+                drop_target.set(None);
+                let Some(dragged_key) = dragged.get_untracked() else {
+                    return EventPropagation::Continue;
+                };
+                dragged.set(None);
+                let Some((target_key, position)) = target.filter(|(k, _)| k == &drop_key) else {
+                    return EventPropagation::Continue;
+                };
+                let handled = drop_fn.with_untracked(|on_drop| {
+                    on_drop.as_ref().is_some_and(|f| {
+                        f(TreeDrop {
+                            dragged: dragged_key,
+                            target: target_key,
+                            position,
+                        })
+                    })
+                });
+                if handled {
+                    EventPropagation::Stop
+                } else {
+                    EventPropagation::Continue
+                }
+            })
+            .on_event_cont(EventListener::DragEnd, move |_| {
+                dragged.set(None);
+                drop_target.set(None);
+            })
+            .style(|s| s.width_full())
+        },
+    )
+    .style(|s| s.flex_col().min_width_full());
+    let child = stack.id();
+    id.set_children(vec![stack.into_view()]);
+
+    // Move the cursor to the row at `index` and select it
+    let move_to = {
+        let select = select.clone();
+        move |index: usize, modifiers: Modifiers| {
+            let key = rows.with_untracked(|rows| rows.get(index).map(|row| row.key.clone()));
+            if let Some(key) = key {
+                select(key, modifiers);
+                id.update_state(TreeUpdate::ScrollToCursor);
+            }
+        }
+    };
+
+    TreeView {
+        id,
+        child,
+        child_size: Size::ZERO,
+        rows,
+        row_index,
+        row_height,
+        indent,
+        expanded,
+        selection,
+        selection_mode,
+        cursor,
+        leaf_fn,
+        drop_fn,
+        can_drop_fn,
+        drop_target,
+        on_accept: None,
+    }
+    .class(TreeViewClass)
+    .keyboard_navigable()
+    .on_event(EventListener::KeyDown, move |e| {
+        let Event::KeyDown(key_event) = e else {
+            return EventPropagation::Continue;
+        };
+        let modifiers = key_event.modifiers;
+        let length = rows.with_untracked(|rows| rows.len());
+        if length == 0 {
+            return EventPropagation::Continue;
+        }
+        let current = cursor
+            .get_untracked()
+            .and_then(|key| row_index.with_untracked(|index| index.get(&key).copied()));
+        match key_event.key.logical_key {
+            Key::Named(NamedKey::Home) => {
+                move_to(0, modifiers);
+                EventPropagation::Stop
+            }
+            Key::Named(NamedKey::End) => {
+                move_to(length - 1, modifiers);
+                EventPropagation::Stop
+            }
+            Key::Named(NamedKey::ArrowUp) => {
+                match current {
+                    Some(i) if i > 0 => move_to(i - 1, modifiers),
+                    Some(_) => {}
+                    None => move_to(length - 1, modifiers),
+                }
+                EventPropagation::Stop
+            }
+            Key::Named(NamedKey::ArrowDown) => {
+                match current {
+                    Some(i) if i + 1 < length => move_to(i + 1, modifiers),
+                    Some(_) => {}
+                    None => move_to(0, modifiers),
+                }
+                EventPropagation::Stop
+            }
+            Key::Named(NamedKey::ArrowLeft) => {
+                let Some(i) = current else {
+                    return EventPropagation::Continue;
+                };
+                let (key, is_expanded, parent) = rows.with_untracked(|rows| {
+                    let row = &rows[i];
+                    (row.key.clone(), row.expanded, row.parent)
+                });
+                if is_expanded {
+                    toggle_expanded(key, false);
+                } else if let Some(parent) = parent {
+                    move_to(parent, Modifiers::empty());
+                }
+                EventPropagation::Stop
+            }
+            Key::Named(NamedKey::ArrowRight) => {
+                let Some(i) = current else {
+                    return EventPropagation::Continue;
+                };
+                let (key, is_expanded, has_children) = rows.with_untracked(|rows| {
+                    let row = &rows[i];
+                    (row.key.clone(), row.expanded, row.has_children)
+                });
+                if !is_expanded && has_children {
+                    toggle_expanded(key, true);
+                } else if is_expanded && i + 1 < length {
+                    let is_child = rows.with_untracked(|rows| rows[i + 1].parent == Some(i));
+                    if is_child {
+                        move_to(i + 1, Modifiers::empty());
+                    }
+                }
+                EventPropagation::Stop
+            }
+            Key::Named(NamedKey::Space)
+                if selection_mode.get_untracked() == TreeSelectionMode::Multiple =>
+            {
+                if let Some(i) = current {
+                    move_to(i, Modifiers::CONTROL);
+                }
+                EventPropagation::Stop
+            }
+            Key::Named(NamedKey::Enter) => {
+                id.update_state(TreeUpdate::Accept);
+                EventPropagation::Stop
+            }
+            _ => EventPropagation::Continue,
+        }
+    })
+}
+
+/// The key that a row is rebuilt on when it changes. The chevron and the double click handler of
+/// a row depend on whether it has children, which is only known once it's expanded.
+fn row_key<T, K: Clone>(node: &TreeNode<T, K>) -> (K, usize, bool) {
+    (node.key.clone(), node.depth, node.has_children)
+}
+
+/// Walk the tree from `root` depth first, descending only into expanded nodes.
+fn flatten_tree<T: Clone, K: Eq + Hash + Clone, I: IntoIterator<Item = T>>(
+    root: T,
+    children_fn: &dyn Fn(&T) -> I,
+    key_fn: &dyn Fn(&T) -> K,
+    leaf_fn: Option<&dyn Fn(&T) -> bool>,
+    expanded: &HashSet<K>,
+) -> (im::Vector<TreeNode<T, K>>, HashMap<K, usize>) {
+    let mut rows = im::Vector::new();
+    let mut index = HashMap::new();
+    // (item, depth, parent row)
+    let mut stack = vec![(root, 0, None)];
+    while let Some((item, depth, parent)) = stack.pop() {
+        let key = key_fn(&item);
+        let is_leaf = leaf_fn.is_some_and(|f| f(&item));
+        let is_expanded = !is_leaf && expanded.contains(&key);
+        let row = rows.len();
+        let mut has_children = !is_leaf;
+        if is_expanded {
+            let children: Vec<T> = children_fn(&item).into_iter().collect();
+            has_children = !children.is_empty();
+            stack.extend(
+                children
+                    .into_iter()
+                    .rev()
+                    .map(|child| (child, depth + 1, Some(row))),
+            );
+        }
+        index.insert(key.clone(), row);
+        rows.push_back(TreeNode {
+            item,
+            key,
+            depth,
+            parent,
+            expanded: is_expanded && has_children,
+            has_children,
+        });
+    }
+    (rows, index)
+}
+
+impl<T: 'static, K: Eq + Hash + Clone + 'static> View for TreeView<T, K> {
+    fn id(&self) -> ViewId {
+        self.id
+    }
+
+    fn debug_name(&self) -> std::borrow::Cow<'static, str> {
+        "TreeView".into()
+    }
+
+    fn update(&mut self, _cx: &mut crate::context::UpdateCx, state: Box<dyn std::any::Any>) {
+        if let Ok(change) = state.downcast::<TreeUpdate>() {
+            match *change {
+                TreeUpdate::SelectionChanged => {
+                    self.id.request_style_recursive();
+                }
+                TreeUpdate::ScrollToCursor => {
+                    let row_height = self.row_height.get_untracked();
+                    let index = self.cursor.with_untracked(|cursor| {
+                        cursor.as_ref().and_then(|key| {
+                            self.row_index
+                                .with_untracked(|index| index.get(key).copied())
+                        })
+                    });
+                    if let Some(index) = index {
+                        let y0 = index as f64 * row_height;
+                        let rect = Rect::new(0.0, y0, self.child_size.width, y0 + row_height);
+                        self.child.scroll_to(Some(rect));
+                    }
+                }
+                TreeUpdate::Accept => {
+                    if let Some(on_accept) = &self.on_accept {
+                        on_accept(self.selection.get_untracked());
+                    }
+                }
+            }
+        }
+    }
+
+    fn compute_layout(&mut self, cx: &mut ComputeLayoutCx) -> Option<Rect> {
+        self.child_size = self
+            .child
+            .get_layout()
+            .map(|layout| Size::new(layout.size.width as f64, layout.size.height as f64))
+            .unwrap_or_default();
+
+        cx.compute_view_layout(self.child)
+    }
+}
+
+struct TreeItem {
+    id: ViewId,
+    child: ViewId,
+    is_selected: Box<dyn Fn() -> bool>,
+}
+
+impl View for TreeItem {
+    fn id(&self) -> ViewId {
+        self.id
+    }
+
+    fn view_style(&self) -> Option<Style> {
+        Some(Style::new().flex_col())
+    }
+
+    fn debug_name(&self) -> std::borrow::Cow<'static, str> {
+        "TreeItem".into()
+    }
+
+    fn style_pass(&mut self, cx: &mut StyleCx<'_>) {
+        if (self.is_selected)() {
+            cx.save();
+            cx.selected();
+            cx.style_view(self.child);
+            cx.restore();
+        } else {
+            cx.style_view(self.child);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use super::{flatten_tree, row_key};
+
+    #[derive(Clone, Debug, PartialEq)]
+    struct Node {
+        name: &'static str,
+        children: Vec<Node>,
+    }
+
+    fn node(name: &'static str, children: Vec<Node>) -> Node {
+        Node { name, children }
+    }
+
+    fn tree() -> Node {
+        node(
+            "root",
+            vec![
+                node("a", vec![node("a1", vec![]), node("a2", vec![])]),
+                node("b", vec![node("b1", vec![])]),
+                node("c", vec![]),
+            ],
+        )
+    }
+
+    fn flatten(expanded: &[&'static str]) -> Vec<(&'static str, usize, Option<usize>, bool)> {
+        let expanded: HashSet<&'static str> = expanded.iter().copied().collect();
+        let (rows, index) = flatten_tree(
+            tree(),
+            &|n: &Node| n.children.clone(),
+            &|n: &Node| n.name,
+            None,
+            &expanded,
+        );
+        for (i, row) in rows.iter().enumerate() {
+            assert_eq!(index[&row.key], i);
+        }
+        rows.iter()
+            .map(|row| (row.key, row.depth, row.parent, row.has_children))
+            .collect()
+    }
+
+    #[test]
+    fn test_collapsed_root() {
+        assert_eq!(flatten(&[]), vec![("root", 0, None, true)]);
+    }
+
+    #[test]
+    fn test_expanded_nodes_are_walked_in_order() {
+        assert_eq!(
+            flatten(&["root", "a"]),
+            vec![
+                ("root", 0, None, true),
+                ("a", 1, Some(0), true),
+                ("a1", 2, Some(1), true),
+                ("a2", 2, Some(1), true),
+                ("b", 1, Some(0), true),
+                ("c", 1, Some(0), true),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_collapsed_children_are_not_loaded() {
+        let expanded: HashSet<&'static str> = ["root"].into_iter().collect();
+        let (rows, _) = flatten_tree(
+            tree(),
+            &|n: &Node| {
+                assert!(n.name == "root", "children of {} were loaded", n.name);
+                n.children.clone()
+            },
+            &|n: &Node| n.name,
+            None,
+            &expanded,
+        );
+        assert_eq!(rows.len(), 4);
+    }
+
+    #[test]
+    fn test_expanded_empty_node_has_no_children() {
+        let rows = flatten(&["root", "c"]);
+        assert_eq!(rows[3], ("c", 1, Some(0), false));
+    }
+
+    #[test]
+    fn test_rows_are_rebuilt_when_a_node_turns_out_empty() {
+        let keys = |expanded: &[&'static str]| {
+            let expanded: HashSet<&'static str> = expanded.iter().copied().collect();
+            let (rows, _) = flatten_tree(
+                tree(),
+                &|n: &Node| n.children.clone(),
+                &|n: &Node| n.name,
+                None,
+                &expanded,
+            );
+            rows.iter().map(row_key).collect::<Vec<_>>()
+        };
+        let collapsed = keys(&["root"]);
+        let expanded = keys(&["root", "c"]);
+        // The row of `c` loses its chevron, while the other rows are kept.
+        assert_eq!(collapsed[3], ("c", 1, true));
+        assert_eq!(expanded[3], ("c", 1, false));
+        assert_eq!(collapsed[..3], expanded[..3]);
+    }
+
+    #[test]
+    fn test_leaf_fn() {
+        let expanded: HashSet<&'static str> = ["root"].into_iter().collect();
+        let is_leaf: &dyn Fn(&Node) -> bool = &|n| n.children.is_empty();
+        let (rows, _) = flatten_tree(
+            tree(),
+            &|n: &Node| n.children.clone(),
+            &|n: &Node| n.name,
+            Some(is_leaf),
+            &expanded,
+        );
+        let has_children: Vec<_> = rows.iter().map(|r| (r.key, r.has_children)).collect();
+        assert_eq!(
+            has_children,
+            vec![("root", true), ("a", true), ("b", true), ("c", false)]
+        );
+    }
+}
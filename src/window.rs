@@ -30,6 +30,8 @@ pub struct WindowConfig {
     #[allow(dead_code)]
     pub(crate) mac_os_config: Option<MacOSWindowConfig>,
     pub(crate) web_config: Option<WebWindowConfig>,
+    #[cfg(feature = "serde")]
+    pub(crate) persist_key: Option<String>,
 }
 
 impl Default for WindowConfig {
@@ -51,6 +53,8 @@ impl Default for WindowConfig {
             font_embolden: if cfg!(target_os = "macos") { 0.2 } else { 0. },
             mac_os_config: None,
             web_config: None,
+            #[cfg(feature = "serde")]
+            persist_key: None,
         }
     }
 }
@@ -184,6 +188,25 @@ impl WindowConfig {
         self
     }
 
+    /// Restores the size and position of the window that were saved under the key in the
+    /// [persistence store](crate::persist), and saves them there whenever the window is resized or
+    /// moved. The size and position that were already set are used when nothing was saved yet.
+    ///
+    /// The size of a maximized or fullscreen window isn't saved, so the window is restored to
+    /// the size it had before.
+    #[cfg(feature = "serde")]
+    pub fn persisted(mut self, key: impl Into<String>) -> Self {
+        let key = key.into();
+        if let Some(geometry) = crate::persist::load_persisted::<WindowGeometry>(&key) {
+            self.size = Some(Size::new(geometry.width, geometry.height));
+            if let Some((x, y)) = geometry.position {
+                self.position = Some(Point::new(x, y));
+            }
+        }
+        self.persist_key = Some(key);
+        self
+    }
+
     /// Set up Mac-OS specific configuration.  The passed closure will only be
     /// called on macOS.
     #[allow(unused_variables, unused_mut)] // build will complain on non-macOS's otherwise
@@ -218,6 +241,17 @@ impl WindowConfig {
     }
 }
 
+/// The size and position of a window, in logical pixels, as they're saved by
+/// [`WindowConfig::persisted`].
+#[cfg(feature = "serde")]
+#[derive(Debug, Clone, Copy, PartialEq, serde::Serialize, serde::Deserialize)]
+pub(crate) struct WindowGeometry {
+    pub(crate) width: f64,
+    pub(crate) height: f64,
+    /// Not every platform tells where windows are.
+    pub(crate) position: Option<(f64, f64)>,
+}
+
 /// Mac-OS specific window configuration properties, accessible via `WindowConfig::with_mac_os_config( FnMut( MacOsWindowConfig ) )`
 ///
 /// See [the winit docs](https://docs.rs/winit/latest/winit/platform/macos/trait.WindowExtMacOS.html) for further
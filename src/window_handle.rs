@@ -10,7 +10,7 @@ use floem_renderer::gpu_resources::GpuResources;
 use floem_renderer::Renderer;
 use floem_winit::{
     dpi::{LogicalPosition, LogicalSize},
-    event::{ElementState, Ime, MouseButton, MouseScrollDelta},
+    event::{ElementState, Ime, MouseButton, MouseScrollDelta, Touch, TouchPhase},
     event_loop::EventLoopProxy,
     keyboard::{Key, ModifiersState, NamedKey},
     window::{CursorIcon, WindowId},
@@ -24,19 +24,24 @@ use crate::unit::UnitExt;
 #[cfg(any(target_os = "linux", target_os = "freebsd"))]
 use crate::views::{container, stack};
 use crate::{
+    action::wake_at,
+    animate::run_animation_frame_callbacks,
     app::UserEvent,
     app_state::AppState,
+    clock,
+    command::{dispatch_command_key, has_pending_keys},
     context::{
         ComputeLayoutCx, EventCx, FrameUpdate, LayoutCx, PaintCx, PaintState, StyleCx, UpdateCx,
     },
     dropped_file::DroppedFileEvent,
     event::{Event, EventListener},
+    gesture::{GestureInput, GestureRecognizer},
     id::ViewId,
     inspector::{self, Capture, CaptureState, CapturedView},
     keyboard::{KeyEvent, Modifiers},
     menu::Menu,
     nav::view_arrow_navigation,
-    pointer::{PointerButton, PointerInputEvent, PointerMoveEvent, PointerWheelEvent},
+    pointer::{PointerButton, PointerId, PointerInputEvent, PointerMoveEvent, PointerWheelEvent},
     profiler::Profile,
     style::{CursorStyle, Style, StyleSelector},
     theme::{default_theme, Theme},
@@ -46,7 +51,11 @@ use crate::{
     },
     view::{default_compute_layout, view_tab_navigation, IntoView, View},
     view_state::ChangeFlags,
-    views::Decorators,
+    views::{
+        dialog::{escape_dialog, top_dialog},
+        popover::{escape_popover, light_dismiss_popovers},
+        Decorators,
+    },
     window_tracking::{remove_window_id_mapping, store_window_id_mapping},
 };
 
@@ -59,7 +68,7 @@ use crate::{
 pub(crate) struct WindowHandle {
     pub(crate) window: Option<Arc<floem_winit::window::Window>>,
     window_id: WindowId,
-    id: ViewId,
+    pub(crate) id: ViewId,
     main_view: ViewId,
     /// Reactive Scope for this WindowHandle
     scope: Scope,
@@ -76,9 +85,13 @@ pub(crate) struct WindowHandle {
     pub(crate) cursor_position: Point,
     pub(crate) window_position: Point,
     pub(crate) last_pointer_down: Option<(u8, Point, Instant)>,
+    gestures: GestureRecognizer,
     #[cfg(any(target_os = "linux", target_os = "freebsd"))]
     pub(crate) context_menu: RwSignal<Option<(Menu, Point)>>,
     dropper_file: Option<PathBuf>,
+    /// The key under which the size and position of the window are persisted.
+    #[cfg(feature = "serde")]
+    pub(crate) persist_key: Option<String>,
 }
 
 impl WindowHandle {
@@ -161,9 +174,12 @@ impl WindowHandle {
             modifiers: Modifiers::default(),
             cursor_position: Point::ZERO,
             window_position: Point::ZERO,
+            #[cfg(feature = "serde")]
+            persist_key: None,
             #[cfg(any(target_os = "linux", target_os = "freebsd"))]
             context_menu,
             last_pointer_down: None,
+            gestures: GestureRecognizer::new(),
             dropper_file: None,
         };
         window_handle.app_state.set_root_size(size.get_untracked());
@@ -211,6 +227,9 @@ impl WindowHandle {
         };
 
         let is_pointer_down = matches!(&event, Event::PointerDown(_));
+        if let Event::PointerDown(pointer_event) = &event {
+            light_dismiss_popovers(self.id, pointer_event.pos);
+        }
         let was_focused = if is_pointer_down {
             cx.app_state.clicking.clear();
             cx.app_state.focus.take()
@@ -220,6 +239,15 @@ impl WindowHandle {
 
         if event.needs_focus() {
             let mut processed = false;
+            // Keyboard input stays within the topmost modal dialog.
+            let focus_root = top_dialog(self.id);
+
+            if focus_root.is_none() && has_pending_keys() {
+                // The rest of a key binding with several shortcuts goes to its command.
+                if let Event::KeyDown(key_event) = &event {
+                    processed = dispatch_command_key(key_event);
+                }
+            }
 
             if !processed {
                 if let Some(id) = cx.app_state.focus {
@@ -228,7 +256,29 @@ impl WindowHandle {
                         .is_processed();
                 }
 
-                if !processed {
+                if !processed && focus_root.is_none() {
+                    if let Event::KeyDown(key_event) = &event {
+                        processed = dispatch_command_key(key_event);
+                    }
+                }
+
+                if !processed && focus_root.is_none() {
+                    // Shortcuts of the window menu work without opening the menu.
+                    if let Event::KeyDown(key_event) = &event {
+                        let action = cx
+                            .app_state
+                            .window_menu_keys
+                            .iter()
+                            .find(|(key, _)| key.matches(key_event))
+                            .and_then(|(_, id)| cx.app_state.window_menu.get(id));
+                        if let Some(action) = action {
+                            action();
+                            processed = true;
+                        }
+                    }
+                }
+
+                if !processed && focus_root.is_none() {
                     if let Some(listener) = event.listener() {
                         processed |= self
                             .main_view
@@ -237,13 +287,25 @@ impl WindowHandle {
                     }
                 }
 
+                if !processed {
+                    if let Event::KeyDown(KeyEvent { key, .. }) = &event {
+                        if key.logical_key == Key::Named(NamedKey::Escape) {
+                            processed |= escape_popover(self.id) || escape_dialog(self.id);
+                        }
+                    }
+                }
+
                 if !processed {
                     if let Event::KeyDown(KeyEvent { key, modifiers }) = &event {
                         if key.logical_key == Key::Named(NamedKey::Tab)
                             && (modifiers.is_empty() || *modifiers == Modifiers::SHIFT)
                         {
                             let backwards = modifiers.contains(Modifiers::SHIFT);
-                            view_tab_navigation(self.id, cx.app_state, backwards);
+                            view_tab_navigation(
+                                focus_root.unwrap_or(self.id),
+                                cx.app_state,
+                                backwards,
+                            );
                             // view_debug_tree(&self.view);
                         } else if let Key::Character(character) = &key.logical_key {
                             // 'I' displays some debug information
@@ -258,7 +320,11 @@ impl WindowHandle {
                                 | NamedKey::ArrowRight),
                             ) = key.logical_key
                             {
-                                view_arrow_navigation(name, cx.app_state, self.id);
+                                view_arrow_navigation(
+                                    name,
+                                    cx.app_state,
+                                    focus_root.unwrap_or(self.id),
+                                );
                             }
                         }
                     }
@@ -398,6 +464,8 @@ impl WindowHandle {
                 self.event(Event::WindowMaximizeChanged(is_maximized));
             }
         }
+        #[cfg(feature = "serde")]
+        self.save_geometry();
 
         self.style();
         self.layout();
@@ -408,6 +476,34 @@ impl WindowHandle {
     pub(crate) fn position(&mut self, point: Point) {
         self.window_position = point;
         self.event(Event::WindowMoved(point));
+        #[cfg(feature = "serde")]
+        self.save_geometry();
+    }
+
+    /// Saves the size and position of the window if its configuration is
+    /// [persisted](crate::window::WindowConfig::persisted).
+    #[cfg(feature = "serde")]
+    fn save_geometry(&self) {
+        let (Some(key), Some(window)) = (self.persist_key.as_ref(), self.window.as_ref()) else {
+            return;
+        };
+        if self.is_maximized || window.fullscreen().is_some() {
+            return;
+        }
+        let size = self.size.get_untracked();
+        let position = window
+            .outer_position()
+            .ok()
+            .map(|position| position.to_logical::<f64>(self.scale))
+            .map(|position| (position.x, position.y));
+        crate::persist::save_persisted(
+            key,
+            &crate::window::WindowGeometry {
+                width: size.width,
+                height: size.height,
+                position,
+            },
+        );
     }
 
     pub(crate) fn key_event(&mut self, key_event: floem_winit::event::KeyEvent) {
@@ -442,6 +538,7 @@ impl WindowHandle {
             let event = PointerMoveEvent {
                 pos,
                 modifiers: self.modifiers,
+                pointer_id: PointerId::Mouse,
             };
             self.event(Event::PointerMove(event));
         }
@@ -516,6 +613,7 @@ impl WindowHandle {
             button,
             modifiers: self.modifiers,
             count,
+            pointer_id: PointerId::Mouse,
         };
         match state {
             ElementState::Pressed => {
@@ -527,6 +625,64 @@ impl WindowHandle {
         }
     }
 
+    pub(crate) fn touch(&mut self, touch: Touch) {
+        let position: LogicalPosition<f64> = touch.location.to_logical(self.scale);
+        let pos = Point::new(position.x, position.y);
+        let pointer_id = PointerId::Touch(touch.id);
+        let input_event = PointerInputEvent {
+            pos,
+            button: PointerButton::Primary,
+            modifiers: self.modifiers,
+            count: 1,
+            pointer_id,
+        };
+        let input = match touch.phase {
+            TouchPhase::Started => {
+                self.event(Event::PointerDown(input_event));
+                GestureInput::Down { id: touch.id, pos }
+            }
+            TouchPhase::Moved => {
+                self.event(Event::PointerMove(PointerMoveEvent {
+                    pos,
+                    modifiers: self.modifiers,
+                    pointer_id,
+                }));
+                GestureInput::Move { id: touch.id, pos }
+            }
+            TouchPhase::Ended => {
+                self.event(Event::PointerUp(input_event));
+                GestureInput::Up { id: touch.id, pos }
+            }
+            TouchPhase::Cancelled => GestureInput::Cancel { id: touch.id },
+        };
+        self.gesture_input(input);
+
+        if matches!(touch.phase, TouchPhase::Ended | TouchPhase::Cancelled) {
+            // A finger doesn't hover once it's lifted.
+            self.pointer_leave();
+        }
+    }
+
+    pub(crate) fn gesture_input(&mut self, input: GestureInput) {
+        let is_down = matches!(input, GestureInput::Down { .. });
+        for event in self.gestures.input(&input, Instant::now()) {
+            self.event(event);
+        }
+        if is_down {
+            if let Some(deadline) = self.gestures.next_deadline() {
+                // Wake up the event loop when a long press is due, so that `poll_gestures` runs.
+                wake_at(deadline);
+            }
+        }
+    }
+
+    /// Deliver the gestures that are recognized by time passing, such as a long press.
+    pub(crate) fn poll_gestures(&mut self) {
+        for event in self.gestures.poll(Instant::now()) {
+            self.event(event);
+        }
+    }
+
     pub(crate) fn focused(&mut self, focused: bool) {
         if focused {
             self.event(Event::WindowGotFocus);
@@ -569,6 +725,8 @@ impl WindowHandle {
     }
 
     pub(crate) fn render_frame(&mut self) {
+        run_animation_frame_callbacks();
+
         // Processes updates scheduled on this frame.
         for update in mem::take(&mut self.app_state.scheduled_updates) {
             match update {
@@ -581,8 +739,9 @@ impl WindowHandle {
         self.process_update_no_paint();
         self.paint();
 
-        // Request a new frame if there's any scheduled updates.
-        if !self.app_state.scheduled_updates.is_empty() {
+        // Request a new frame if there's any scheduled updates. While the animation clock is
+        // paused they wait for it to change, which requests a frame.
+        if !self.app_state.scheduled_updates.is_empty() && !clock::is_paused() {
             self.schedule_repaint();
         }
     }
@@ -869,6 +1028,14 @@ impl WindowHandle {
                         }
                         id.request_style_recursive();
                     }
+                    UpdateMessage::Invalid { id, is_invalid } => {
+                        if is_invalid {
+                            cx.app_state.invalid.insert(id);
+                        } else {
+                            cx.app_state.invalid.remove(&id);
+                        }
+                        id.request_style_recursive();
+                    }
                     UpdateMessage::State { id, state } => {
                         let view = id.view();
                         view.borrow_mut().update(&mut cx, state);
@@ -1054,23 +1221,10 @@ impl WindowHandle {
         })
     }
 
-    fn update_window_menu(&mut self, _menu: Menu) {
-        // if let Some(action) = menu.item.action.take() {
-        //     self.window_menu.insert(menu.item.id as u32, action);
-        // }
-        // for child in menu.children {
-        //     match child {
-        //         crate::menu::MenuEntry::Separator => {}
-        //         crate::menu::MenuEntry::Item(mut item) => {
-        //             if let Some(action) = item.action.take() {
-        //                 self.window_menu.insert(item.id as u32, action);
-        //             }
-        //         }
-        //         crate::menu::MenuEntry::SubMenu(m) => {
-        //             self.update_window_menu(m);
-        //         }
-        //     }
-        // }
+    fn update_window_menu(&mut self, mut menu: Menu) {
+        self.app_state.window_menu.clear();
+        self.app_state.window_menu_keys.clear();
+        self.app_state.update_window_menu(&mut menu);
     }
 
     fn set_cursor(&mut self) {
@@ -1100,7 +1254,7 @@ impl WindowHandle {
         }
     }
 
-    fn schedule_repaint(&self) {
+    pub(crate) fn schedule_repaint(&self) {
         if let Some(window) = self.window.as_ref() {
             window.request_redraw();
         }
@@ -1221,9 +1375,15 @@ fn context_menu_view(
 
     use crate::{
         app::{add_app_update_event, AppUpdateEvent},
-        views::{dyn_stack, empty, svg, text},
+        menu::MenuCheck,
+        views::{
+            dyn_stack, empty, h_stack, popover::compute_popover_position, svg, text, Placement,
+        },
     };
 
+    const CHECKBOX_SVG: &str = r#"<svg width="16" height="16" viewBox="0 0 16 16" xmlns="http://www.w3.org/2000/svg" fill="currentColor"><path fill-rule="evenodd" clip-rule="evenodd" d="M14.431 3.323l-8.47 10-.79-.036-3.35-4.77.818-.574 2.978 4.24 8.051-9.506.764.646z"/></svg>"#;
+    const RADIO_SVG: &str = r#"<svg width="16" height="16" viewBox="0 0 16 16" xmlns="http://www.w3.org/2000/svg" fill="currentColor"><circle cx="8" cy="8" r="4"/></svg>"#;
+
     #[derive(Clone, PartialEq, Eq, Hash)]
     enum MenuDisplay {
         Separator(usize),
@@ -1231,6 +1391,9 @@ fn context_menu_view(
             id: Option<u64>,
             enabled: bool,
             title: String,
+            key: Option<String>,
+            check: Option<MenuCheck>,
+            icon: Option<String>,
             children: Option<Vec<MenuDisplay>>,
         },
     }
@@ -1245,12 +1408,18 @@ fn context_menu_view(
                     id: Some(i.id),
                     enabled: i.enabled,
                     title: i.title.clone(),
+                    key: i.key.as_ref().map(|key| key.to_string()),
+                    check: i.check,
+                    icon: i.icon.clone(),
                     children: None,
                 },
                 crate::menu::MenuEntry::SubMenu(m) => MenuDisplay::Item {
                     id: None,
                     enabled: m.item.enabled,
                     title: m.item.title.clone(),
+                    key: None,
+                    check: None,
+                    icon: m.item.icon.clone(),
                     children: Some(format_menu(m)),
                 },
             })
@@ -1278,6 +1447,9 @@ fn context_menu_view(
                 id,
                 enabled,
                 title,
+                key,
+                check,
+                icon,
                 children,
             } => {
                 let menu_width = create_rw_signal(0.0);
@@ -1286,17 +1458,47 @@ fn context_menu_view(
                 let on_child_submenu = create_rw_signal(false);
                 let has_submenu = children.is_some();
                 let submenu_svg = r#"<svg width="16" height="16" viewBox="0 0 16 16" xmlns="http://www.w3.org/2000/svg" fill="currentColor"><path fill-rule="evenodd" clip-rule="evenodd" d="M10.072 8.024L5.715 3.667l.618-.62L11 7.716v.618L6.333 13l-.618-.619 4.357-4.357z"/></svg>"#;
+                let check_svg = match check {
+                    Some(MenuCheck::Checkbox(true)) => CHECKBOX_SVG,
+                    Some(MenuCheck::Radio(true)) => RADIO_SVG,
+                    _ => "",
+                };
+                let has_icon = icon.is_some();
+                let has_key = key.is_some();
                 container(
                     stack((
                         stack((
-                            text(title),
-                            svg(submenu_svg).style(move |s| {
-                                s.size(20.0, 20.0)
-                                    .color(Color::rgb8(201, 201, 201))
-                                    .margin_right(10.0)
-                                    .margin_left(20.0)
-                                    .apply_if(!has_submenu, |s| s.hide())
-                            }),
+                            h_stack((
+                                // The check column is always there so the titles line up.
+                                svg(check_svg).style(move |s| {
+                                    s.size(16.0, 16.0)
+                                        .margin_right(6.0)
+                                        .color(Color::rgb8(201, 201, 201))
+                                }),
+                                svg(icon.unwrap_or_default()).style(move |s| {
+                                    s.size(16.0, 16.0)
+                                        .margin_right(6.0)
+                                        .color(Color::rgb8(201, 201, 201))
+                                        .apply_if(!has_icon, |s| s.hide())
+                                }),
+                                text(title),
+                            ))
+                            .style(|s| s.items_center()),
+                            h_stack((
+                                text(key.unwrap_or_default()).style(move |s| {
+                                    s.margin_left(30.0)
+                                        .color(Color::rgb8(140, 140, 140))
+                                        .apply_if(!has_key, |s| s.hide())
+                                }),
+                                svg(submenu_svg).style(move |s| {
+                                    s.size(20.0, 20.0)
+                                        .color(Color::rgb8(201, 201, 201))
+                                        .margin_right(10.0)
+                                        .margin_left(20.0)
+                                        .apply_if(!has_submenu, |s| s.hide())
+                                }),
+                            ))
+                            .style(|s| s.items_center()),
                         ))
                         .on_event_stop(EventListener::PointerEnter, move |_| {
                             if has_submenu {
@@ -1475,13 +1677,15 @@ fn context_menu_view(
         let window_size = window_size.get();
         let menu_size = context_menu_size.get();
         let is_active = context_menu.with(|m| m.is_some());
-        let mut pos = context_menu.with(|m| m.as_ref().map(|(_, pos)| *pos).unwrap_or_default());
-        if pos.x + menu_size.width > window_size.width {
-            pos.x = window_size.width - menu_size.width;
-        }
-        if pos.y + menu_size.height > window_size.height {
-            pos.y = window_size.height - menu_size.height;
-        }
+        let pos = context_menu.with(|m| m.as_ref().map(|(_, pos)| *pos).unwrap_or_default());
+        let pos = compute_popover_position(
+            Rect::from_origin_size(pos, Size::ZERO),
+            menu_size,
+            window_size.to_rect(),
+            Placement::BOTTOM_START,
+            0.0,
+        )
+        .origin;
         s.absolute()
             .min_width(200.0)
             .flex_col()